@@ -0,0 +1,66 @@
+use scrypto::prelude::*;
+
+// Merkle commitments to allowlists too large to store on-ledger. The sale only keeps the root,
+// buyers present the sibling hashes on the path from their account's leaf up to it. Pairs are
+// hashed in sorted order, so a proof does not need to say which side each sibling is on, and the
+// last node of an odd level moves up unpaired.
+//
+// Deployment tooling builds the root and every member's proof with these same functions:
+//
+//     let root = allowlist_root(&members);
+//     let proof = allowlist_proof(&members, buyer).unwrap();
+
+pub fn allowlist_leaf(account: ComponentAddress) -> Hash {
+    hash(account.as_node_id().as_bytes())
+}
+
+fn hash_pair(a: Hash, b: Hash) -> Hash {
+    let (low, high) = if a.0 <= b.0 { (a, b) } else { (b, a) };
+    hash([low.0, high.0].concat())
+}
+
+fn next_level(level: &[Hash]) -> Vec<Hash> {
+    level
+        .chunks(2)
+        .map(|pair| match pair {
+            [a, b] => hash_pair(*a, *b),
+            [a] => *a,
+            _ => unreachable!(),
+        })
+        .collect()
+}
+
+pub fn allowlist_root(members: &[ComponentAddress]) -> Hash {
+    let mut level: Vec<Hash> = members.iter().copied().map(allowlist_leaf).collect();
+    if level.is_empty() {
+        return Hash([0; 32]);
+    }
+    while level.len() > 1 {
+        level = next_level(&level);
+    }
+    level[0]
+}
+
+// The proof of `member`, None if it is not on the list
+pub fn allowlist_proof(
+    members: &[ComponentAddress],
+    member: ComponentAddress,
+) -> Option<Vec<Hash>> {
+    let mut index = members.iter().position(|address| *address == member)?;
+    let mut level: Vec<Hash> = members.iter().copied().map(allowlist_leaf).collect();
+    let mut proof = Vec::new();
+    while level.len() > 1 {
+        if let Some(sibling) = level.get(index ^ 1) {
+            proof.push(*sibling);
+        }
+        level = next_level(&level);
+        index /= 2;
+    }
+    Some(proof)
+}
+
+pub fn verify_allowlist_proof(root: Hash, member: ComponentAddress, proof: &[Hash]) -> bool {
+    proof.iter().fold(allowlist_leaf(member), |node, sibling| {
+        hash_pair(node, *sibling)
+    }) == root
+}
@@ -0,0 +1,13 @@
+use scrypto::prelude::*;
+
+// Buyers of a sale running a price experiment are split into cohorts by a hash of their
+// account, so the same account always lands in the same cohort. Frontends quote the price an
+// account will pay with the same function:
+//
+//     let cohort = &cohorts[price_cohort(account, cohorts.len())];
+
+pub fn price_cohort(account: ComponentAddress, cohorts: usize) -> usize {
+    let digest = hash(account.as_node_id().as_bytes());
+    let value = u64::from_le_bytes(digest.0[..8].try_into().unwrap());
+    (value % cohorts as u64) as usize
+}
@@ -0,0 +1,133 @@
+use std::fmt;
+
+// Every way a sale call can fail. Panic messages of the blueprint start with the variant's code,
+// e.g. `NFTSALE_ERR_008 [Buy]: Invalid token provided...`, so frontends can map failures to
+// their own messages with `SaleError::from_message`. Codes are never reused or renumbered, new
+// failures get new variants at the end
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum SaleError {
+    // An instantiation option or owner setting is out of range
+    InvalidConfiguration = 1,
+    // A price is negative or finer than the payment token allows
+    InvalidPrice = 2,
+    // The sale has not started or has been ended
+    SaleNotOpen = 3,
+    // The action is only possible before the sale opens
+    SaleAlreadyOpen = 4,
+    // The sale has been cancelled
+    SaleCancelled = 5,
+    // The sale is free, which only VIP badge holders and mint pass holders can use
+    FreeSaleGated = 6,
+    // More NFTs were requested than one transaction may buy
+    TransactionLimitExceeded = 7,
+    // Payment or deposit in a token the sale does not accept
+    WrongPaymentToken = 8,
+    // Payment or deposit below what the action costs
+    InsufficientPayment = 9,
+    // Overpayment under `ChangePolicy::Reject`
+    ExactPaymentRequired = 10,
+    // The remaining NFTs are held back for mint pass holders
+    ReservedForPasses = 11,
+    // The NFT is not available through this method
+    NotForSale = 12,
+    // A bucket or proof of a resource the method does not take
+    WrongResource = 13,
+    // The feature is not enabled on this sale
+    NotOffered = 14,
+    // The action is only possible within a window of epochs that is not open
+    OutsideWindow = 15,
+    // The action has already been taken and cannot be repeated
+    AlreadyDone = 16,
+    // No recall, hold, purchase or deposit matches the given id
+    NotFound = 17,
+    // The sale holds too little to pay out
+    InsufficientFunds = 18,
+    // The vault holds too few NFTs
+    InsufficientInventory = 19,
+    // A price change beyond the configured limit
+    PriceChangeTooLarge = 20,
+    // A deposit breaking the owner's inventory rules
+    InventoryRuleViolation = 21,
+    // A quantity that is zero or not a valid multiple
+    InvalidQuantity = 22,
+    // A sale's price is above the limit a buyer set
+    PriceAboveLimit = 23,
+    // The buyer is not on the sale's allowlist
+    NotAllowlisted = 24,
+    // Purchases are limited per account, which an anonymous purchase does not name
+    AccountRequired = 25,
+    // The account bought too recently under the purchase cooldown
+    CooldownActive = 26,
+    // A purchase tag longer than the sale records
+    InvalidTag = 27,
+    // The sale has been finalized and takes no more calls that change it
+    SaleFinalized = 28,
+    // The sale still holds NFTs, funds or outstanding claims
+    NotSettled = 29,
+    // An NFT outside the id ranges of the presented listing manager badge
+    NotManaged = 30,
+    // Purchases are paused by the sale's guardian
+    BuyingPaused = 31,
+    // An account the sale pays out to, e.g. the platform fee recipient, refuses the deposit
+    DepositRefused = 32,
+}
+
+const CODE_PREFIX: &str = "NFTSALE_ERR_";
+
+impl SaleError {
+    const ALL: [SaleError; 32] = [
+        SaleError::InvalidConfiguration,
+        SaleError::InvalidPrice,
+        SaleError::SaleNotOpen,
+        SaleError::SaleAlreadyOpen,
+        SaleError::SaleCancelled,
+        SaleError::FreeSaleGated,
+        SaleError::TransactionLimitExceeded,
+        SaleError::WrongPaymentToken,
+        SaleError::InsufficientPayment,
+        SaleError::ExactPaymentRequired,
+        SaleError::ReservedForPasses,
+        SaleError::NotForSale,
+        SaleError::WrongResource,
+        SaleError::NotOffered,
+        SaleError::OutsideWindow,
+        SaleError::AlreadyDone,
+        SaleError::NotFound,
+        SaleError::InsufficientFunds,
+        SaleError::InsufficientInventory,
+        SaleError::PriceChangeTooLarge,
+        SaleError::InventoryRuleViolation,
+        SaleError::InvalidQuantity,
+        SaleError::PriceAboveLimit,
+        SaleError::NotAllowlisted,
+        SaleError::AccountRequired,
+        SaleError::CooldownActive,
+        SaleError::InvalidTag,
+        SaleError::SaleFinalized,
+        SaleError::NotSettled,
+        SaleError::NotManaged,
+        SaleError::BuyingPaused,
+        SaleError::DepositRefused,
+    ];
+
+    pub fn code(self) -> u16 {
+        self as u16
+    }
+
+    pub fn from_code(code: u16) -> Option<Self> {
+        Self::ALL.into_iter().find(|error| error.code() == code)
+    }
+
+    // Find the error in a panic message, e.g. the error message of a failed transaction receipt
+    pub fn from_message(message: &str) -> Option<Self> {
+        let start = message.find(CODE_PREFIX)? + CODE_PREFIX.len();
+        let digits = message.get(start..start + 3)?;
+        Self::from_code(digits.parse().ok()?)
+    }
+}
+
+impl fmt::Display for SaleError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}{:03}", CODE_PREFIX, self.code())
+    }
+}
@@ -0,0 +1,77 @@
+use scrypto::prelude::*;
+
+use crate::SaleConfig;
+
+// Fee locks for the main user flows of a sale, so frontends can set one up front instead of
+// guessing or previewing every transaction. A flow is modelled as a fixed cost plus a cost per
+// NFT moved, which covers the vault work and the larger events of bigger purchases, plus a cost
+// per extra event the sale's configuration adds, e.g. a `LargePurchaseEvent`. The figures are
+// generous estimates for Scrypto 1.2 rather than measurements; `tests/costs.rs` asserts that
+// they cover the receipts of the flows it runs. Unused fee is refunded, so they err high.
+//
+//     let lock = estimate_fee_lock(&config, SaleFlow::Buy, 5, 0);
+//     builder.lock_fee(account, lock)
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SaleFlow {
+    // `buy` and its variants taking NFTs from the front of the vault
+    Buy,
+    // `buy_specific` picking NFTs by id
+    BuySpecific,
+    // `escrow` of a payment for later settlement
+    Escrow,
+    // `claim_refund` returning NFTs for the buyer's money
+    ClaimRefund,
+    // `redeem_passes` exchanging mint passes for NFTs
+    RedeemPasses,
+}
+
+impl SaleFlow {
+    // (fixed, per NFT) execution cost units
+    fn cost_units(self) -> (u32, u32) {
+        match self {
+            SaleFlow::Buy => (25_000_000, 1_000_000),
+            SaleFlow::BuySpecific => (25_000_000, 1_500_000),
+            SaleFlow::Escrow => (20_000_000, 500_000),
+            SaleFlow::ClaimRefund => (20_000_000, 1_000_000),
+            SaleFlow::RedeemPasses => (25_000_000, 1_000_000),
+        }
+    }
+
+    fn charges_buy_royalty(self) -> bool {
+        matches!(self, SaleFlow::Buy | SaleFlow::BuySpecific)
+    }
+}
+
+// Execution cost units per event beyond the ones every flow emits
+pub const EVENT_COST_UNITS: u32 = 200_000;
+
+// XRD on top of execution for finalization and state storage, which barely vary between flows
+pub const FINALIZATION_AND_STORAGE_FEE: Decimal = dec!("0.5");
+
+// Execution cost units of `flow` moving `number_of_nfts` and emitting `extra_events`, capped at
+// the network's limit per transaction
+pub fn estimate_execution_cost(flow: SaleFlow, number_of_nfts: u16, extra_events: u16) -> u32 {
+    let (fixed, per_nft) = flow.cost_units();
+    fixed
+        .saturating_add(per_nft.saturating_mul(number_of_nfts.into()))
+        .saturating_add(EVENT_COST_UNITS.saturating_mul(extra_events.into()))
+        .min(EXECUTION_COST_UNIT_LIMIT)
+}
+
+// XRD to lock for `flow` on a sale with `config`. Royalties set in USD depend on the network's
+// exchange rate and are left to the caller to add
+pub fn estimate_fee_lock(
+    config: &SaleConfig,
+    flow: SaleFlow,
+    number_of_nfts: u16,
+    extra_events: u16,
+) -> Decimal {
+    let execution = Decimal::from(estimate_execution_cost(flow, number_of_nfts, extra_events))
+        * Decimal::try_from(EXECUTION_COST_UNIT_PRICE_IN_XRD).unwrap();
+    let royalty = match config.buy_royalty {
+        RoyaltyAmount::Xrd(amount) if flow.charges_buy_royalty() => amount,
+        _ => Decimal::zero(),
+    };
+    execution + FINALIZATION_AND_STORAGE_FEE + royalty
+}
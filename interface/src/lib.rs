@@ -0,0 +1,934 @@
+// Typed stubs and argument types for calling a deployed `NFTSale` component from other
+// Scrypto packages.
+//
+// Expand `nft_sale_stub!` with the address of the published sale package, then turn any sale
+// component address into a `Global<NFTSale>` and call its methods with compile-time checked
+// arguments and return types:
+//
+//     nft_sale_interface::nft_sale_stub!(PackageAddress::new_or_panic([/* package bytes */]));
+//
+//     let mut sale: Global<NFTSale> = sale_component_address.into();
+//     let (change, nfts, rewards) = sale.buy(payment, 2);
+//
+// Method calls work from anywhere. Calling `instantiate_nft_sale` through `NFTSaleFunctions`
+// additionally requires the sale package to be a declared dependency of the calling blueprint,
+// which only the `extern_blueprint!` form inside a `#[blueprint]` module registers.
+
+use scrypto::prelude::*;
+
+mod allowlist;
+mod cohorts;
+mod errors;
+mod fees;
+
+pub use allowlist::{allowlist_leaf, allowlist_proof, allowlist_root, verify_allowlist_proof};
+pub use cohorts::price_cohort;
+pub use errors::SaleError;
+pub use fees::{
+    estimate_execution_cost, estimate_fee_lock, SaleFlow, EVENT_COST_UNITS,
+    FINALIZATION_AND_STORAGE_FEE,
+};
+
+// Everything needed to instantiate a sale. New options are added here with defaults rather than
+// as extra positional arguments, so existing deployment tooling keeps working.
+#[derive(ScryptoSbor, ManifestSbor, Clone, Debug, PartialEq, Eq)]
+pub struct SaleConfig {
+    // The NFT collection being sold
+    pub nft_resource_address: ResourceAddress,
+    // The fungible token to accept as payment
+    pub accepted_payment_token: ResourceAddress,
+    // Price per NFT in `accepted_payment_token`
+    pub price: Decimal,
+    // Maximum number of NFTs a single `buy` may take
+    pub max_nfts_per_transaction: u16,
+    // Component royalty charged on every `buy` call. Public methods stay updatable by the owner,
+    // owner and admin methods are always free
+    pub buy_royalty: RoyaltyAmount,
+    // Cut of every sale routed to the platform that deployed the sale, if any
+    pub platform_fee: Option<PlatformFee>,
+    // Who owns the component, the returned owner badge unless a rule is supplied
+    pub owner: SaleOwner,
+    // What purchases do with payment beyond the cost
+    pub change_policy: ChangePolicy,
+    // Highest rate, between 0 and 1, any fee of the sale can ever be set to: the platform fee
+    // and the hold expiry fee. Fixed for the lifetime of the sale
+    pub max_fee_rate: Decimal,
+    // Largest fraction, between 0 and 1, a price cohort's price may differ from the sale price
+    // by, and largest extra bonus a cohort may earn per NFT. Fixed for the lifetime of the sale
+    pub max_cohort_adjustment: Decimal,
+    pub max_cohort_bonus: Decimal,
+    // Badge gated phases ahead of the public sale, e.g. a presale for holders of a partner
+    // collection. None by default, the owner can still change them later
+    pub gated_phases: Vec<GatedPhase>,
+}
+
+// How a purchase handles overpayment. Change in gift vouchers is always returned
+#[derive(ScryptoSbor, ManifestSbor, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum ChangePolicy {
+    // Return the change to the buyer
+    #[default]
+    Return,
+    // Fail unless the payment matches the cost exactly
+    Reject,
+    // Keep the change as a tip the owner withdraws with `withdraw_tips`
+    Tip,
+}
+
+// What happens to NFTs a sale gives away (gifts, donations, airdrops) when the recipient's
+// account refuses them
+#[derive(ScryptoSbor, ManifestSbor, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum DeliveryPolicy {
+    // Keep them in the sale's account locker for the recipient to claim
+    #[default]
+    Locker,
+    // Keep them in the sale until the owner calls `retry_deliveries`
+    Retry,
+    // Put them back on sale
+    Restock,
+}
+
+// The component's owner role. With a custom rule the owner badge is still minted and returned,
+// but carries no authority over the sale
+#[derive(ScryptoSbor, ManifestSbor, Clone, Debug, Default, PartialEq, Eq)]
+pub enum SaleOwner {
+    // Fixed to the owner badge minted at instantiation
+    #[default]
+    Badge,
+    // Fixed to the given rule, e.g. a DAO's badge or a multi-resource rule
+    Fixed(AccessRule),
+    // Starts out as the given rule, which the owner can later replace, e.g. when securifying
+    Updatable(AccessRule),
+}
+
+// A launchpad's share of sale revenue, set once at instantiation and paid out on every sale
+#[derive(ScryptoSbor, ManifestSbor, Clone, Debug, PartialEq, Eq)]
+pub struct PlatformFee {
+    // Fraction of the price, between 0 and 1, taken in the payment token
+    pub rate: Decimal,
+    // Account the fee is deposited into, which must accept deposits of the payment token
+    pub recipient: ComponentAddress,
+}
+
+// Used when a deployer does not pick a per-transaction limit
+pub const DEFAULT_MAX_NFTS_PER_TRANSACTION: u16 = 10;
+
+// Assembles a `SaleConfig` from only the options a deployer cares about, applying the same
+// checks as the blueprint before anything is submitted. Works both off-ledger in deployment
+// tooling and on-ledger in launchpads passing the result to `instantiate_nft_sale`, or calling
+// `instantiate` of the `InstantiateNFTSale` trait the stub declares:
+//
+//     let config = NFTSaleBuilder::new(collection, XRD).price(10).max_per_tx(5).build()?;
+//     let (sale, owner_badge, admin_badge, pricer_badge) =
+//         NFTSaleBuilder::new(collection, XRD).price(10).presale(phases).instantiate()?;
+#[derive(Clone, Debug)]
+pub struct NFTSaleBuilder {
+    nft_resource_address: ResourceAddress,
+    accepted_payment_token: ResourceAddress,
+    price: Option<Decimal>,
+    max_nfts_per_transaction: u16,
+    buy_royalty: RoyaltyAmount,
+    platform_fee: Option<PlatformFee>,
+    owner: SaleOwner,
+    change_policy: ChangePolicy,
+    max_fee_rate: Decimal,
+    max_cohort_adjustment: Decimal,
+    max_cohort_bonus: Decimal,
+    gated_phases: Vec<GatedPhase>,
+}
+
+impl NFTSaleBuilder {
+    pub fn new(
+        nft_resource_address: ResourceAddress,
+        accepted_payment_token: ResourceAddress,
+    ) -> Self {
+        Self {
+            nft_resource_address,
+            accepted_payment_token,
+            price: None,
+            max_nfts_per_transaction: DEFAULT_MAX_NFTS_PER_TRANSACTION,
+            buy_royalty: RoyaltyAmount::Free,
+            platform_fee: None,
+            owner: SaleOwner::Badge,
+            change_policy: ChangePolicy::Return,
+            max_fee_rate: Decimal::one(),
+            max_cohort_adjustment: dec!("0.2"),
+            max_cohort_bonus: Decimal::zero(),
+            gated_phases: Vec::new(),
+        }
+    }
+
+    // Price per NFT in the payment token, the one option without a default
+    pub fn price<P: Into<Decimal>>(mut self, price: P) -> Self {
+        self.price = Some(price.into());
+        self
+    }
+
+    pub fn max_per_tx(mut self, max_nfts_per_transaction: u16) -> Self {
+        self.max_nfts_per_transaction = max_nfts_per_transaction;
+        self
+    }
+
+    pub fn buy_royalty(mut self, buy_royalty: RoyaltyAmount) -> Self {
+        self.buy_royalty = buy_royalty;
+        self
+    }
+
+    pub fn platform_fee<R: Into<Decimal>>(mut self, rate: R, recipient: ComponentAddress) -> Self {
+        self.platform_fee = Some(PlatformFee {
+            rate: rate.into(),
+            recipient,
+        });
+        self
+    }
+
+    pub fn owner(mut self, owner: SaleOwner) -> Self {
+        self.owner = owner;
+        self
+    }
+
+    pub fn change_policy(mut self, change_policy: ChangePolicy) -> Self {
+        self.change_policy = change_policy;
+        self
+    }
+
+    // Guarantee buyers that fees never exceed the given rate, by default they can go up to 1
+    pub fn max_fee_rate<R: Into<Decimal>>(mut self, max_fee_rate: R) -> Self {
+        self.max_fee_rate = max_fee_rate.into();
+        self
+    }
+
+    // Bound price experiments, by default cohort prices differ by at most 0.2 and cohorts earn no
+    // extra bonus
+    pub fn cohort_limits<A: Into<Decimal>, B: Into<Decimal>>(
+        mut self,
+        max_adjustment: A,
+        max_bonus: B,
+    ) -> Self {
+        self.max_cohort_adjustment = max_adjustment.into();
+        self.max_cohort_bonus = max_bonus.into();
+        self
+    }
+
+    // Open the sale with gated phases, given in the order they start
+    pub fn presale(mut self, gated_phases: Vec<GatedPhase>) -> Self {
+        self.gated_phases = gated_phases;
+        self
+    }
+
+    pub fn build(self) -> Result<SaleConfig, String> {
+        if self.nft_resource_address.is_fungible() {
+            return Err(format!(
+                "{} Only non fungible resources can be sold.",
+                SaleError::InvalidConfiguration
+            ));
+        }
+        if !self.accepted_payment_token.is_fungible() {
+            return Err(format!(
+                "{} Only payments of fungible resources are accepted.",
+                SaleError::InvalidConfiguration
+            ));
+        }
+        let price = self
+            .price
+            .ok_or_else(|| format!("{} A price has to be set.", SaleError::InvalidPrice))?;
+        if price < Decimal::zero() {
+            return Err(format!(
+                "{} The price cannot be less then ZERO!",
+                SaleError::InvalidPrice
+            ));
+        }
+        if self.max_nfts_per_transaction == 0 {
+            return Err(format!(
+                "{} At least one NFT must be purchasable per transaction.",
+                SaleError::InvalidConfiguration
+            ));
+        }
+        if self.buy_royalty.is_negative() {
+            return Err(format!(
+                "{} The buy royalty cannot be negative.",
+                SaleError::InvalidConfiguration
+            ));
+        }
+        if self.max_fee_rate < Decimal::zero() || self.max_fee_rate > Decimal::one() {
+            return Err(format!(
+                "{} The maximum fee rate must be between 0 and 1.",
+                SaleError::InvalidConfiguration
+            ));
+        }
+        if let Some(fee) = &self.platform_fee {
+            if fee.rate < Decimal::zero() || fee.rate > self.max_fee_rate {
+                return Err(format!(
+                    "{} The platform fee rate must be between 0 and the maximum fee rate.",
+                    SaleError::InvalidConfiguration
+                ));
+            }
+        }
+
+        if self.max_cohort_adjustment < Decimal::zero()
+            || self.max_cohort_adjustment >= Decimal::one()
+        {
+            return Err(format!(
+                "{} The maximum cohort adjustment must be at least 0 and below 1.",
+                SaleError::InvalidConfiguration
+            ));
+        }
+        if self.max_cohort_bonus < Decimal::zero() {
+            return Err(format!(
+                "{} The maximum cohort bonus cannot be negative.",
+                SaleError::InvalidConfiguration
+            ));
+        }
+
+        if !self
+            .gated_phases
+            .windows(2)
+            .all(|pair| pair[0].starts_at < pair[1].starts_at)
+        {
+            return Err(format!(
+                "{} Gated phases must be given in the order they start.",
+                SaleError::InvalidConfiguration
+            ));
+        }
+
+        Ok(SaleConfig {
+            nft_resource_address: self.nft_resource_address,
+            accepted_payment_token: self.accepted_payment_token,
+            price,
+            max_nfts_per_transaction: self.max_nfts_per_transaction,
+            buy_royalty: self.buy_royalty,
+            platform_fee: self.platform_fee,
+            owner: self.owner,
+            change_policy: self.change_policy,
+            max_fee_rate: self.max_fee_rate,
+            max_cohort_adjustment: self.max_cohort_adjustment,
+            max_cohort_bonus: self.max_cohort_bonus,
+            gated_phases: self.gated_phases,
+        })
+    }
+}
+
+// Chooses the NFTs an owner operation takes out of the sale vault
+#[derive(ScryptoSbor, ManifestSbor, Clone, Debug, PartialEq, Eq)]
+pub enum NftSelection {
+    // The next `n` NFTs in the vault
+    Count(u32),
+    // Exactly these NFTs, all of which must be in the vault
+    Ids(IndexSet<NonFungibleLocalId>),
+}
+
+// Lets holders of an older collection burn their NFTs in exchange for NFTs from the sale vault
+#[derive(ScryptoSbor, ManifestSbor, Clone, Debug, PartialEq, Eq)]
+pub struct UpgradePath {
+    // The "generation 1" collection being retired. This component must be allowed to burn it
+    pub old_collection: ResourceAddress,
+    // How many old NFTs are burned for each NFT received
+    pub burned_per_nft: u16,
+    // Epoch window in which upgrades are accepted, `closes_at` being exclusive
+    pub opens_at: Epoch,
+    pub closes_at: Epoch,
+}
+
+// Price discount for buyers who lock payment tokens in the sale
+#[derive(ScryptoSbor, ManifestSbor, Clone, Debug, PartialEq, Eq)]
+pub struct StakeDiscount {
+    // Smallest stake that earns the discount
+    pub minimum_stake: Decimal,
+    // Epochs a stake stays locked before it can be withdrawn
+    pub lock_epochs: u64,
+    // Fraction taken off the price, above 0 and at most 1
+    pub discount: Decimal,
+}
+
+// Discount for buyers returning from earlier drops, who prove they hold one of the honored
+// resources, e.g. the collection or purchase receipts of a previous sale by the same creator
+#[derive(ScryptoSbor, ManifestSbor, Clone, Debug, PartialEq, Eq)]
+pub struct LoyaltyDiscount {
+    pub honored: Vec<ResourceAddress>,
+    // Fraction taken off the price, above 0 and at most 1
+    pub discount: Decimal,
+}
+
+// One cohort of a price experiment. Buyers in it pay the price adjusted by `adjustment`, a
+// fraction within the sale's `max_cohort_adjustment` either way, and earn `bonus_per_nft`, at
+// most `max_cohort_bonus`, on top of the sale's bonus
+#[derive(ScryptoSbor, ManifestSbor, Clone, Debug, PartialEq, Eq)]
+pub struct PriceCohort {
+    pub adjustment: Decimal,
+    pub bonus_per_nft: Decimal,
+}
+
+// Checks on NFTs deposited into the sale vault. No rules apply by default
+#[derive(ScryptoSbor, ManifestSbor, Clone, Debug, Default, PartialEq, Eq)]
+pub struct InventoryRules {
+    // Most NFTs the sale may hold at once, counting delisted, scheduled and held ones
+    pub max_inventory: Option<u64>,
+    // Inclusive ranges of integer ids kept for other channels, e.g. team or auction pieces
+    pub reserved_ranges: Vec<(u64, u64)>,
+}
+
+// Tightens the per-transaction limit as the collection sells out, so the last pieces are spread
+// over more buyers. Applies while at most `up_to_remaining` NFTs are left in the sale
+#[derive(ScryptoSbor, ManifestSbor, Clone, Debug, PartialEq, Eq)]
+pub struct LimitTier {
+    pub up_to_remaining: u64,
+    pub max_nfts_per_transaction: u16,
+}
+
+// A declining price for a single listing, for price discovery on high-value pieces. The price
+// drops by `decline_per_epoch` every epoch from `start_price` at `starts_at` down to `floor_price`
+#[derive(ScryptoSbor, ManifestSbor, Clone, Debug, PartialEq, Eq)]
+pub struct PriceSchedule {
+    pub start_price: Decimal,
+    pub floor_price: Decimal,
+    pub starts_at: Epoch,
+    pub decline_per_epoch: Decimal,
+}
+
+impl PriceSchedule {
+    // The price at `epoch`, the one `buy_specific` charges then. Lets bots work out the price of
+    // any future epoch off-ledger and sign purchases for it ahead of time
+    pub fn price_at(&self, epoch: Epoch) -> Decimal {
+        let elapsed = epoch.number().saturating_sub(self.starts_at.number());
+        (self.start_price - self.decline_per_epoch * elapsed).max(self.floor_price)
+    }
+}
+
+// One named phase of a sale, lasting until the next one starts. Gated phases sell to holders of
+// their badge or the badge of an earlier phase, e.g. OG passes first, then partner passes as well,
+// and a phase without a badge is open to everyone
+#[derive(ScryptoSbor, ManifestSbor, Clone, Debug, PartialEq, Eq)]
+pub struct GatedPhase {
+    pub name: String,
+    // Any resource held across the ecosystem, presented as a proof to `buy_gated`
+    pub badge: Option<ResourceAddress>,
+    pub starts_at: Epoch,
+    // Account the phase's proceeds are deposited into as they come in, e.g. a development fund
+    // during a presale, instead of the treasury. Routed proceeds are not there to back refunds
+    pub proceeds_to: Option<ComponentAddress>,
+}
+
+// Terms for holding a specific NFT against a deposit before buying it. No holds are offered by
+// default
+#[derive(ScryptoSbor, ManifestSbor, Clone, Debug, PartialEq, Eq)]
+pub struct HoldTerms {
+    // Epochs a hold lasts before the NFT goes back on sale
+    pub duration_epochs: u64,
+    // Smallest deposit accepted, which is credited toward the price
+    pub minimum_deposit: Decimal,
+    // Fraction of the deposit kept by the sale when a hold expires unconverted
+    pub expiry_fee: Decimal,
+}
+
+// Rate limit on `change_price`, which the owner can bypass with `override_price`
+#[derive(ScryptoSbor, ManifestSbor, Clone, Debug, PartialEq, Eq)]
+pub struct PriceChangeLimit {
+    // Epochs that have to pass between two price changes
+    pub min_interval_epochs: u64,
+    // Largest change allowed at once, as a fraction of the current price
+    pub max_change: Decimal,
+}
+
+// One sale of a single NFT, as returned by `item_history`
+#[derive(ScryptoSbor, Clone, Debug, PartialEq, Eq)]
+pub struct ItemSale {
+    // Paid for this NFT, before the platform fee
+    pub price: Decimal,
+    pub epoch: Epoch,
+    // Set once the buyer returned the NFT for a refund
+    pub refunded: bool,
+}
+
+// What a component may still pull from a sale, as returned by `allowance`
+#[derive(ScryptoSbor, Clone, Debug, PartialEq, Eq)]
+pub struct PurchaseAllowance {
+    // NFTs left to pull
+    pub remaining: u64,
+    // Paid per NFT from the budget
+    pub price: Decimal,
+    // Deposited with `deposit_budget` and not yet spent
+    pub budget: Decimal,
+}
+
+// The sale's payment tokens by what they are set aside for, as returned by `treasury_breakdown`
+#[derive(ScryptoSbor, Clone, Debug, PartialEq, Eq)]
+pub struct TreasuryBreakdown {
+    // Revenue `withdraw_profits` pays out now
+    pub free: Decimal,
+    // Revenue held back for refunds while the refund window of a cancelled sale is open
+    pub locked_for_refunds: Decimal,
+    // Funds of escrows not converted or claimed back yet, kept outside the treasury
+    pub escrowed: Decimal,
+    // Deposits kept in holds, outside the treasury
+    pub held_deposits: Decimal,
+}
+
+// Sales and refunds between two calls of `close_accounting_period`, kept for reconciliation
+#[derive(ScryptoSbor, Clone, Debug, PartialEq, Eq)]
+pub struct AccountingPeriod {
+    pub opened_at: Epoch,
+    pub closed_at: Epoch,
+    // Paid for the NFTs sold in the period, before the platform fee
+    pub revenue: Decimal,
+    pub units: u64,
+    // Paid back to buyers returning their NFTs in the period
+    pub refunds: Decimal,
+    // Running totals since the sale was instantiated
+    pub total_revenue: Decimal,
+    pub total_units: u64,
+    pub total_refunds: Decimal,
+}
+
+// One owner or admin action as recorded in a sale's audit log
+#[derive(ScryptoSbor, Clone, Debug, PartialEq, Eq)]
+pub struct AuditEntry {
+    // Name of the method called
+    pub action: String,
+    pub epoch: Epoch,
+    // Human readable arguments of the call
+    pub params: String,
+    pub authority: Authority,
+}
+
+// Who authorized a call in the audit log: the role it was made under and, for methods several
+// roles may call, the badge the caller presented to tell them apart
+#[derive(ScryptoSbor, Clone, Debug, PartialEq, Eq)]
+pub struct Authority {
+    pub role: String,
+    pub badge: Option<ResourceOrNonFungible>,
+}
+
+impl Authority {
+    // A call only one role may make, whose badge stays in the caller's auth zone
+    pub fn role(role: &str) -> Self {
+        Self {
+            role: role.to_string(),
+            badge: None,
+        }
+    }
+}
+
+// One change applied by `configure`, named after the method making it on its own
+#[derive(ScryptoSbor, ManifestSbor, Clone, Debug, PartialEq, Eq)]
+pub enum AdminAction {
+    StartSale,
+    EndSale,
+    ChangePrice(Decimal),
+    SetLimitTiers(Vec<LimitTier>),
+    SetPurchaseCooldown(Option<u64>),
+    SetMinimumPayment(Decimal),
+    SetPassWindow(Epoch, Epoch),
+    SetGatedPhases(Vec<GatedPhase>),
+    SetStakeDiscount(Option<StakeDiscount>),
+    SetLoyaltyDiscount(Option<LoyaltyDiscount>),
+    SetCashbackRate(Decimal),
+    SetBonusPerNft(Decimal),
+}
+
+// One sale to buy from in a `PurchaseRouter::sweep`
+#[derive(ScryptoSbor, ManifestSbor, Clone, Debug, PartialEq, Eq)]
+pub struct PurchaseLeg {
+    pub sale: ComponentAddress,
+    pub number_of_nfts: u16,
+    // Highest price per NFT the buyer accepts, protecting against price changes before execution
+    pub max_price: Decimal,
+}
+
+// Everything but the inventory ids of a sale, as exported by `export_state_snapshot`. New
+// fields are only ever appended so indexers decoding older snapshots keep working
+#[derive(ScryptoSbor, Clone, Debug, PartialEq, Eq)]
+pub struct SaleSnapshot {
+    pub nft_resource_address: ResourceAddress,
+    pub accepted_payment_token: ResourceAddress,
+    pub price: Decimal,
+    pub max_nfts_per_transaction: u16,
+    pub limit_tiers: Vec<LimitTier>,
+    pub inventory_rules: InventoryRules,
+    pub platform_fee: Option<PlatformFee>,
+    pub stake_discount: Option<StakeDiscount>,
+    pub price_change_limit: Option<PriceChangeLimit>,
+    pub upgrade_path: Option<UpgradePath>,
+    pub bonus_per_nft: Decimal,
+    pub cashback_rate: Decimal,
+    pub pass_window: Option<(Epoch, Epoch)>,
+    pub sale_allowed: bool,
+    // Set once the sale is cancelled
+    pub refunds_close_at: Option<Epoch>,
+    pub inventory: Decimal,
+    pub delisted: Decimal,
+    pub reserved_for_passes: u64,
+    // Balance of the payment token in the treasury
+    pub treasury: Decimal,
+    // Recalls ever scheduled, escrows ever opened and audit log entries so far
+    pub recalls: u64,
+    pub escrows: u64,
+    pub audit_log_length: u64,
+    pub change_policy: ChangePolicy,
+    pub purchase_cooldown: Option<u64>,
+    pub minimum_payment: Decimal,
+    pub gated_phases: Vec<GatedPhase>,
+    pub max_fee_rate: Decimal,
+    pub max_cohort_adjustment: Decimal,
+    pub max_cohort_bonus: Decimal,
+    pub loyalty_discount: Option<LoyaltyDiscount>,
+    pub price_cohorts: Vec<PriceCohort>,
+    pub allowlist_root: Option<Hash>,
+    pub hold_terms: Option<HoldTerms>,
+    pub large_purchase_threshold: Option<Decimal>,
+    pub donation_list: Vec<(ComponentAddress, u32)>,
+    pub delivery_policy: DeliveryPolicy,
+    pub deferred_delivery: bool,
+}
+
+// Where a sale stands, as shown in countdowns
+#[derive(ScryptoSbor, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SalePhase {
+    // Not started yet, or ended by the owner
+    Closed,
+    // Mint pass holders are redeeming their allocations, the rest sells publicly
+    Priority,
+    Public,
+    // Cancelled, buyers are returning their NFTs for refunds
+    Refunding,
+}
+
+// The phase a sale is in, as returned by `current_phase_info`
+#[derive(ScryptoSbor, Clone, Debug, PartialEq, Eq)]
+pub struct PhaseInfo {
+    pub phase: SalePhase,
+    // Public price per NFT, passes redeem at the price locked in each pass
+    pub price: Decimal,
+    // NFTs left in this phase: the pass allocations during the priority window, the unreserved
+    // inventory otherwise
+    pub remaining: Decimal,
+    // Epochs until the priority window opens or closes, the next gated phase starts, or the
+    // refund window closes, whichever comes first
+    pub epochs_until_next_phase: Option<u64>,
+    // Name of the current gated phase, if the owner set any up
+    pub gated_phase: Option<String>,
+}
+
+// One page of `export_state_snapshot`: the summary at cursor 0, inventory ids after that
+#[derive(ScryptoSbor, Clone, Debug, PartialEq, Eq)]
+pub enum SnapshotChunk {
+    Summary(Box<SaleSnapshot>),
+    Inventory(Vec<NonFungibleLocalId>),
+}
+
+#[macro_export]
+macro_rules! nft_sale_stub {
+    ($package_address:expr) => {
+        ::scrypto::extern_blueprint_internal! {
+            $package_address,
+            NFTSale,
+            "NFTSale",
+            "OwnedNFTSale",
+            "GlobalNFTSale",
+            NFTSaleFunctions {
+                fn instantiate_nft_sale(
+                    config: $crate::SaleConfig
+                ) -> (Global<NFTSale>, NonFungibleBucket, NonFungibleBucket, NonFungibleBucket);
+                fn instantiate_with_nfts(
+                    nfts: NonFungibleBucket,
+                    config: $crate::SaleConfig
+                ) -> (Global<NFTSale>, NonFungibleBucket, NonFungibleBucket, NonFungibleBucket);
+                fn clone_sale(
+                    source: ComponentAddress,
+                    nft_resource_address: ResourceAddress
+                ) -> (Global<NFTSale>, NonFungibleBucket, NonFungibleBucket, NonFungibleBucket);
+            },
+            {
+                fn add_nfts_to_vault(
+                    &mut self,
+                    badge: Option<Proof>,
+                    nft_deposit_bucket: NonFungibleBucket
+                );
+                fn schedule_listing(
+                    &mut self,
+                    badge: Option<Proof>,
+                    nft_id: NonFungibleLocalId,
+                    schedule: Option<$crate::PriceSchedule>
+                );
+                fn listing_price(&self, nft_id: NonFungibleLocalId) -> Option<Decimal>;
+                fn listing_price_at(
+                    &self,
+                    nft_id: NonFungibleLocalId,
+                    epoch: Epoch
+                ) -> Option<Decimal>;
+                fn item_history(&self, nft_id: NonFungibleLocalId) -> Vec<$crate::ItemSale>;
+                fn buy_specific(
+                    &mut self,
+                    payment: Bucket,
+                    nft_id: NonFungibleLocalId
+                ) -> (Bucket, NonFungibleBucket);
+                fn grant_allowance(
+                    &mut self,
+                    component: ComponentAddress,
+                    number_of_nfts: u64,
+                    price: Decimal
+                );
+                fn deposit_budget(&mut self, component: ComponentAddress, budget: Bucket);
+                fn pull_nfts(
+                    &mut self,
+                    component: ComponentAddress,
+                    number_of_nfts: u16
+                ) -> NonFungibleBucket;
+                fn withdraw_budget(&mut self, component: ComponentAddress) -> Bucket;
+                fn allowance(&self, component: ComponentAddress) -> Option<$crate::PurchaseAllowance>;
+                fn set_inventory_rules(&mut self, inventory_rules: $crate::InventoryRules);
+                fn delist(
+                    &mut self,
+                    badge: Option<Proof>,
+                    nft_ids: IndexSet<NonFungibleLocalId>
+                );
+                fn relist(
+                    &mut self,
+                    badge: Option<Proof>,
+                    nft_ids: IndexSet<NonFungibleLocalId>
+                );
+                fn start_sale(&mut self, badge: Option<Proof>);
+                fn end_sale(&mut self, badge: Option<Proof>);
+                fn pause_buying(&mut self);
+                fn resume_buying(&mut self);
+                fn buy(
+                    &mut self,
+                    payment: Bucket,
+                    number_of_nfts: u16
+                ) -> (Bucket, NonFungibleBucket, Vec<Bucket>);
+                fn buy_tagged(
+                    &mut self,
+                    payment: Bucket,
+                    number_of_nfts: u16,
+                    tag: String
+                ) -> (Bucket, NonFungibleBucket, Vec<Bucket>);
+                fn buy_vip(
+                    &mut self,
+                    payment: Bucket,
+                    number_of_nfts: u16
+                ) -> (Bucket, NonFungibleBucket, Vec<Bucket>);
+                fn grant_vip(&mut self, name: String) -> NonFungibleBucket;
+                fn rename_admin_badge(&mut self, badge_id: NonFungibleLocalId, name: String);
+                fn buy_allowlisted(
+                    &mut self,
+                    payment: Bucket,
+                    number_of_nfts: u16,
+                    account: Global<Account>,
+                    proof: Vec<Hash>
+                ) -> (Bucket, NonFungibleBucket, Vec<Bucket>);
+                fn set_allowlist_root(&mut self, allowlist_root: Option<Hash>);
+                fn buy_gated(
+                    &mut self,
+                    payment: Bucket,
+                    number_of_nfts: u16,
+                    badge: Proof
+                ) -> (Bucket, NonFungibleBucket, Vec<Bucket>);
+                fn set_gated_phases(&mut self, gated_phases: Vec<$crate::GatedPhase>);
+                fn buy_as(
+                    &mut self,
+                    payment: Bucket,
+                    number_of_nfts: u16,
+                    account: Global<Account>
+                ) -> (Bucket, NonFungibleBucket, Vec<Bucket>);
+                fn set_purchase_cooldown(&mut self, purchase_cooldown: Option<u64>);
+                fn buy_for(
+                    &mut self,
+                    payment: Bucket,
+                    number_of_nfts: u16,
+                    sponsor: Global<Account>,
+                    recipient: Global<Account>
+                ) -> Bucket;
+                fn buy_staked(
+                    &mut self,
+                    payment: Bucket,
+                    number_of_nfts: u16,
+                    stake: NonFungibleProof
+                ) -> (Bucket, NonFungibleBucket, Vec<Bucket>);
+                fn stake(&mut self, payment: Bucket) -> NonFungibleBucket;
+                fn unstake(&mut self, receipt: NonFungibleBucket) -> Bucket;
+                fn buy_vouchers(&mut self, payment: Bucket) -> Bucket;
+                fn escrow(&mut self, payment: Bucket, number_of_nfts: u16) -> NonFungibleBucket;
+                fn convert_escrows(&mut self, badge: Option<Proof>, limit: u32);
+                fn set_hold_terms(&mut self, hold_terms: Option<$crate::HoldTerms>);
+                fn hold(&mut self, nft_id: NonFungibleLocalId, deposit: Bucket) -> NonFungibleBucket;
+                fn complete_hold(
+                    &mut self,
+                    receipt: NonFungibleBucket,
+                    payment: Bucket
+                ) -> (Bucket, NonFungibleBucket);
+                fn release_hold(&mut self, hold_id: u64);
+                fn reclaim_hold(&mut self, receipt: NonFungibleBucket) -> Bucket;
+                fn claim_escrow(&mut self, receipts: NonFungibleBucket) -> (Bucket, NonFungibleBucket);
+                fn mint_passes(&mut self, count: u32, allocation: u16, price: Decimal) -> NonFungibleBucket;
+                fn issue_backer_shares(
+                    &mut self,
+                    count: u32,
+                    revenue_share: Decimal
+                ) -> NonFungibleBucket;
+                fn claim_revenue_share(&mut self, shares: NonFungibleProof) -> Bucket;
+                fn issue_affiliate_link(
+                    &mut self,
+                    name: String,
+                    commission_rate: Decimal
+                ) -> NonFungibleBucket;
+                fn buy_via_affiliate(
+                    &mut self,
+                    payment: Bucket,
+                    number_of_nfts: u16,
+                    link: NonFungibleProof
+                ) -> (Bucket, NonFungibleBucket, Vec<Bucket>);
+                fn claim_commission(&mut self, links: NonFungibleProof) -> Bucket;
+                fn commission_owed(&self, link_id: NonFungibleLocalId) -> Decimal;
+                fn issue_automation_badge(&mut self, name: String) -> NonFungibleBucket;
+                fn issue_listing_manager(
+                    &mut self,
+                    name: String,
+                    ranges: Vec<(u64, u64)>
+                ) -> NonFungibleBucket;
+                fn set_managed_ranges(
+                    &mut self,
+                    badge_id: NonFungibleLocalId,
+                    ranges: Vec<(u64, u64)>
+                );
+                fn manage_delist(
+                    &mut self,
+                    badge: NonFungibleProof,
+                    nft_ids: IndexSet<NonFungibleLocalId>
+                );
+                fn manage_relist(
+                    &mut self,
+                    badge: NonFungibleProof,
+                    nft_ids: IndexSet<NonFungibleLocalId>
+                );
+                fn manage_listing(
+                    &mut self,
+                    badge: NonFungibleProof,
+                    nft_id: NonFungibleLocalId,
+                    schedule: Option<$crate::PriceSchedule>
+                );
+                fn set_pass_window(&mut self, opens_at: Epoch, closes_at: Epoch);
+                fn redeem_passes(
+                    &mut self,
+                    passes: NonFungibleBucket,
+                    payment: Bucket
+                ) -> (Bucket, NonFungibleBucket);
+                fn gift_from_vault(
+                    &mut self,
+                    selection: $crate::NftSelection,
+                    recipient: Global<Account>
+                );
+                fn set_donation_list(&mut self, donation_list: Vec<(ComponentAddress, u32)>);
+                fn donate_unsold(&mut self, limit: u32);
+                fn add_airdrop_recipients(&mut self, recipients: Vec<ComponentAddress>);
+                fn airdrop_batch(&mut self, badge: Option<Proof>, limit: u32) -> u64;
+                fn set_deferred_delivery(&mut self, deferred_delivery: bool);
+                fn fulfil(&mut self, badge: Option<Proof>, limit: u32) -> u64;
+                fn delivery_locker(&self) -> Option<ComponentAddress>;
+                fn set_delivery_policy(&mut self, delivery_policy: $crate::DeliveryPolicy);
+                fn retry_deliveries(&mut self, badge: Option<Proof>, limit: u32) -> u64;
+                fn schedule_recall(
+                    &mut self,
+                    vault: InternalAddress,
+                    nft_ids: IndexSet<NonFungibleLocalId>
+                ) -> u64;
+                fn cancel_recall(&mut self, recall_id: u64);
+                fn recall_item(&mut self, recall_id: u64) -> NonFungibleBucket;
+                fn redeem_physical(&mut self, nft_proof: NonFungibleProof, shipping_hash: Hash);
+                fn set_upgrade_path(&mut self, upgrade_path: Option<$crate::UpgradePath>);
+                fn burn_to_redeem(&mut self, old_nft_bucket: NonFungibleBucket) -> NonFungibleBucket;
+                fn cancel_sale(&mut self);
+                fn finalize(&mut self);
+                fn claim_refund(&mut self, nfts: NonFungibleBucket) -> Bucket;
+                fn lock_insurance(
+                    &mut self,
+                    deposit: Bucket,
+                    locked_until: Epoch,
+                    reveal_by: Option<Epoch>
+                );
+                fn withdraw_insurance(&mut self) -> Bucket;
+                fn reveal(&mut self);
+                fn slash_insurance(&mut self);
+                fn insurance(&self) -> (Decimal, Option<Epoch>, Option<Epoch>);
+                fn deposit_bonus(&mut self, bonus: Bucket);
+                fn set_bonus_per_nft(&mut self, bonus_per_nft: Decimal);
+                fn withdraw_bonus(&mut self) -> Bucket;
+                fn set_cashback_rate(&mut self, cashback_rate: Decimal);
+                fn set_minimum_payment(&mut self, minimum_payment: Decimal);
+                fn set_large_purchase_threshold(&mut self, large_purchase_threshold: Option<Decimal>);
+                fn set_stake_discount(&mut self, stake_discount: Option<$crate::StakeDiscount>);
+                fn set_price_cohorts(&mut self, price_cohorts: Vec<$crate::PriceCohort>);
+                fn price_cohorts(&self) -> Vec<$crate::PriceCohort>;
+                fn set_loyalty_discount(
+                    &mut self,
+                    loyalty_discount: Option<$crate::LoyaltyDiscount>
+                );
+                fn buy_returning(
+                    &mut self,
+                    payment: Bucket,
+                    number_of_nfts: u16,
+                    receipt: Proof
+                ) -> (Bucket, NonFungibleBucket, Vec<Bucket>);
+                fn set_limit_tiers(
+                    &mut self,
+                    badge: Option<Proof>,
+                    limit_tiers: Vec<$crate::LimitTier>
+                );
+                fn configure(&mut self, badge: Option<Proof>, batch: Vec<$crate::AdminAction>);
+                fn max_per_transaction(&self) -> u16;
+                fn current_phase_info(&self) -> $crate::PhaseInfo;
+                fn set_dapp_definition(&mut self, dapp_definition: ComponentAddress);
+                fn set_info_url(&mut self, info_url: String);
+                fn set_social_urls(&mut self, social_urls: Vec<String>);
+                fn audit_log(&self, start: u64, limit: u32) -> (u64, Vec<$crate::AuditEntry>);
+                fn close_accounting_period(
+                    &mut self,
+                    badge: Option<Proof>
+                ) -> $crate::AccountingPeriod;
+                fn accounting_periods(
+                    &self,
+                    start: u64,
+                    limit: u32
+                ) -> (u64, Vec<$crate::AccountingPeriod>);
+                fn export_buyers(&self, cursor: u64, limit: u32) -> (u64, Vec<u8>);
+                fn export_state_snapshot(
+                    &self,
+                    cursor: u64
+                ) -> ($crate::SnapshotChunk, Option<u64>);
+                fn withdraw_profits(&mut self) -> Bucket;
+                fn treasury_breakdown(&self) -> $crate::TreasuryBreakdown;
+                fn verify_invariants(&self) -> bool;
+                fn withdraw_tips(&mut self) -> Bucket;
+                fn withdraw_from_treasury(&mut self, resource: ResourceAddress) -> Bucket;
+                fn change_price(&mut self, badge: Option<Proof>, price: Decimal);
+                fn override_price(&mut self, price: Decimal);
+                fn set_price_change_limit(
+                    &mut self,
+                    price_change_limit: Option<$crate::PriceChangeLimit>
+                );
+                fn price(&self) -> (ResourceAddress, Decimal);
+                fn is_sold(&self) -> bool;
+            }
+        }
+
+        // Instantiate the sale a builder describes in one call, from a blueprint declaring the
+        // sale package as a dependency
+        pub trait InstantiateNFTSale {
+            fn instantiate(
+                self,
+            ) -> Result<
+                (Global<NFTSale>, NonFungibleBucket, NonFungibleBucket, NonFungibleBucket),
+                String,
+            >;
+        }
+
+        impl InstantiateNFTSale for $crate::NFTSaleBuilder {
+            fn instantiate(
+                self,
+            ) -> Result<
+                (Global<NFTSale>, NonFungibleBucket, NonFungibleBucket, NonFungibleBucket),
+                String,
+            > {
+                let config = self.build()?;
+                Ok(Blueprint::<NFTSale>::instantiate_nft_sale(config))
+            }
+        }
+    };
+}
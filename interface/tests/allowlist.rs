@@ -0,0 +1,34 @@
+use nft_sale_interface::{allowlist_proof, allowlist_root, verify_allowlist_proof};
+use scrypto::prelude::*;
+
+fn accounts(count: u64) -> Vec<ComponentAddress> {
+    (1..=count)
+        .map(|key| {
+            let public_key = Secp256k1PublicKey([key as u8; Secp256k1PublicKey::LENGTH]);
+            ComponentAddress::virtual_account_from_public_key(&public_key)
+        })
+        .collect()
+}
+
+#[test]
+fn test_every_member_proves_membership() {
+    for count in [1, 2, 3, 7, 8, 33] {
+        let members = accounts(count);
+        let root = allowlist_root(&members);
+        for member in &members {
+            let proof = allowlist_proof(&members, *member).unwrap();
+            assert!(verify_allowlist_proof(root, *member, &proof));
+        }
+    }
+}
+
+#[test]
+fn test_outsiders_cannot_prove_membership() {
+    let members = accounts(5);
+    let outsider = accounts(6)[5];
+    let root = allowlist_root(&members);
+
+    assert_eq!(allowlist_proof(&members, outsider), None);
+    let borrowed = allowlist_proof(&members, members[0]).unwrap();
+    assert!(!verify_allowlist_proof(root, outsider, &borrowed));
+}
@@ -0,0 +1,124 @@
+use nft_sale_interface::{
+    ChangePolicy, GatedPhase, NFTSaleBuilder, PlatformFee, SaleConfig, SaleOwner,
+    DEFAULT_MAX_NFTS_PER_TRANSACTION,
+};
+use scrypto::prelude::*;
+
+// Any well-known non-fungible resource will do as the collection
+const COLLECTION: ResourceAddress = PACKAGE_OWNER_BADGE;
+
+#[test]
+fn test_builder_applies_defaults() {
+    let config = NFTSaleBuilder::new(COLLECTION, XRD).price(10).build();
+    assert_eq!(
+        config,
+        Ok(SaleConfig {
+            nft_resource_address: COLLECTION,
+            accepted_payment_token: XRD,
+            price: dec!(10),
+            max_nfts_per_transaction: DEFAULT_MAX_NFTS_PER_TRANSACTION,
+            buy_royalty: RoyaltyAmount::Free,
+            platform_fee: None,
+            owner: SaleOwner::Badge,
+            change_policy: ChangePolicy::Return,
+            max_fee_rate: dec!(1),
+            max_cohort_adjustment: dec!("0.2"),
+            max_cohort_bonus: dec!(0),
+            gated_phases: vec![],
+        })
+    );
+}
+
+fn phase(name: &str, starts_at: u64) -> GatedPhase {
+    GatedPhase {
+        name: name.to_string(),
+        badge: Some(COLLECTION),
+        starts_at: Epoch::of(starts_at),
+        proceeds_to: None,
+    }
+}
+
+#[test]
+fn test_builder_overrides_defaults() {
+    let config = NFTSaleBuilder::new(COLLECTION, XRD)
+        .price(dec!("2.5"))
+        .max_per_tx(5)
+        .buy_royalty(RoyaltyAmount::Usd(dec!("0.1")))
+        .platform_fee(dec!("0.05"), FAUCET)
+        .owner(SaleOwner::Updatable(rule!(require(COLLECTION))))
+        .change_policy(ChangePolicy::Reject)
+        .max_fee_rate(dec!("0.1"))
+        .cohort_limits(dec!("0.5"), 3)
+        .presale(vec![phase("Presale", 10)])
+        .build()
+        .unwrap();
+    assert_eq!(config.price, dec!("2.5"));
+    assert_eq!(config.max_nfts_per_transaction, 5);
+    assert_eq!(config.buy_royalty, RoyaltyAmount::Usd(dec!("0.1")));
+    assert_eq!(
+        config.platform_fee,
+        Some(PlatformFee {
+            rate: dec!("0.05"),
+            recipient: FAUCET
+        })
+    );
+    assert_eq!(
+        config.owner,
+        SaleOwner::Updatable(rule!(require(COLLECTION)))
+    );
+    assert_eq!(config.change_policy, ChangePolicy::Reject);
+    assert_eq!(config.max_fee_rate, dec!("0.1"));
+    assert_eq!(config.max_cohort_adjustment, dec!("0.5"));
+    assert_eq!(config.max_cohort_bonus, dec!(3));
+    assert_eq!(config.gated_phases, vec![phase("Presale", 10)]);
+}
+
+#[test]
+fn test_builder_rejects_invalid_options() {
+    assert!(NFTSaleBuilder::new(COLLECTION, XRD).build().is_err());
+    assert!(NFTSaleBuilder::new(COLLECTION, XRD)
+        .price(-1)
+        .build()
+        .is_err());
+    assert!(NFTSaleBuilder::new(COLLECTION, XRD)
+        .price(1)
+        .max_per_tx(0)
+        .build()
+        .is_err());
+    assert!(NFTSaleBuilder::new(COLLECTION, XRD)
+        .price(1)
+        .buy_royalty(RoyaltyAmount::Xrd(dec!(-1)))
+        .build()
+        .is_err());
+    assert!(NFTSaleBuilder::new(COLLECTION, XRD)
+        .price(1)
+        .platform_fee(dec!("1.5"), FAUCET)
+        .build()
+        .is_err());
+    assert!(NFTSaleBuilder::new(COLLECTION, XRD)
+        .price(1)
+        .platform_fee(dec!("0.2"), FAUCET)
+        .max_fee_rate(dec!("0.1"))
+        .build()
+        .is_err());
+    assert!(NFTSaleBuilder::new(COLLECTION, XRD)
+        .price(1)
+        .cohort_limits(1, 0)
+        .build()
+        .is_err());
+    assert!(NFTSaleBuilder::new(COLLECTION, XRD)
+        .price(1)
+        .cohort_limits(dec!("0.2"), -1)
+        .build()
+        .is_err());
+    assert!(NFTSaleBuilder::new(COLLECTION, XRD)
+        .price(1)
+        .presale(vec![phase("Partners", 20), phase("OG", 10)])
+        .build()
+        .is_err());
+    assert!(NFTSaleBuilder::new(XRD, XRD).price(1).build().is_err());
+    assert!(NFTSaleBuilder::new(COLLECTION, COLLECTION)
+        .price(1)
+        .build()
+        .is_err());
+}
@@ -0,0 +1,14 @@
+use nft_sale_interface::price_cohort;
+use scrypto::prelude::*;
+
+#[test]
+fn test_price_cohorts_are_stable_and_in_range() {
+    for key in 1..=20u8 {
+        let public_key = Secp256k1PublicKey([key; Secp256k1PublicKey::LENGTH]);
+        let account = ComponentAddress::virtual_account_from_public_key(&public_key);
+        let cohort = price_cohort(account, 3);
+        assert!(cohort < 3);
+        assert_eq!(price_cohort(account, 3), cohort);
+        assert_eq!(price_cohort(account, 1), 0);
+    }
+}
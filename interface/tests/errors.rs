@@ -0,0 +1,27 @@
+use nft_sale_interface::{NFTSaleBuilder, SaleError};
+use scrypto::prelude::*;
+
+#[test]
+fn test_error_codes_round_trip() {
+    assert_eq!(SaleError::WrongPaymentToken.to_string(), "NFTSALE_ERR_008");
+    let mut code = 1;
+    while let Some(error) = SaleError::from_code(code) {
+        assert_eq!(error.code(), code);
+        let message = format!("Panic: {} [Buy]: Something went wrong.", error);
+        assert_eq!(SaleError::from_message(&message), Some(error));
+        code += 1;
+    }
+    assert_eq!(code, 33);
+    assert_eq!(SaleError::from_message("[Buy]: No code here."), None);
+}
+
+#[test]
+fn test_builder_errors_carry_codes() {
+    let error = NFTSaleBuilder::new(PACKAGE_OWNER_BADGE, XRD)
+        .build()
+        .unwrap_err();
+    assert_eq!(
+        SaleError::from_message(&error),
+        Some(SaleError::InvalidPrice)
+    );
+}
@@ -0,0 +1,49 @@
+use nft_sale_interface::{
+    estimate_execution_cost, estimate_fee_lock, NFTSaleBuilder, SaleFlow,
+    FINALIZATION_AND_STORAGE_FEE,
+};
+use scrypto::prelude::*;
+
+const COLLECTION: ResourceAddress = PACKAGE_OWNER_BADGE;
+
+#[test]
+fn test_estimates_grow_with_nfts_and_events() {
+    for flow in [
+        SaleFlow::Buy,
+        SaleFlow::BuySpecific,
+        SaleFlow::Escrow,
+        SaleFlow::ClaimRefund,
+        SaleFlow::RedeemPasses,
+    ] {
+        assert!(estimate_execution_cost(flow, 10, 0) > estimate_execution_cost(flow, 1, 0));
+        assert!(estimate_execution_cost(flow, 1, 1) > estimate_execution_cost(flow, 1, 0));
+        assert_eq!(
+            estimate_execution_cost(flow, u16::MAX, u16::MAX),
+            EXECUTION_COST_UNIT_LIMIT
+        );
+    }
+}
+
+#[test]
+fn test_fee_lock_adds_xrd_buy_royalty() {
+    let free = NFTSaleBuilder::new(COLLECTION, XRD)
+        .price(1)
+        .build()
+        .unwrap();
+    let royalty = NFTSaleBuilder::new(COLLECTION, XRD)
+        .price(1)
+        .buy_royalty(RoyaltyAmount::Xrd(dec!(2)))
+        .build()
+        .unwrap();
+
+    let lock = estimate_fee_lock(&free, SaleFlow::Buy, 1, 0);
+    assert!(lock > FINALIZATION_AND_STORAGE_FEE);
+    assert_eq!(
+        estimate_fee_lock(&royalty, SaleFlow::Buy, 1, 0),
+        lock + dec!(2)
+    );
+    assert_eq!(
+        estimate_fee_lock(&royalty, SaleFlow::Escrow, 1, 0),
+        estimate_fee_lock(&free, SaleFlow::Escrow, 1, 0)
+    );
+}
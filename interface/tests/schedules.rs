@@ -0,0 +1,17 @@
+use nft_sale_interface::PriceSchedule;
+use scrypto::prelude::*;
+
+#[test]
+fn test_schedule_prices_decline_to_the_floor() {
+    let schedule = PriceSchedule {
+        start_price: dec!(100),
+        floor_price: dec!(30),
+        starts_at: Epoch::of(10),
+        decline_per_epoch: dec!(20),
+    };
+    assert_eq!(schedule.price_at(Epoch::of(5)), dec!(100));
+    assert_eq!(schedule.price_at(Epoch::of(10)), dec!(100));
+    assert_eq!(schedule.price_at(Epoch::of(12)), dec!(60));
+    assert_eq!(schedule.price_at(Epoch::of(14)), dec!(30));
+    assert_eq!(schedule.price_at(Epoch::of(1000)), dec!(30));
+}
@@ -0,0 +1,35 @@
+use nft_sale_interface::NFTSaleBuilder;
+use scrypto::prelude::*;
+
+nft_sale_interface::nft_sale_stub!(PACKAGE_PACKAGE);
+
+// Never executed: only checks that the generated stub exposes the sale's signatures
+#[allow(dead_code)]
+fn purchase(
+    component_address: ComponentAddress,
+    payment: Bucket,
+) -> (Bucket, NonFungibleBucket, Vec<Bucket>) {
+    let mut sale: Global<NFTSale> = component_address.into();
+    let (_token, price) = sale.price();
+    assert!(!price.is_negative());
+    sale.buy(payment, 1)
+}
+
+// Never executed: only checks that builders instantiate through the stub
+#[allow(dead_code)]
+fn launch(collection: ResourceAddress) -> Global<NFTSale> {
+    let (sale, _owner_badge, _admin_badge, _pricer_badge) = NFTSaleBuilder::new(collection, XRD)
+        .price(10)
+        .instantiate()
+        .unwrap();
+    sale
+}
+
+#[test]
+fn test_stub_declares_blueprint() {
+    assert_eq!(<NFTSale as HasTypeInfo>::BLUEPRINT_NAME, "NFTSale");
+    assert_eq!(
+        <NFTSale as HasTypeInfo>::PACKAGE_ADDRESS,
+        Some(PACKAGE_PACKAGE)
+    );
+}
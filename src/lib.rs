@@ -1,5 +1,14 @@
+use nft_sale_interface::{
+    price_cohort, verify_allowlist_proof, AccountingPeriod, AdminAction, AuditEntry, Authority,
+    ChangePolicy, DeliveryPolicy, GatedPhase, HoldTerms, InventoryRules, ItemSale, LimitTier,
+    LoyaltyDiscount, NftSelection, PhaseInfo, PlatformFee, PriceChangeLimit, PriceCohort,
+    PriceSchedule, PurchaseAllowance, SaleConfig, SaleError, SaleOwner, SalePhase, SaleSnapshot,
+    SnapshotChunk, StakeDiscount, TreasuryBreakdown, UpgradePath,
+};
 use scrypto::prelude::*;
 
+mod router;
+
 #[derive(ScryptoSbor, NonFungibleData)]
 struct OwnerBadge {
     pub name: String,
@@ -7,64 +16,756 @@ struct OwnerBadge {
 
 #[derive(ScryptoSbor, NonFungibleData)]
 struct AdminBadge {
+    // The holder's handle, kept current with 'rename_admin_badge'
+    #[mutable]
+    pub name: String,
+}
+
+#[derive(ScryptoSbor, NonFungibleData)]
+struct PricerBadge {
+    pub name: String,
+}
+
+#[derive(ScryptoSbor, NonFungibleData)]
+struct EscrowReceipt {
+    pub escrow_id: u64,
+}
+
+// Funds deposited ahead of the sale, converted into NFTs in deposit order once it opens
+#[derive(ScryptoSbor)]
+struct Escrow {
+    funds: Vault,
+    nfts: NonFungibleVault,
+    number_of_nfts: u16,
+}
+
+#[derive(ScryptoSbor, NonFungibleData)]
+struct HoldReceipt {
+    pub hold_id: u64,
+    pub nft_id: NonFungibleLocalId,
+    pub expires_at: Epoch,
+}
+
+// A specific NFT set aside for a buyer, at the price of the moment, until 'expires_at'
+#[derive(ScryptoSbor)]
+struct Hold {
+    nft_id: NonFungibleLocalId,
+    price: Decimal,
+    deposit: Vault,
+    expires_at: Epoch,
+    expiry_fee: Decimal,
+    // Whether the hold is over, by purchase or by the NFT returning to the sale
+    closed: bool,
+}
+
+#[derive(ScryptoSbor, NonFungibleData)]
+struct VipBadge {
+    pub name: String,
+}
+
+// Lets an outside curator list and delist the NFTs of the id ranges the sale keeps for the badge
+#[derive(ScryptoSbor, NonFungibleData)]
+struct ListingManagerBadge {
+    pub name: String,
+}
+
+// Earns its holder 'commission_rate' of the purchases made presenting it, transferable so the
+// referral rights can change hands
+#[derive(ScryptoSbor, NonFungibleData)]
+struct AffiliateLink {
+    pub name: String,
+    pub commission_rate: Decimal,
+}
+
+// Lets keepers and bots run the sale's maintenance methods, none of which pay out to the caller
+#[derive(ScryptoSbor, NonFungibleData)]
+struct AutomationBadge {
+    pub name: String,
+}
+
+// Entitles its holder to an equal share of the revenue set aside for backers
+#[derive(ScryptoSbor, NonFungibleData)]
+struct BackerShare {
     pub name: String,
 }
 
+// Guarantees its holder 'allocation' NFTs at 'price' each during the priority window
+#[derive(ScryptoSbor, NonFungibleData)]
+struct MintPass {
+    pub allocation: u16,
+    pub price: Decimal,
+}
+
+// Proof of payment tokens locked in the sale for the staking discount
+#[derive(ScryptoSbor, NonFungibleData)]
+struct StakeReceipt {
+    pub amount: Decimal,
+    pub unlocks_at: Epoch,
+}
+
+// Emitted whenever NFTs leave the sale vault, gifts being recorded as zero-revenue transfers
+#[derive(ScryptoSbor, ScryptoEvent)]
+struct NftsSoldEvent {
+    nft_ids: IndexSet<NonFungibleLocalId>,
+    revenue: Decimal,
+    // Set when the NFTs were deposited straight into an account instead of returned to the caller
+    recipient: Option<ComponentAddress>,
+    // Set when an account paid for NFTs delivered to someone else
+    sponsor: Option<ComponentAddress>,
+    // Attribution given by the buyer, e.g. a campaign code or storefront id
+    tag: Option<String>,
+    // Pricing cohort of the buyer's account while the sale runs a price experiment
+    cohort: Option<u32>,
+}
+
+// NFTs another component may pull at a fixed price, paid from the budget it deposited
+#[derive(ScryptoSbor)]
+struct Allowance {
+    remaining: u64,
+    price: Decimal,
+    budget: Vault,
+}
+
+// Who a purchase is credited to in its 'NftsSoldEvent'
+#[derive(Default)]
+struct Attribution {
+    recipient: Option<ComponentAddress>,
+    sponsor: Option<ComponentAddress>,
+    tag: Option<String>,
+    // Affiliate link presented with the purchase and its commission rate
+    affiliate: Option<(NonFungibleLocalId, Decimal)>,
+}
+
+// Who a purchase is made for, deciding which limits apply to it
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Buyer {
+    Public,
+    // Authorized with the given account, which the purchase cooldown is tracked for
+    Account(ComponentAddress),
+    // Proved a spot on the allowlist with the given account
+    Allowlisted(ComponentAddress),
+    // Presented a badge of the given resource for the gated phases
+    Gated(ResourceAddress),
+    // Holds the vip role and skips the per-transaction cap
+    Vip,
+}
+
+// Epochs an owner recall has to wait between being scheduled and executed (roughly one day),
+// giving holders and the community time to see it coming
+const RECALL_TIMELOCK_EPOCHS: u64 = 288;
+
+// Epochs buyers have to return their NFTs after a sale is cancelled (roughly one week)
+const REFUND_WINDOW_EPOCHS: u64 = 2016;
+
+// Stocked NFTs read per 'export_state_snapshot' page, of which those still on sale are returned
+const SNAPSHOT_CHUNK_IDS: u64 = 100;
+
+// NFTs the batched methods and gifts move per call at most, whatever limit they are given, so a
+// call never runs into the execution cost limit. Entries are never split: one holding more NFTs,
+// at most a full purchase, is processed on its own. What is left is picked up by the next call
+const MAX_BATCH: u32 = 50;
+
+// Longest attribution tag 'buy_tagged' records, in bytes
+const MAX_TAG_LENGTH: usize = 64;
+
+// A recall of NFTs from a holder's vault that has been announced but not yet executed
+#[derive(ScryptoSbor, Clone)]
+struct PendingRecall {
+    vault: InternalAddress,
+    nft_ids: IndexSet<NonFungibleLocalId>,
+    executable_at: Epoch,
+}
+
+#[derive(ScryptoSbor, ScryptoEvent)]
+struct RecallScheduledEvent {
+    recall_id: u64,
+    vault: InternalAddress,
+    nft_ids: IndexSet<NonFungibleLocalId>,
+    executable_at: Epoch,
+}
+
+#[derive(ScryptoSbor, ScryptoEvent)]
+struct RecallCancelledEvent {
+    recall_id: u64,
+}
+
+#[derive(ScryptoSbor, ScryptoEvent)]
+struct RecallExecutedEvent {
+    recall_id: u64,
+    vault: InternalAddress,
+    nft_ids: IndexSet<NonFungibleLocalId>,
+}
+
+// Emitted when a holder claims the physical item behind an NFT of a phygital drop
+#[derive(ScryptoSbor, ScryptoEvent)]
+struct PhysicalRedeemedEvent {
+    nft_id: NonFungibleLocalId,
+    // Hash of the shipping details, which are exchanged with the project off-ledger
+    shipping_hash: Hash,
+}
+
+// Emitted whenever the owner or an admin stocks the sale vault
+#[derive(ScryptoSbor, ScryptoEvent)]
+struct InventoryChangedEvent {
+    added: IndexSet<NonFungibleLocalId>,
+    // NFTs in the vault after the deposit
+    inventory: Decimal,
+}
+
+#[derive(ScryptoSbor, ScryptoEvent)]
+struct ListingChangedEvent {
+    nft_ids: IndexSet<NonFungibleLocalId>,
+    listed: bool,
+}
+
+#[derive(ScryptoSbor, ScryptoEvent)]
+struct UpgradeRedeemedEvent {
+    burned_ids: IndexSet<NonFungibleLocalId>,
+    nft_ids: IndexSet<NonFungibleLocalId>,
+}
+
+#[derive(ScryptoSbor, ScryptoEvent)]
+struct SaleCancelledEvent {
+    refunds_close_at: Epoch,
+}
+
+#[derive(ScryptoSbor, ScryptoEvent)]
+struct RefundClaimedEvent {
+    nft_ids: IndexSet<NonFungibleLocalId>,
+    refund: Decimal,
+}
+
+// Emitted when a refund the treasury cannot cover is paid from the owner's insurance deposit
+#[derive(ScryptoSbor, ScryptoEvent)]
+struct InsuranceSlashedEvent {
+    amount: Decimal,
+}
+
+#[derive(ScryptoSbor, ScryptoEvent)]
+struct AccountingPeriodClosedEvent {
+    index: u64,
+    period: AccountingPeriod,
+}
+
+// Emitted next to 'NftsSoldEvent' for purchases worth at least the large purchase threshold
+#[derive(ScryptoSbor, ScryptoEvent)]
+struct LargePurchaseEvent {
+    // The account the purchase was made as, when the buyer named one
+    buyer: Option<ComponentAddress>,
+    amount: Decimal,
+    number_of_nfts: u16,
+}
+
+// Emitted when an account refuses NFTs the sale gives away, which then follow the delivery policy
+#[derive(ScryptoSbor, ScryptoEvent)]
+struct DeliveryRefusedEvent {
+    recipient: ComponentAddress,
+    nft_ids: IndexSet<NonFungibleLocalId>,
+    policy: DeliveryPolicy,
+}
+
+// Emitted when NFTs bought while deliveries were deferred are handed over by 'fulfil'
+#[derive(ScryptoSbor, ScryptoEvent)]
+struct IntentFulfilledEvent {
+    intent_id: u64,
+    recipient: ComponentAddress,
+    nft_ids: IndexSet<NonFungibleLocalId>,
+}
+
+// Emitted when a purchase made presenting an affiliate link earns the link a commission
+#[derive(ScryptoSbor, ScryptoEvent)]
+struct CommissionEarnedEvent {
+    link_id: NonFungibleLocalId,
+    commission: Decimal,
+}
+
+// Emitted by 'verify_invariants' for every counter that disagrees with the vaults it tracks
+#[derive(ScryptoSbor, ScryptoEvent)]
+struct InvariantViolatedEvent {
+    invariant: String,
+    expected: Decimal,
+    actual: Decimal,
+}
+
+// Emitted when proceeds collected during a gated phase go to the account the phase routes them to
+#[derive(ScryptoSbor, ScryptoEvent)]
+struct ProceedsRoutedEvent {
+    phase: String,
+    recipient: ComponentAddress,
+    amount: Decimal,
+}
+
+// Emitted once for every 'configure' call, with all of its changes
+#[derive(ScryptoSbor, ScryptoEvent)]
+struct ConfigurationChangedEvent {
+    actions: Vec<AdminAction>,
+}
+
+// The last event of a sale, emitted once it is finalized
+#[derive(ScryptoSbor, ScryptoEvent)]
+struct SaleFinalizedEvent {
+    epoch: Epoch,
+}
+
 #[blueprint]
+#[events(
+    NftsSoldEvent,
+    RecallScheduledEvent,
+    RecallCancelledEvent,
+    RecallExecutedEvent,
+    PhysicalRedeemedEvent,
+    UpgradeRedeemedEvent,
+    SaleCancelledEvent,
+    RefundClaimedEvent,
+    InsuranceSlashedEvent,
+    InventoryChangedEvent,
+    ListingChangedEvent,
+    AccountingPeriodClosedEvent,
+    LargePurchaseEvent,
+    SaleFinalizedEvent,
+    DeliveryRefusedEvent,
+    ConfigurationChangedEvent,
+    IntentFulfilledEvent,
+    CommissionEarnedEvent,
+    InvariantViolatedEvent,
+    ProceedsRoutedEvent
+)]
 mod nft_sale {
 
     enable_method_auth! {
         roles {
             admin => updatable_by: [OWNER];
+            pricer => updatable_by: [OWNER];
+            vip => updatable_by: [OWNER];
+            guardian => updatable_by: [OWNER];
+            automation => updatable_by: [OWNER];
         },
         methods {
             start_sale => restrict_to: [admin, OWNER];
+            pause_buying => restrict_to: [guardian];
+            resume_buying => restrict_to: [guardian];
             end_sale => restrict_to: [admin, OWNER];
-            change_price => restrict_to: [admin, OWNER];
+            change_price => restrict_to: [pricer, admin, OWNER];
+            override_price => restrict_to: [OWNER];
+            set_price_change_limit => restrict_to: [OWNER];
+            set_limit_tiers => restrict_to: [admin, OWNER];
+            configure => restrict_to: [admin, OWNER];
+            max_per_transaction => PUBLIC;
+            set_dapp_definition => restrict_to: [OWNER];
+            set_info_url => restrict_to: [OWNER];
+            set_social_urls => restrict_to: [OWNER];
             withdraw_profits => restrict_to: [OWNER];
+            treasury_breakdown => PUBLIC;
+            verify_invariants => PUBLIC;
+            withdraw_tips => restrict_to: [OWNER];
+            audit_log => PUBLIC;
+            close_accounting_period => restrict_to: [admin, automation, OWNER];
+            accounting_periods => PUBLIC;
+            export_buyers => PUBLIC;
+            current_phase_info => PUBLIC;
+            export_state_snapshot => PUBLIC;
+            withdraw_from_treasury => restrict_to: [OWNER];
             add_nfts_to_vault => restrict_to: [admin, OWNER];
+            set_inventory_rules => restrict_to: [OWNER];
+            delist => restrict_to: [admin, OWNER];
+            relist => restrict_to: [admin, OWNER];
+            schedule_listing => restrict_to: [admin, OWNER];
+            issue_listing_manager => restrict_to: [OWNER];
+            issue_affiliate_link => restrict_to: [OWNER];
+            issue_automation_badge => restrict_to: [OWNER];
+            buy_via_affiliate => PUBLIC;
+            claim_commission => PUBLIC;
+            commission_owed => PUBLIC;
+            set_managed_ranges => restrict_to: [OWNER];
+            manage_delist => PUBLIC;
+            manage_relist => PUBLIC;
+            manage_listing => PUBLIC;
+            listing_price => PUBLIC;
+            listing_price_at => PUBLIC;
+            buy_specific => PUBLIC;
+            gift_from_vault => restrict_to: [OWNER];
+            set_donation_list => restrict_to: [OWNER];
+            donate_unsold => restrict_to: [OWNER];
+            add_airdrop_recipients => restrict_to: [OWNER];
+            airdrop_batch => restrict_to: [automation, OWNER];
+            set_deferred_delivery => restrict_to: [OWNER];
+            fulfil => restrict_to: [admin, automation, OWNER];
+            delivery_locker => PUBLIC;
+            set_delivery_policy => restrict_to: [OWNER];
+            retry_deliveries => restrict_to: [automation, OWNER];
+            schedule_recall => restrict_to: [OWNER];
+            cancel_recall => restrict_to: [OWNER];
+            recall_item => restrict_to: [OWNER];
             price => PUBLIC;
             is_sold => PUBLIC;
             buy => PUBLIC;
+            buy_tagged => PUBLIC;
+            item_history => PUBLIC;
+            buy_vip => restrict_to: [vip, OWNER];
+            grant_vip => restrict_to: [OWNER];
+            rename_admin_badge => restrict_to: [OWNER];
+            buy_for => PUBLIC;
+            grant_allowance => restrict_to: [OWNER];
+            deposit_budget => PUBLIC;
+            pull_nfts => PUBLIC;
+            withdraw_budget => PUBLIC;
+            allowance => PUBLIC;
+            buy_allowlisted => PUBLIC;
+            set_allowlist_root => restrict_to: [OWNER];
+            buy_as => PUBLIC;
+            buy_gated => PUBLIC;
+            set_gated_phases => restrict_to: [OWNER];
+            set_purchase_cooldown => restrict_to: [OWNER];
+            buy_vouchers => PUBLIC;
+            escrow => PUBLIC;
+            claim_escrow => PUBLIC;
+            convert_escrows => restrict_to: [admin, automation, OWNER];
+            set_hold_terms => restrict_to: [OWNER];
+            hold => PUBLIC;
+            complete_hold => PUBLIC;
+            release_hold => PUBLIC;
+            reclaim_hold => PUBLIC;
+            redeem_physical => PUBLIC;
+            set_upgrade_path => restrict_to: [OWNER];
+            burn_to_redeem => PUBLIC;
+            cancel_sale => restrict_to: [OWNER];
+            finalize => restrict_to: [OWNER];
+            claim_refund => PUBLIC;
+            lock_insurance => restrict_to: [OWNER];
+            withdraw_insurance => restrict_to: [OWNER];
+            reveal => restrict_to: [OWNER];
+            slash_insurance => PUBLIC;
+            insurance => PUBLIC;
+            deposit_bonus => restrict_to: [OWNER];
+            set_bonus_per_nft => restrict_to: [OWNER];
+            set_price_cohorts => restrict_to: [OWNER];
+            price_cohorts => PUBLIC;
+            withdraw_bonus => restrict_to: [OWNER];
+            set_cashback_rate => restrict_to: [OWNER];
+            set_minimum_payment => restrict_to: [OWNER];
+            set_large_purchase_threshold => restrict_to: [OWNER];
+            set_stake_discount => restrict_to: [OWNER];
+            set_loyalty_discount => restrict_to: [OWNER];
+            buy_returning => PUBLIC;
+            mint_passes => restrict_to: [OWNER];
+            issue_backer_shares => restrict_to: [OWNER];
+            claim_revenue_share => PUBLIC;
+            set_pass_window => restrict_to: [OWNER];
+            redeem_passes => PUBLIC;
+            stake => PUBLIC;
+            unstake => PUBLIC;
+            buy_staked => PUBLIC;
         }
     }
 
     struct NFTSale {
         // Vault to hold the NFT collection
         nft_vault: NonFungibleVault,
-        // Vault to hold the xrd paid for NFTs
-        xrd_vault: Vault,
+        // NFTs pulled from sale, e.g. while disputed, that stay with the component
+        delisted_vault: NonFungibleVault,
+        // Merkle root of the allowlist flat-price purchases are restricted to, if any
+        allowlist_root: Option<Hash>,
+        // Named phases gating purchases by badge, in order of their start
+        gated_phases: Vec<GatedPhase>,
+        // Epochs an account has to wait between purchases, and the epoch of each one's last purchase
+        purchase_cooldown: Option<u64>,
+        last_purchases: KeyValueStore<ComponentAddress, Epoch>,
+        // Single listings sold on their own declining price schedule through 'buy_specific'
+        scheduled_vault: NonFungibleVault,
+        listing_schedules: KeyValueStore<NonFungibleLocalId, PriceSchedule>,
+        // Integer id ranges, first to last inclusive, each listing manager badge may manage
+        listing_manager_address: ResourceAddress,
+        automation_badge_address: ResourceAddress,
+        managed_ranges: KeyValueStore<NonFungibleLocalId, Vec<(u64, u64)>>,
+        // Revenue held by the sale, one vault per token received, and how much of the payment
+        // token should be in it
+        treasury: KeyValueStore<ResourceAddress, Vault>,
+        treasury_funds: Decimal,
         // The token to accept as payment
         accepted_payment_token: ResourceAddress,
         // Price per NFT in 'accepted_payment_token'
         price: Decimal,
+        // Maximum number of NFTs per purchase
+        max_nfts_per_transaction: u16,
+        // Lower limits that kick in as the remaining supply dwindles
+        limit_tiers: Vec<LimitTier>,
+        // Checks applied to every deposit into 'nft_vault'
+        inventory_rules: InventoryRules,
+        // Decimal places of 'accepted_payment_token', read once at instantiation
+        payment_divisibility: u8,
+        // Id type of the collection on sale, read once at instantiation
+        nft_id_type: NonFungibleIdType,
+        // Smallest payment any purchase, voucher sale or escrow takes, zero for no minimum
+        minimum_payment: Decimal,
+        // Purchases costing at least this much also emit a 'LargePurchaseEvent'
+        large_purchase_threshold: Option<Decimal>,
+        // Handling of overpayment, and the change kept as tips under 'ChangePolicy::Tip'
+        change_policy: ChangePolicy,
+        tips: Vault,
 
         admin_badge_address: ResourceAddress,
+        pricer_badge_address: ResourceAddress,
+        // Transferable gift vouchers worth one 'accepted_payment_token' each, redeemable in 'buy'
+        voucher_resource_address: ResourceAddress,
 
         sale_allowed: bool,
+        // Set once the sale first opens, from then on funds can no longer be escrowed
+        sale_started: bool,
+        // The price the sale first opened at, which escrows are converted at
+        opening_price: Decimal,
+        // Set by the guardian in an emergency, stopping every purchase until it resumes them
+        buying_paused: bool,
+
+        // Announced recalls of delivered NFTs, executable once their timelock has passed
+        pending_recalls: KeyValueStore<u64, PendingRecall>,
+        next_recall_id: u64,
+
+        // Shipping hash of every NFT whose physical item has been claimed
+        physical_redemptions: KeyValueStore<NonFungibleLocalId, Hash>,
+
+        // Exchange of an older collection for NFTs from the vault, if the owner opened one
+        upgrade_path: Option<UpgradePath>,
+
+        // Accounts unsold NFTs are donated to with their weights, and the number donated so far
+        donation_list: Vec<(ComponentAddress, u32)>,
+        donated: u64,
+        // Airdrop recipients in the order they were added, the ones below 'airdrop_cursor'
+        // having received their NFT
+        airdrop_recipients: KeyValueStore<u64, ComponentAddress>,
+        airdrop_length: u64,
+        airdrop_cursor: u64,
+        // Keeps donated and airdropped NFTs an account refused until its owner claims them
+        delivery_locker: Option<Global<AccountLocker>>,
+        // What happens to given away NFTs an account refuses
+        delivery_policy: DeliveryPolicy,
+        // Refused deliveries waiting for 'retry_deliveries' in the order they were refused, the
+        // ones below 'retry_cursor' having been retried, and the NFTs they hold and their number
+        retry_queue: KeyValueStore<u64, (ComponentAddress, IndexSet<NonFungibleLocalId>)>,
+        retry_length: u64,
+        retry_cursor: u64,
+        retry_vault: NonFungibleVault,
+        retry_queued: u64,
+
+        // While set, purchases are paid for up front and their NFTs kept as intents, delivered
+        // in order by 'fulfil'. The ones below 'intent_cursor' have been fulfilled, the NFTs of
+        // the others are counted in 'intents_queued'
+        deferred_delivery: bool,
+        intents: KeyValueStore<u64, (ComponentAddress, IndexSet<NonFungibleLocalId>)>,
+        intent_length: u64,
+        intent_cursor: u64,
+        intent_vault: NonFungibleVault,
+        intents_queued: u64,
+
+        // Components allowed to pull NFTs straight from the vault, e.g. in-game shops
+        allowances: KeyValueStore<ComponentAddress, Allowance>,
+        // Sum of the budgets they deposited and have not spent or withdrawn
+        allowance_budgets: Decimal,
+
+        // Price paid for every NFT sold, which is what a refund pays back
+        sale_prices: KeyValueStore<NonFungibleLocalId, Decimal>,
+        // Sum of 'sale_prices', what refunding every NFT sold would cost
+        refundable: Decimal,
+        // Every sale of each NFT, kept after refunds for provenance
+        item_sales: KeyValueStore<NonFungibleLocalId, Vec<ItemSale>>,
+        // Set once the sale is cancelled, refunds are claimable until this epoch
+        refunds_close_at: Option<Epoch>,
+        // Set when the sale ends, the sale can be cancelled and refundable revenue stays in the
+        // treasury until this epoch. Past it the revenue is the owner's and the sale is over
+        refunds_guaranteed_until: Option<Epoch>,
+        // Deposit the owner locks as a pledge to buyers, paying the refunds the treasury cannot
+        // cover once the sale is cancelled. Locked until 'insurance_locked_until', or the refund
+        // window closing if the sale is cancelled
+        insurance_vault: Vault,
+        insurance_locked_until: Option<Epoch>,
+        // Epoch the owner pledged with the insurance to reveal the collection by. Once it passes
+        // without a 'reveal', anyone can cancel the sale and have refunds paid from the insurance
+        reveal_deadline: Option<Epoch>,
+        // Set by 'finalize', after which no owner, admin or funding method runs again
+        finalized: bool,
+
+        // Launchpad cut deducted from every payment before it reaches the treasury
+        platform_fee: Option<PlatformFee>,
+        // Cap on every fee rate, fixed at instantiation
+        max_fee_rate: Decimal,
+        // Bounds on every price cohort's adjustment and extra bonus, fixed at instantiation
+        max_cohort_adjustment: Decimal,
+        max_cohort_bonus: Decimal,
+
+        // Tokens dispensed with every purchase at 'bonus_per_nft' until the vault runs dry
+        bonus_vault: Option<Vault>,
+        bonus_per_nft: Decimal,
+
+        // Price experiment splitting buyers who name their account into cohorts, none if empty
+        price_cohorts: Vec<PriceCohort>,
+
+        // Loyalty tokens minted as cashback, 'cashback_rate' of every purchase's cost
+        loyalty_resource_address: ResourceAddress,
+        cashback_rate: Decimal,
+
+        // Payment tokens locked by buyers in exchange for the staking discount
+        stake_vault: Vault,
+        stake_receipt_address: ResourceAddress,
+        stake_discount: Option<StakeDiscount>,
+
+        // Discount for holders of resources from the creator's earlier drops
+        loyalty_discount: Option<LoyaltyDiscount>,
+
+        vip_badge_address: ResourceAddress,
+
+        // Mint passes and the NFTs held back for them until the priority window closes
+        mint_pass_address: ResourceAddress,
+        pass_window: Option<(Epoch, Epoch)>,
+        reserved_for_passes: u64,
+
+        // Pre-sale deposits by id, the ones below 'escrows_converted' already being processed
+        escrows: KeyValueStore<u64, Escrow>,
+        escrow_receipt_address: ResourceAddress,
+        next_escrow_id: u64,
+        escrows_converted: u64,
+        // Funds in escrows not converted or claimed back yet
+        escrowed_funds: Decimal,
+
+        // NFTs held against deposits, kept out of 'nft_vault' while a hold lasts
+        hold_terms: Option<HoldTerms>,
+        holds: KeyValueStore<u64, Hold>,
+        held_vault: NonFungibleVault,
+        hold_receipt_address: ResourceAddress,
+        next_hold_id: u64,
+        // Deposits still kept in holds, and the number of holds not closed yet
+        held_deposits: Decimal,
+        open_holds: u64,
+
+        // Part of every payment collected that is set aside for backer share holders. Each share
+        // is owed 'revenue_per_share' in total, of which 'revenue_claimed' was paid out already
+        backer_share_address: ResourceAddress,
+        backer_shares: u64,
+        revenue_share: Decimal,
+        revenue_share_vault: Vault,
+        revenue_per_share: Decimal,
+        revenue_claimed: KeyValueStore<NonFungibleLocalId, Decimal>,
+        // Sum of 'revenue_claimed' over all shares
+        revenue_claimed_total: Decimal,
+
+        // Commissions earned by affiliate links and not yet claimed, kept apart from the treasury
+        affiliate_link_address: ResourceAddress,
+        commissions: KeyValueStore<NonFungibleLocalId, Decimal>,
+        commission_vault: Vault,
+        // Sum of 'commissions'
+        commissions_owed: Decimal,
+
+        // Bounds on 'change_price' protecting buyers from a whipsawed price
+        price_change_limit: Option<PriceChangeLimit>,
+        last_price_change: Option<Epoch>,
+
+        // Append-only record of every owner and admin action, keyed by sequence number
+        audit_log: KeyValueStore<u64, AuditEntry>,
+        audit_log_length: u64,
+
+        // Append-only index of every NFT ever stocked, by position and the other way around, so
+        // snapshots page through the inventory by a position that never shifts
+        stocked_ids: KeyValueStore<u64, NonFungibleLocalId>,
+        stocked_positions: KeyValueStore<NonFungibleLocalId, u64>,
+        stocked_length: u64,
+
+        // Running totals of sales and refunds, and the closed accounting periods by index
+        total_revenue: Decimal,
+        total_units: u64,
+        total_refunds: Decimal,
+        accounting_periods: KeyValueStore<u64, AccountingPeriod>,
+        accounting_period_count: u64,
+        period_opened_at: Epoch,
+
+        // Units bought and amount spent by every account a purchase was made as, in the order
+        // the accounts first bought. Anonymous purchases are not attributed to anyone
+        buyers: KeyValueStore<u64, ComponentAddress>,
+        buyer_count: u64,
+        buyer_totals: KeyValueStore<ComponentAddress, (u64, Decimal)>,
     }
 
     impl NFTSale {
         pub fn instantiate_nft_sale(
+            config: SaleConfig,
+        ) -> (Global<NFTSale>, NonFungibleBucket, NonFungibleBucket, NonFungibleBucket) {
+            let nft_vault = NonFungibleVault::new(config.nft_resource_address);
+            Self::instantiate(config, nft_vault)
+        }
+
+        // Instantiate a sale already stocked with the given NFTs. The collection is taken from the
+        // bucket, so 'nft_resource_address' in the config is ignored
+        pub fn instantiate_with_nfts(
+            nfts: NonFungibleBucket,
+            config: SaleConfig,
+        ) -> (Global<NFTSale>, NonFungibleBucket, NonFungibleBucket, NonFungibleBucket) {
+            let config = SaleConfig {
+                nft_resource_address: nfts.resource_address(),
+                ..config
+            };
+            Self::instantiate(config, NonFungibleVault::with_bucket(nfts))
+        }
+
+        // Relaunch the setup of an existing sale for another collection. The new sale gets fresh
+        // badges and empty vaults, and copies every setting of the source. Its buy royalty starts
+        // out free and its owner is its own owner badge
+        pub fn clone_sale(
+            source: ComponentAddress,
             nft_resource_address: ResourceAddress,
-            accepted_payment_token: ResourceAddress,
-            price: Decimal,
-        ) -> (Global<NFTSale>, NonFungibleBucket, NonFungibleBucket) {
-            let (address_reservation, component_address) =
-                Runtime::allocate_component_address(<NFTSale>::blueprint_id());
+        ) -> (Global<NFTSale>, NonFungibleBucket, NonFungibleBucket, NonFungibleBucket) {
+            let source: Global<NFTSale> = source.into();
+            let SnapshotChunk::Summary(summary) = source.export_state_snapshot(0).0 else {
+                unreachable!("The first snapshot chunk is always the summary.");
+            };
+            let config = SaleConfig {
+                nft_resource_address,
+                accepted_payment_token: summary.accepted_payment_token,
+                price: summary.price,
+                max_nfts_per_transaction: summary.max_nfts_per_transaction,
+                buy_royalty: RoyaltyAmount::Free,
+                platform_fee: summary.platform_fee.clone(),
+                owner: SaleOwner::Badge,
+                change_policy: summary.change_policy,
+                max_fee_rate: summary.max_fee_rate,
+                max_cohort_adjustment: summary.max_cohort_adjustment,
+                max_cohort_bonus: summary.max_cohort_bonus,
+                gated_phases: summary.gated_phases.clone(),
+            };
+            let (sale, owner_badge, admin_badge, pricer_badge) =
+                Self::instantiate(config, NonFungibleVault::new(nft_resource_address));
 
-            assert!(
-                !matches!(
-                    ResourceManager::from_address(accepted_payment_token).resource_type(),
-                    ResourceType::NonFungible { id_type: _ }
-                ),
-                "Only payments of fungible resources are accepted."
-            );
-            assert!(
-                price >= Decimal::zero(),
-                "The price cannot be less then ZERO!"
-            );
+            owner_badge.authorize_with_all(move || {
+                sale.set_limit_tiers(None, summary.limit_tiers);
+                sale.set_inventory_rules(summary.inventory_rules);
+                sale.set_stake_discount(summary.stake_discount);
+                sale.set_loyalty_discount(summary.loyalty_discount);
+                sale.set_price_change_limit(summary.price_change_limit);
+                sale.set_cashback_rate(summary.cashback_rate);
+                sale.set_bonus_per_nft(summary.bonus_per_nft);
+                sale.set_price_cohorts(summary.price_cohorts);
+                sale.set_purchase_cooldown(summary.purchase_cooldown);
+                sale.set_minimum_payment(summary.minimum_payment);
+                sale.set_allowlist_root(summary.allowlist_root);
+                sale.set_hold_terms(summary.hold_terms);
+                sale.set_large_purchase_threshold(summary.large_purchase_threshold);
+                sale.set_delivery_policy(summary.delivery_policy);
+                sale.set_deferred_delivery(summary.deferred_delivery);
+                sale.set_upgrade_path(summary.upgrade_path);
+                if let Some((opens_at, closes_at)) = summary.pass_window {
+                    sale.set_pass_window(opens_at, closes_at);
+                }
+                if !summary.donation_list.is_empty() {
+                    sale.set_donation_list(summary.donation_list);
+                }
+            });
+            (sale, owner_badge, admin_badge, pricer_badge)
+        }
 
+        fn instantiate(
+            config: SaleConfig,
+            nft_vault: NonFungibleVault,
+        ) -> (Global<NFTSale>, NonFungibleBucket, NonFungibleBucket, NonFungibleBucket) {
+            let (payment_divisibility, nft_id_type) = Self::validate_config(&config);
+
+            let (address_reservation, component_address) =
+                Runtime::allocate_component_address(<NFTSale>::blueprint_id());
 
             let owner_badge: NonFungibleBucket = ResourceBuilder::new_integer_non_fungible::<OwnerBadge>(OwnerRole::None)
                 .metadata(metadata!{
@@ -84,6 +785,16 @@ mod nft_sale {
                     (0u64.into(), OwnerBadge { name: "Owner Badge".to_owned()}),
                 ]);
 
+            // Metadata and VIP badge recalls stay with the initial rule, even if an updatable
+            // owner role is changed later
+            let (owner_role, owner_rule) = match config.owner.clone() {
+                SaleOwner::Badge => {
+                    let rule = rule!(require(owner_badge.resource_address()));
+                    (OwnerRole::Fixed(rule.clone()), rule)
+                }
+                SaleOwner::Fixed(rule) => (OwnerRole::Fixed(rule.clone()), rule),
+                SaleOwner::Updatable(rule) => (OwnerRole::Updatable(rule.clone()), rule),
+            };
             // Create admin badges for team members to interact with a few of the auth protected methods
             let admin_badge: NonFungibleBucket = ResourceBuilder::new_integer_non_fungible::<AdminBadge>(OwnerRole::None)
                 .metadata(metadata!{
@@ -99,6 +810,11 @@ mod nft_sale {
                     // no one can update the minter role
                     minter_updater => rule!(deny_all);
                 })
+                .non_fungible_data_update_roles(non_fungible_data_update_roles!{
+                    // Renaming goes through the component, which restricts it to the owner
+                    non_fungible_data_updater => rule!(require(global_caller(component_address)));
+                    non_fungible_data_updater_updater => rule!(deny_all);
+                })
                 .recall_roles(recall_roles!{
                     // Owner can recall the admin badges
                     recaller => rule!(require(global_caller(owner_badge.resource_address())));
@@ -109,117 +825,4181 @@ mod nft_sale {
                     (0u64.into(), AdminBadge { name: "Admin Badge".to_owned()}),
                 ]);
 
-            let component_address = Self {
-                nft_vault: NonFungibleVault::new(nft_resource_address),
-                xrd_vault: Vault::new(accepted_payment_token),
-                accepted_payment_token,
-                price,
-                admin_badge_address: admin_badge.resource_address(),
-                sale_allowed: false,
-            }
-            .instantiate()
-            .prepare_to_globalize(OwnerRole::Fixed(rule!(require(
-                owner_badge.resource_address()
-            ))))
-            .with_address(address_reservation)
-            .roles(roles!(
-                admin => rule!(require(admin_badge.resource_address()));
-            ))
-            .globalize();
-
-            (component_address, owner_badge, admin_badge)
-        }
-
-        // Add nfts to the nft vault after instatiation for testing methods
-        pub fn add_nfts_to_vault(&mut self, nft_deposit_bucket: NonFungibleBucket) {
-            // Add the bucket to the vault
-            self.nft_vault.put(nft_deposit_bucket)
-        }
-
-
-        // Set the bool to true so the sale can begin
-        pub fn start_sale(&mut self) {
-            self.sale_allowed = true;
-        }
+            // Create pricer badges for market ops or repricing bots that may only change the price
+            let pricer_badge: NonFungibleBucket = ResourceBuilder::new_integer_non_fungible::<PricerBadge>(OwnerRole::None)
+                .metadata(metadata!{
+                    init {
+                        "name" => "Component Pricer Badge", locked;
+                        "icon_url" => Url::of("https://s2.coinmarketcap.com/static/img/coins/200x200/11948.png"/*Placeholder Image*/), updatable;
+                        "tags" => "badge", locked;
+                    }
+                })
+                .mint_roles(mint_roles!{
+                    // This rule says only the component itself can mint these non fungibles
+                    minter => rule!(require(global_caller(component_address)));
+                    // no one can update the minter role
+                    minter_updater => rule!(deny_all);
+                })
+                .recall_roles(recall_roles!{
+                    // Owner can recall the pricer badges, e.g. from a compromised pricing bot
+                    recaller => owner_rule.clone();
+                    // no one can update the recaller role
+                    recaller_updater => rule!(deny_all);
+                })
+                .mint_initial_supply([
+                    (0u64.into(), PricerBadge { name: "Pricer Badge".to_owned()}),
+                ]);
 
-        // Set the bool back to false so the sale will no longer be available
-        pub fn end_sale(&mut self) {
-            self.sale_allowed = false;
-        }
+            // Create the gift voucher token, only this component can issue and redeem them
+            let voucher_manager = ResourceBuilder::new_fungible(OwnerRole::None)
+                .divisibility(payment_divisibility)
+                .metadata(metadata!{
+                    init {
+                        "name" => "NFT Sale Gift Voucher", locked;
+                        "description" => "Redeemable at face value for NFTs from this sale.", locked;
+                        "tags" => "voucher", locked;
+                    }
+                })
+                .mint_roles(mint_roles!{
+                    minter => rule!(require(global_caller(component_address)));
+                    minter_updater => rule!(deny_all);
+                })
+                .burn_roles(burn_roles!{
+                    burner => rule!(require(global_caller(component_address)));
+                    burner_updater => rule!(deny_all);
+                })
+                .create_with_no_initial_supply();
 
-        // Buy the specified number of NFTs and supply payment
-        pub fn buy(
-            &mut self,
-            mut payment: Bucket,
-            number_of_nfts: u16,
-        ) -> (Bucket, NonFungibleBucket) {
-            // Check if the sale is allowed
-            assert!(
-                self.sale_allowed,
-                "[Buy]: Sale is not allowed yet. Please wait until the sale starts."
-            );
-            // Verify the token supplied is the correct resource
-            assert_eq!(
-                payment.resource_address(),
-                self.accepted_payment_token,
-                "[Buy]: Invalid token provided. Payment is only accepted in {:?}",
-                self.accepted_payment_token
-            );
-            // Enforce the limit of 10 NFTs per purchase
-            assert!(
-                number_of_nfts <= 10,
-                "[Buy]: You can only buy a maximum of 10 NFTs per transaction."
-            );
+            // Create the loyalty token paid out as cashback, only this component can mint it
+            let loyalty_manager = ResourceBuilder::new_fungible(OwnerRole::None)
+                .metadata(metadata!{
+                    init {
+                        "name" => "NFT Sale Loyalty Token", locked;
+                        "description" => "Cashback earned by buying NFTs from this sale.", locked;
+                    }
+                })
+                .mint_roles(mint_roles!{
+                    minter => rule!(require(global_caller(component_address)));
+                    minter_updater => rule!(deny_all);
+                })
+                .create_with_no_initial_supply();
 
-            // Verify the amount supplied is correct
-            assert!(
-                payment.amount() >= self.price * number_of_nfts,
-                "[Buy]: Invalid quantity was provided. This sale can only go through when {} tokens are provided.",
-                self.price * number_of_nfts
-            );
+            // Create the receipts handed out for staked payment tokens
+            let stake_receipt_manager = ResourceBuilder::new_ruid_non_fungible::<StakeReceipt>(OwnerRole::None)
+                .metadata(metadata!{
+                    init {
+                        "name" => "NFT Sale Stake Receipt", locked;
+                        "description" => "Unlocks discounted purchases and returns the stake once its lock expires.", locked;
+                    }
+                })
+                .mint_roles(mint_roles!{
+                    minter => rule!(require(global_caller(component_address)));
+                    minter_updater => rule!(deny_all);
+                })
+                .burn_roles(burn_roles!{
+                    burner => rule!(require(global_caller(component_address)));
+                    burner_updater => rule!(deny_all);
+                })
+                .create_with_no_initial_supply();
 
-            // Take the given number of NFTs specified by the user from the vault
-            let nft = self.nft_vault.take(number_of_nfts);
+            // Create the VIP badges the owner hands to buyers exempt from the per-transaction cap
+            let vip_badge_manager = ResourceBuilder::new_ruid_non_fungible::<VipBadge>(OwnerRole::None)
+                .metadata(metadata!{
+                    init {
+                        "name" => "NFT Sale VIP Badge", locked;
+                        "tags" => "badge", locked;
+                    }
+                })
+                .mint_roles(mint_roles!{
+                    minter => rule!(require(global_caller(component_address)));
+                    minter_updater => rule!(deny_all);
+                })
+                .recall_roles(recall_roles!{
+                    // Owner can take VIP status back
+                    recaller => owner_rule.clone();
+                    recaller_updater => rule!(deny_all);
+                })
+                .create_with_no_initial_supply();
 
-            // Take the required amount of tokens for the purchase (without change)
-            let revenue = payment.take(self.price * number_of_nfts);
+            // Create the mint passes sold or airdropped ahead of the drop
+            let mint_pass_manager = ResourceBuilder::new_ruid_non_fungible::<MintPass>(OwnerRole::None)
+                .metadata(metadata!{
+                    init {
+                        "name" => "NFT Sale Mint Pass", locked;
+                        "description" => "Guarantees an allocation of NFTs at a locked price during the priority window.", locked;
+                    }
+                })
+                .mint_roles(mint_roles!{
+                    minter => rule!(require(global_caller(component_address)));
+                    minter_updater => rule!(deny_all);
+                })
+                .burn_roles(burn_roles!{
+                    burner => rule!(require(global_caller(component_address)));
+                    burner_updater => rule!(deny_all);
+                })
+                .create_with_no_initial_supply();
 
-            // Store it in the xrd vault
-            self.xrd_vault.put(revenue);
+            // Create the receipts for funds escrowed before the sale opens
+            let escrow_receipt_manager = ResourceBuilder::new_ruid_non_fungible::<EscrowReceipt>(OwnerRole::None)
+                .metadata(metadata!{
+                    init {
+                        "name" => "NFT Sale Escrow Receipt", locked;
+                        "description" => "Claims the NFTs bought with, or the refund of, an escrowed deposit.", locked;
+                    }
+                })
+                .mint_roles(mint_roles!{
+                    minter => rule!(require(global_caller(component_address)));
+                    minter_updater => rule!(deny_all);
+                })
+                .burn_roles(burn_roles!{
+                    burner => rule!(require(global_caller(component_address)));
+                    burner_updater => rule!(deny_all);
+                })
+                .create_with_no_initial_supply();
 
-            // Return any excess funds and the bucket of NFTs purchased
-            (payment, nft)
-        }
+            // Create the receipts of holds on specific NFTs
+            let hold_receipt_manager = ResourceBuilder::new_ruid_non_fungible::<HoldReceipt>(OwnerRole::None)
+                .metadata(metadata!{
+                    init {
+                        "name" => "NFT Sale Hold Receipt", locked;
+                        "description" => "Buys the held NFT before the hold expires, or reclaims the deposit after.", locked;
+                    }
+                })
+                .mint_roles(mint_roles!{
+                    minter => rule!(require(global_caller(component_address)));
+                    minter_updater => rule!(deny_all);
+                })
+                .burn_roles(burn_roles!{
+                    burner => rule!(require(global_caller(component_address)));
+                    burner_updater => rule!(deny_all);
+                })
+                .create_with_no_initial_supply();
+
+            // Create the backer shares, issued once by the owner
+            let backer_share_manager = ResourceBuilder::new_integer_non_fungible::<BackerShare>(OwnerRole::None)
+                .metadata(metadata!{
+                    init {
+                        "name" => "NFT Sale Backer Share", locked;
+                        "description" => "Claims an equal part of the sale revenue shared with backers.", locked;
+                    }
+                })
+                .mint_roles(mint_roles!{
+                    minter => rule!(require(global_caller(component_address)));
+                    minter_updater => rule!(deny_all);
+                })
+                .create_with_no_initial_supply();
+
+            // Create the badges of curators managing a consigned part of the collection
+            let listing_manager_manager = ResourceBuilder::new_ruid_non_fungible::<ListingManagerBadge>(OwnerRole::None)
+                .metadata(metadata!{
+                    init {
+                        "name" => "NFT Sale Listing Manager Badge", locked;
+                        "tags" => "badge", locked;
+                    }
+                })
+                .mint_roles(mint_roles!{
+                    minter => rule!(require(global_caller(component_address)));
+                    minter_updater => rule!(deny_all);
+                })
+                .recall_roles(recall_roles!{
+                    // Owner can take a curator's badge back
+                    recaller => owner_rule.clone();
+                    recaller_updater => rule!(deny_all);
+                })
+                .create_with_no_initial_supply();
+
+            // Create the transferable affiliate links, each carrying its own commission rate
+            let affiliate_link_manager = ResourceBuilder::new_ruid_non_fungible::<AffiliateLink>(OwnerRole::None)
+                .metadata(metadata!{
+                    init {
+                        "name" => "NFT Sale Affiliate Link", locked;
+                        "description" => "Earns its holder a commission on the purchases made presenting it.", locked;
+                    }
+                })
+                .mint_roles(mint_roles!{
+                    minter => rule!(require(global_caller(component_address)));
+                    minter_updater => rule!(deny_all);
+                })
+                .create_with_no_initial_supply();
+
+            // Create the badges of keepers and bots, limited to maintenance so a leaked one cannot
+            // move assets
+            let automation_badge_manager = ResourceBuilder::new_ruid_non_fungible::<AutomationBadge>(OwnerRole::None)
+                .metadata(metadata!{
+                    init {
+                        "name" => "NFT Sale Automation Badge", locked;
+                        "tags" => "badge", locked;
+                    }
+                })
+                .mint_roles(mint_roles!{
+                    minter => rule!(require(global_caller(component_address)));
+                    minter_updater => rule!(deny_all);
+                })
+                .recall_roles(recall_roles!{
+                    // Owner can take a leaked badge back
+                    recaller => owner_rule.clone();
+                    recaller_updater => rule!(deny_all);
+                })
+                .create_with_no_initial_supply();
+
+            let delisted_vault = NonFungibleVault::new(nft_vault.resource_address());
+            let held_vault = NonFungibleVault::new(nft_vault.resource_address());
+            let scheduled_vault = NonFungibleVault::new(nft_vault.resource_address());
+            let retry_vault = NonFungibleVault::new(nft_vault.resource_address());
+            let intent_vault = NonFungibleVault::new(nft_vault.resource_address());
+            let component_address = Self {
+                nft_vault,
+                delisted_vault,
+                allowlist_root: None,
+                gated_phases: config.gated_phases.clone(),
+                purchase_cooldown: None,
+                last_purchases: KeyValueStore::new(),
+                scheduled_vault,
+                listing_schedules: KeyValueStore::new(),
+                listing_manager_address: listing_manager_manager.address(),
+                automation_badge_address: automation_badge_manager.address(),
+                managed_ranges: KeyValueStore::new(),
+                treasury: KeyValueStore::new(),
+                treasury_funds: Decimal::zero(),
+                accepted_payment_token: config.accepted_payment_token,
+                price: config.price,
+                max_nfts_per_transaction: config.max_nfts_per_transaction,
+                limit_tiers: Vec::new(),
+                inventory_rules: InventoryRules::default(),
+                payment_divisibility,
+                nft_id_type,
+                minimum_payment: Decimal::zero(),
+                large_purchase_threshold: None,
+                change_policy: config.change_policy,
+                tips: Vault::new(config.accepted_payment_token),
+                admin_badge_address: admin_badge.resource_address(),
+                pricer_badge_address: pricer_badge.resource_address(),
+                voucher_resource_address: voucher_manager.address(),
+                sale_allowed: false,
+                sale_started: false,
+                opening_price: Decimal::zero(),
+                buying_paused: false,
+                pending_recalls: KeyValueStore::new(),
+                next_recall_id: 0,
+                physical_redemptions: KeyValueStore::new(),
+                upgrade_path: None,
+                donation_list: Vec::new(),
+                donated: 0,
+                airdrop_recipients: KeyValueStore::new(),
+                airdrop_length: 0,
+                airdrop_cursor: 0,
+                delivery_locker: None,
+                delivery_policy: DeliveryPolicy::default(),
+                retry_queue: KeyValueStore::new(),
+                retry_length: 0,
+                retry_cursor: 0,
+                retry_vault,
+                retry_queued: 0,
+                deferred_delivery: false,
+                intents: KeyValueStore::new(),
+                intent_length: 0,
+                intent_cursor: 0,
+                intent_vault,
+                intents_queued: 0,
+                allowances: KeyValueStore::new(),
+                allowance_budgets: Decimal::zero(),
+                sale_prices: KeyValueStore::new(),
+                refundable: Decimal::zero(),
+                item_sales: KeyValueStore::new(),
+                refunds_close_at: None,
+                refunds_guaranteed_until: None,
+                insurance_vault: Vault::new(config.accepted_payment_token),
+                insurance_locked_until: None,
+                reveal_deadline: None,
+                finalized: false,
+                platform_fee: config.platform_fee.clone(),
+                max_fee_rate: config.max_fee_rate,
+                max_cohort_adjustment: config.max_cohort_adjustment,
+                max_cohort_bonus: config.max_cohort_bonus,
+                bonus_vault: None,
+                bonus_per_nft: Decimal::zero(),
+                price_cohorts: Vec::new(),
+                loyalty_resource_address: loyalty_manager.address(),
+                cashback_rate: Decimal::zero(),
+                stake_vault: Vault::new(config.accepted_payment_token),
+                stake_receipt_address: stake_receipt_manager.address(),
+                stake_discount: None,
+                loyalty_discount: None,
+                vip_badge_address: vip_badge_manager.address(),
+                mint_pass_address: mint_pass_manager.address(),
+                pass_window: None,
+                reserved_for_passes: 0,
+                escrows: KeyValueStore::new(),
+                escrow_receipt_address: escrow_receipt_manager.address(),
+                next_escrow_id: 0,
+                escrows_converted: 0,
+                escrowed_funds: Decimal::zero(),
+                hold_terms: None,
+                holds: KeyValueStore::new(),
+                held_vault,
+                hold_receipt_address: hold_receipt_manager.address(),
+                next_hold_id: 0,
+                held_deposits: Decimal::zero(),
+                open_holds: 0,
+                backer_share_address: backer_share_manager.address(),
+                backer_shares: 0,
+                revenue_share: Decimal::zero(),
+                revenue_share_vault: Vault::new(config.accepted_payment_token),
+                revenue_per_share: Decimal::zero(),
+                revenue_claimed: KeyValueStore::new(),
+                revenue_claimed_total: Decimal::zero(),
+                affiliate_link_address: affiliate_link_manager.address(),
+                commissions: KeyValueStore::new(),
+                commission_vault: Vault::new(config.accepted_payment_token),
+                commissions_owed: Decimal::zero(),
+                price_change_limit: None,
+                last_price_change: None,
+                audit_log: KeyValueStore::new(),
+                audit_log_length: 0,
+                stocked_ids: KeyValueStore::new(),
+                stocked_positions: KeyValueStore::new(),
+                stocked_length: 0,
+                total_revenue: Decimal::zero(),
+                total_units: 0,
+                total_refunds: Decimal::zero(),
+                accounting_periods: KeyValueStore::new(),
+                accounting_period_count: 0,
+                buyers: KeyValueStore::new(),
+                buyer_count: 0,
+                buyer_totals: KeyValueStore::new(),
+                period_opened_at: Runtime::current_epoch(),
+            }
+            .instantiate()
+            .prepare_to_globalize(owner_role)
+            .with_address(address_reservation)
+            .metadata(metadata!{
+                roles {
+                    // Branding goes through the owner methods below, which call back into the
+                    // metadata module as the component itself
+                    metadata_setter => rule!(require(global_caller(component_address)));
+                    metadata_setter_updater => rule!(deny_all);
+                    metadata_locker => owner_rule;
+                    metadata_locker_updater => rule!(deny_all);
+                },
+                init {
+                    // Published for other components, kept in sync by every price change
+                    "price" => config.price, updatable;
+                    "payment_token" => GlobalAddress::from(config.accepted_payment_token), locked;
+                    // The buyers' guarantee on fees, which no setter can exceed
+                    "max_fee_rate" => config.max_fee_rate, locked;
+                }
+            })
+            .enable_component_royalties(component_royalties! {
+                // Only public methods can ever carry a royalty, the owner and admins never pay
+                // the platform to manage their own sale and users never pay to get back what is
+                // theirs
+                init {
+                    add_nfts_to_vault => Free, locked;
+                    set_inventory_rules => Free, locked;
+                    delist => Free, locked;
+                    relist => Free, locked;
+                    schedule_listing => Free, locked;
+                    issue_listing_manager => Free, locked;
+                    issue_affiliate_link => Free, locked;
+                    issue_automation_badge => Free, locked;
+                    buy_via_affiliate => config.buy_royalty, updatable;
+                    claim_commission => Free, locked;
+                    commission_owed => Free, updatable;
+                    set_managed_ranges => Free, locked;
+                    manage_delist => Free, updatable;
+                    manage_relist => Free, updatable;
+                    manage_listing => Free, updatable;
+                    listing_price => Free, updatable;
+                    listing_price_at => Free, updatable;
+                    buy_specific => config.buy_royalty, updatable;
+                    grant_allowance => Free, locked;
+                    deposit_budget => Free, updatable;
+                    pull_nfts => config.buy_royalty, updatable;
+                    withdraw_budget => Free, locked;
+                    allowance => Free, updatable;
+                    start_sale => Free, locked;
+                    end_sale => Free, locked;
+                    buy => config.buy_royalty, updatable;
+                    buy_tagged => config.buy_royalty, updatable;
+                    item_history => Free, updatable;
+                    buy_vip => config.buy_royalty, updatable;
+                    grant_vip => Free, locked;
+                    rename_admin_badge => Free, locked;
+                    buy_for => config.buy_royalty, updatable;
+                    buy_allowlisted => config.buy_royalty, updatable;
+                    set_allowlist_root => Free, locked;
+                    buy_as => config.buy_royalty, updatable;
+                    buy_gated => config.buy_royalty, updatable;
+                    set_gated_phases => Free, locked;
+                    set_purchase_cooldown => Free, locked;
+                    buy_vouchers => Free, updatable;
+                    escrow => Free, updatable;
+                    claim_escrow => Free, locked;
+                    convert_escrows => Free, locked;
+                    set_hold_terms => Free, locked;
+                    hold => Free, updatable;
+                    complete_hold => config.buy_royalty, updatable;
+                    release_hold => Free, locked;
+                    reclaim_hold => Free, locked;
+                    redeem_physical => Free, locked;
+                    set_upgrade_path => Free, locked;
+                    burn_to_redeem => Free, updatable;
+                    cancel_sale => Free, locked;
+                    finalize => Free, locked;
+                    claim_refund => Free, locked;
+                    lock_insurance => Free, locked;
+                    withdraw_insurance => Free, locked;
+                    reveal => Free, locked;
+                    slash_insurance => Free, locked;
+                    insurance => Free, updatable;
+                    deposit_bonus => Free, locked;
+                    set_bonus_per_nft => Free, locked;
+                    set_price_cohorts => Free, locked;
+                    price_cohorts => Free, updatable;
+                    withdraw_bonus => Free, locked;
+                    set_cashback_rate => Free, locked;
+                    set_minimum_payment => Free, locked;
+                    set_large_purchase_threshold => Free, locked;
+                    set_stake_discount => Free, locked;
+                    set_loyalty_discount => Free, locked;
+                    buy_returning => config.buy_royalty, updatable;
+                    mint_passes => Free, locked;
+                    issue_backer_shares => Free, locked;
+                    claim_revenue_share => Free, locked;
+                    set_pass_window => Free, locked;
+                    redeem_passes => config.buy_royalty, updatable;
+                    stake => Free, updatable;
+                    unstake => Free, locked;
+                    buy_staked => config.buy_royalty, updatable;
+                    gift_from_vault => Free, locked;
+                    set_donation_list => Free, locked;
+                    donate_unsold => Free, locked;
+                    add_airdrop_recipients => Free, locked;
+                    airdrop_batch => Free, locked;
+                    set_deferred_delivery => Free, locked;
+                    fulfil => Free, locked;
+                    delivery_locker => Free, updatable;
+                    set_delivery_policy => Free, locked;
+                    retry_deliveries => Free, locked;
+                    schedule_recall => Free, locked;
+                    cancel_recall => Free, locked;
+                    recall_item => Free, locked;
+                    set_limit_tiers => Free, locked;
+                    configure => Free, locked;
+                    max_per_transaction => Free, updatable;
+                    set_dapp_definition => Free, locked;
+                    set_info_url => Free, locked;
+                    set_social_urls => Free, locked;
+                    withdraw_profits => Free, locked;
+                    treasury_breakdown => Free, updatable;
+                    verify_invariants => Free, updatable;
+                    withdraw_tips => Free, locked;
+                    audit_log => Free, updatable;
+                    close_accounting_period => Free, locked;
+                    accounting_periods => Free, updatable;
+                    export_buyers => Free, updatable;
+                    current_phase_info => Free, updatable;
+                    export_state_snapshot => Free, updatable;
+                    withdraw_from_treasury => Free, locked;
+                    change_price => Free, locked;
+                    override_price => Free, locked;
+                    set_price_change_limit => Free, locked;
+                    price => Free, updatable;
+                    is_sold => Free, updatable;
+                    pause_buying => Free, locked;
+                    resume_buying => Free, locked;
+                }
+            })
+            .roles(roles!(
+                admin => rule!(require(admin_badge.resource_address()));
+                pricer => rule!(require(pricer_badge.resource_address()));
+                vip => rule!(require(vip_badge_manager.address()));
+                // Nobody until the owner designates a guardian with 'set_role'
+                guardian => rule!(deny_all);
+                automation => rule!(require(automation_badge_manager.address()));
+            ))
+            .globalize();
+
+            (component_address, owner_badge, admin_badge, pricer_badge)
+        }
+
+        // All checks on instantiation parameters live here. Returns the payment token divisibility
+        // and the collection's id type so they never have to be looked up again
+        fn validate_config(config: &SaleConfig) -> (u8, NonFungibleIdType) {
+            let nft_id_type = match ResourceManager::from_address(config.nft_resource_address).resource_type() {
+                ResourceType::NonFungible { id_type } => id_type,
+                ResourceType::Fungible { divisibility: _ } => panic!(
+                    "{} Only non fungible resources can be sold.",
+                    SaleError::InvalidConfiguration
+                ),
+            };
+            let payment_divisibility = match ResourceManager::from_address(config.accepted_payment_token).resource_type() {
+                ResourceType::Fungible { divisibility } => divisibility,
+                ResourceType::NonFungible { id_type: _ } => panic!(
+                    "{} Only payments of fungible resources are accepted.",
+                    SaleError::InvalidConfiguration
+                ),
+            };
+            Self::validate_price(config.price, payment_divisibility);
+            assert!(
+                config.max_nfts_per_transaction > 0,
+                "{} At least one NFT must be purchasable per transaction.",
+                SaleError::InvalidConfiguration
+            );
+            assert!(
+                config.max_fee_rate >= Decimal::zero() && config.max_fee_rate <= Decimal::one(),
+                "{} The maximum fee rate must be between 0 and 1.",
+                SaleError::InvalidConfiguration
+            );
+            if let Some(fee) = &config.platform_fee {
+                assert!(
+                    fee.rate >= Decimal::zero() && fee.rate <= config.max_fee_rate,
+                    "{} The platform fee rate must be between 0 and the maximum fee rate.",
+                    SaleError::InvalidConfiguration
+                );
+            }
+            assert!(
+                config.max_cohort_adjustment >= Decimal::zero()
+                    && config.max_cohort_adjustment < Decimal::one(),
+                "{} The maximum cohort adjustment must be at least 0 and below 1.",
+                SaleError::InvalidConfiguration
+            );
+            assert!(
+                config.max_cohort_bonus >= Decimal::zero(),
+                "{} The maximum cohort bonus cannot be negative.",
+                SaleError::InvalidConfiguration
+            );
+            assert!(
+                config.gated_phases.windows(2).all(|pair| pair[0].starts_at < pair[1].starts_at),
+                "{} Gated phases must be given in the order they start.",
+                SaleError::InvalidConfiguration
+            );
+            (payment_divisibility, nft_id_type)
+        }
+
+        // A valid price is never negative and representable in the payment token, so multiples of
+        // it can be taken from a payment bucket without further checks
+        fn validate_price(price: Decimal, payment_divisibility: u8) {
+            assert!(
+                price >= Decimal::zero(),
+                "{} The price cannot be less then ZERO!",
+                SaleError::InvalidPrice
+            );
+            assert!(
+                price.checked_round(payment_divisibility, RoundingMode::ToZero) == Some(price),
+                "{} The price cannot have more than {} decimal places.",
+                SaleError::InvalidPrice,
+                payment_divisibility
+            );
+        }
+
+        // Add nfts to the nft vault after instatiation for testing methods
+        pub fn add_nfts_to_vault(
+            &mut self,
+            badge: Option<Proof>,
+            nft_deposit_bucket: NonFungibleBucket,
+        ) {
+            let authority = self.presented(badge, &["admin", "OWNER"], "Add NFTs");
+            self.assert_not_finalized("Add NFTs");
+            self.record(
+                "add_nfts_to_vault",
+                authority,
+                nft_deposit_bucket.amount().to_string(),
+            );
+            let nft_ids = nft_deposit_bucket.non_fungible_local_ids();
+
+            // Enforce the owner's inventory rules before accepting the deposit. The cap counts
+            // every NFT the sale holds, whether listed, delisted, scheduled or held
+            if let Some(max_inventory) = self.inventory_rules.max_inventory {
+                let inventory = self.nft_vault.amount()
+                    + self.delisted_vault.amount()
+                    + self.scheduled_vault.amount()
+                    + self.held_vault.amount();
+                assert!(
+                    inventory + nft_deposit_bucket.amount() <= Decimal::from(max_inventory),
+                    "{} [Add NFTs]: The sale may hold at most {} NFTs.",
+                    SaleError::InventoryRuleViolation,
+                    max_inventory
+                );
+            }
+            // Reserved ranges are integer ranges, an id they cannot tell about is refused
+            let reserved_ranges = &self.inventory_rules.reserved_ranges;
+            for nft_id in nft_ids.iter().filter(|_| !reserved_ranges.is_empty()) {
+                let reserved = match nft_id {
+                    NonFungibleLocalId::Integer(id) => reserved_ranges
+                        .iter()
+                        .any(|(first, last)| (*first..=*last).contains(&id.value())),
+                    _ => true,
+                };
+                assert!(
+                    !reserved,
+                    "{} [Add NFTs]: NFT {} is reserved for another channel.",
+                    SaleError::InventoryRuleViolation,
+                    nft_id
+                );
+            }
+
+            // Add the bucket to the vault
+            for nft_id in &nft_ids {
+                if self.stocked_positions.get(nft_id).is_none() {
+                    self.stocked_positions.insert(nft_id.clone(), self.stocked_length);
+                    self.stocked_ids.insert(self.stocked_length, nft_id.clone());
+                    self.stocked_length += 1;
+                }
+            }
+            self.nft_vault.put(nft_deposit_bucket);
+            Runtime::emit_event(InventoryChangedEvent {
+                added: nft_ids,
+                inventory: self.nft_vault.amount(),
+            });
+        }
+
+        // Take specific NFTs off sale without withdrawing them from the component
+        pub fn delist(&mut self, badge: Option<Proof>, nft_ids: IndexSet<NonFungibleLocalId>) {
+            let authority = self.presented(badge, &["admin", "OWNER"], "Delist");
+            self.delist_as(authority, nft_ids);
+        }
+
+        fn delist_as(&mut self, authority: Authority, nft_ids: IndexSet<NonFungibleLocalId>) {
+            self.assert_not_finalized("Delist");
+            self.record("delist", authority, format!("{:?}", nft_ids));
+            self.check_unreserved(nft_ids.len().into(), "Delist");
+            self.delisted_vault.put(self.nft_vault.take_non_fungibles(&nft_ids));
+            Runtime::emit_event(ListingChangedEvent { nft_ids, listed: false });
+        }
+
+        // Put delisted NFTs back on sale
+        pub fn relist(&mut self, badge: Option<Proof>, nft_ids: IndexSet<NonFungibleLocalId>) {
+            let authority = self.presented(badge, &["admin", "OWNER"], "Relist");
+            self.relist_as(authority, nft_ids);
+        }
+
+        fn relist_as(&mut self, authority: Authority, nft_ids: IndexSet<NonFungibleLocalId>) {
+            self.assert_not_finalized("Relist");
+            self.record("relist", authority, format!("{:?}", nft_ids));
+            self.nft_vault.put(self.delisted_vault.take_non_fungibles(&nft_ids));
+            Runtime::emit_event(ListingChangedEvent { nft_ids, listed: true });
+        }
+
+        // Sell an NFT of the vault on its own declining price schedule instead of the flat price,
+        // or put it back at the flat price with None
+        pub fn schedule_listing(
+            &mut self,
+            badge: Option<Proof>,
+            nft_id: NonFungibleLocalId,
+            schedule: Option<PriceSchedule>,
+        ) {
+            let authority = self.presented(badge, &["admin", "OWNER"], "Schedule Listing");
+            self.schedule_listing_as(authority, nft_id, schedule);
+        }
+
+        fn schedule_listing_as(
+            &mut self,
+            authority: Authority,
+            nft_id: NonFungibleLocalId,
+            schedule: Option<PriceSchedule>,
+        ) {
+            self.assert_not_finalized("Schedule Listing");
+            self.record("schedule_listing", authority, format!("{} {:?}", nft_id, schedule));
+            match schedule {
+                Some(schedule) => {
+                    let PriceSchedule { start_price, floor_price, decline_per_epoch, .. } = schedule;
+                    for price in [start_price, floor_price, decline_per_epoch] {
+                        Self::validate_price(price, self.payment_divisibility);
+                    }
+                    assert!(
+                        floor_price > Decimal::zero() && floor_price <= start_price,
+                        "{} [Schedule Listing]: The floor price must be above 0 and at most the start price.",
+                        SaleError::InvalidPrice
+                    );
+                    if self.listing_schedules.get(&nft_id).is_none() {
+                        self.check_unreserved(Decimal::one(), "Schedule Listing");
+                        self.scheduled_vault.put(self.nft_vault.take_non_fungible(&nft_id));
+                    }
+                    self.listing_schedules.insert(nft_id, schedule);
+                }
+                None => {
+                    self.listing_schedules
+                        .remove(&nft_id)
+                        .unwrap_or_else(|| {
+                            panic!(
+                                "{} [Schedule Listing]: The NFT is not on a price schedule.",
+                                SaleError::NotFound
+                            )
+                        });
+                    self.nft_vault.put(self.scheduled_vault.take_non_fungible(&nft_id));
+                }
+            }
+        }
+
+        // Mint a listing manager badge for a curator, managing the NFTs of the given id ranges
+        pub fn issue_listing_manager(
+            &mut self,
+            name: String,
+            ranges: Vec<(u64, u64)>,
+        ) -> NonFungibleBucket {
+            self.assert_not_finalized("Issue Listing Manager");
+            self.record(
+                "issue_listing_manager",
+                Authority::role("OWNER"),
+                format!("{} {:?}", name, ranges),
+            );
+            assert!(
+                self.integer_ids(),
+                "{} [Issue Listing Manager]: Managed ranges need a collection with integer ids.",
+                SaleError::InvalidConfiguration
+            );
+            let badge = ResourceManager::from_address(self.listing_manager_address)
+                .mint_ruid_non_fungible(ListingManagerBadge { name })
+                .as_non_fungible();
+            self.set_ranges(badge.non_fungible_local_id(), ranges);
+            badge
+        }
+
+        // Mint an automation badge for a keeper or bot, e.g. one converting escrows or retrying
+        // deliveries on a schedule
+        pub fn issue_automation_badge(&mut self, name: String) -> NonFungibleBucket {
+            self.assert_not_finalized("Issue Automation Badge");
+            self.record("issue_automation_badge", Authority::role("OWNER"), name.clone());
+            ResourceManager::from_address(self.automation_badge_address)
+                .mint_ruid_non_fungible(AutomationBadge { name })
+                .as_non_fungible()
+        }
+
+        // Replace the id ranges a listing manager badge manages, no ranges revoking the badge
+        pub fn set_managed_ranges(
+            &mut self,
+            badge_id: NonFungibleLocalId,
+            ranges: Vec<(u64, u64)>,
+        ) {
+            self.assert_not_finalized("Set Managed Ranges");
+            self.record(
+                "set_managed_ranges",
+                Authority::role("OWNER"),
+                format!("{} {:?}", badge_id, ranges),
+            );
+            assert!(
+                self.managed_ranges.get(&badge_id).is_some(),
+                "{} [Set Managed Ranges]: No listing manager badge {} was issued.",
+                SaleError::NotFound,
+                badge_id
+            );
+            self.set_ranges(badge_id, ranges);
+        }
+
+        fn set_ranges(&mut self, badge_id: NonFungibleLocalId, ranges: Vec<(u64, u64)>) {
+            assert!(
+                ranges.iter().all(|(first, last)| first <= last),
+                "{} [Set Managed Ranges]: Managed id ranges must not be empty.",
+                SaleError::InvalidConfiguration
+            );
+            self.managed_ranges.insert(badge_id, ranges);
+        }
+
+        // 'delist' for the holder of a listing manager badge, limited to its ranges
+        pub fn manage_delist(
+            &mut self,
+            badge: NonFungibleProof,
+            nft_ids: IndexSet<NonFungibleLocalId>,
+        ) {
+            let authority = self.check_managed(badge, &nft_ids, "Manage Delist");
+            self.delist_as(authority, nft_ids);
+        }
+
+        // 'relist' for the holder of a listing manager badge, limited to its ranges
+        pub fn manage_relist(
+            &mut self,
+            badge: NonFungibleProof,
+            nft_ids: IndexSet<NonFungibleLocalId>,
+        ) {
+            let authority = self.check_managed(badge, &nft_ids, "Manage Relist");
+            self.relist_as(authority, nft_ids);
+        }
+
+        // 'schedule_listing' for the holder of a listing manager badge, limited to its ranges
+        pub fn manage_listing(
+            &mut self,
+            badge: NonFungibleProof,
+            nft_id: NonFungibleLocalId,
+            schedule: Option<PriceSchedule>,
+        ) {
+            let authority = self.check_managed(badge, &indexset!(nft_id.clone()), "Manage Listing");
+            self.schedule_listing_as(authority, nft_id, schedule);
+        }
+
+        // Checks the NFTs are all within the ranges of the one listing manager badge presented and
+        // returns that badge as the authority the call is made under
+        fn check_managed(
+            &self,
+            badge: NonFungibleProof,
+            nft_ids: &IndexSet<NonFungibleLocalId>,
+            method: &str,
+        ) -> Authority {
+            let badge = badge.check_with_message(
+                self.listing_manager_address,
+                format!(
+                    "{} [{}]: A listing manager badge of this sale must be presented.",
+                    SaleError::WrongResource,
+                    method
+                ),
+            );
+            let badge_ids = badge.non_fungible_local_ids();
+            assert!(
+                badge_ids.len() == 1,
+                "{} [{}]: Exactly one listing manager badge must be presented.",
+                SaleError::WrongResource,
+                method
+            );
+            let badge_id = badge_ids.first().unwrap().clone();
+            let ranges = self
+                .managed_ranges
+                .get(&badge_id)
+                .map(|ranges| ranges.clone())
+                .unwrap_or_default();
+            for nft_id in nft_ids {
+                let managed = match nft_id {
+                    NonFungibleLocalId::Integer(id) => ranges
+                        .iter()
+                        .any(|(first, last)| (*first..=*last).contains(&id.value())),
+                    _ => false,
+                };
+                assert!(
+                    managed,
+                    "{} [{}]: NFT {} is outside the ranges the badge manages.",
+                    SaleError::NotManaged,
+                    method,
+                    nft_id
+                );
+            }
+            let badge_id = NonFungibleGlobalId::new(self.listing_manager_address, badge_id);
+            Authority {
+                role: "listing manager".to_string(),
+                badge: Some(ResourceOrNonFungible::NonFungible(badge_id)),
+            }
+        }
+
+        // Whether the collection on sale uses integer ids, the only ones id ranges apply to
+        fn integer_ids(&self) -> bool {
+            self.nft_id_type == NonFungibleIdType::Integer
+        }
+
+        // The current price of an NFT on a price schedule, None if it is not on one
+        pub fn listing_price(&self, nft_id: NonFungibleLocalId) -> Option<Decimal> {
+            self.listing_price_at(nft_id, Runtime::current_epoch())
+        }
+
+        // The price of an NFT on a price schedule at 'epoch', None if it is not on one. The
+        // schedule can still be changed or withdrawn by the admins before then
+        pub fn listing_price_at(
+            &self,
+            nft_id: NonFungibleLocalId,
+            epoch: Epoch,
+        ) -> Option<Decimal> {
+            self.listing_schedules.get(&nft_id).map(|schedule| schedule.price_at(epoch))
+        }
+
+        // Buy an NFT on a price schedule at its current price. Returns the change and the NFT
+        pub fn buy_specific(
+            &mut self,
+            mut payment: Bucket,
+            nft_id: NonFungibleLocalId,
+        ) -> (Bucket, NonFungibleBucket) {
+            assert!(
+                self.sale_allowed,
+                "{} [Buy Specific]: Sale is not allowed yet. Please wait until the sale starts.",
+                SaleError::SaleNotOpen
+            );
+            self.assert_buying_open("Buy Specific");
+            self.admit(Buyer::Public);
+            let price = self
+                .listing_price(nft_id.clone())
+                .unwrap_or_else(|| {
+                    panic!(
+                        "{} [Buy Specific]: The NFT is not on a price schedule.",
+                        SaleError::NotForSale
+                    )
+                });
+            let starts_at = self.listing_schedules.get(&nft_id).unwrap().starts_at;
+            assert!(
+                Runtime::current_epoch() >= starts_at,
+                "{} [Buy Specific]: The listing opens at epoch {}.",
+                SaleError::OutsideWindow,
+                starts_at.number()
+            );
+            assert_eq!(
+                payment.resource_address(),
+                self.accepted_payment_token,
+                "{} [Buy Specific]: Payment is only accepted in {:?}",
+                SaleError::WrongPaymentToken,
+                self.accepted_payment_token
+            );
+            let settlement = self.take_payment(&mut payment, price, "Buy Specific");
+
+            let fee = self.platform_cut(price);
+            self.collect(settlement, fee, "Buy Specific");
+            self.record_sale(nft_id.clone(), price);
+            self.listing_schedules.remove(&nft_id);
+            let nft = self.scheduled_vault.take_non_fungible(&nft_id);
+
+            self.announce_sale(
+                None,
+                NftsSoldEvent {
+                    nft_ids: nft.non_fungible_local_ids(),
+                    revenue: price,
+                    recipient: None,
+                    sponsor: None,
+                    tag: None,
+                    cohort: None,
+                },
+            );
+            (payment, nft)
+        }
+
+        // Replace the rules deposits into the sale vault have to follow
+        pub fn set_inventory_rules(&mut self, inventory_rules: InventoryRules) {
+            self.assert_not_finalized("Set Inventory Rules");
+            self.record(
+                "set_inventory_rules",
+                Authority::role("OWNER"),
+                format!("{:?}", inventory_rules),
+            );
+            assert!(
+                inventory_rules.reserved_ranges.iter().all(|(first, last)| first <= last),
+                "{} [Set Inventory Rules]: Reserved id ranges must not be empty.",
+                SaleError::InvalidConfiguration
+            );
+            assert!(
+                inventory_rules.reserved_ranges.is_empty() || self.integer_ids(),
+                "{} [Set Inventory Rules]: Reserved id ranges need a collection with integer ids.",
+                SaleError::InvalidConfiguration
+            );
+            self.inventory_rules = inventory_rules;
+        }
+
+
+        // Set the bool to true so the sale can begin
+        pub fn start_sale(&mut self, badge: Option<Proof>) {
+            let authority = self.presented(badge, &["admin", "OWNER"], "Start Sale");
+            self.start_sale_as(authority);
+        }
+
+        fn start_sale_as(&mut self, authority: Authority) {
+            self.assert_not_finalized("Start Sale");
+            self.record("start_sale", authority, String::new());
+            assert!(
+                self.refunds_close_at.is_none(),
+                "{} [Start Sale]: A cancelled sale cannot be restarted.",
+                SaleError::SaleCancelled
+            );
+            self.assert_refunds_guaranteed("Start Sale");
+            if !self.sale_started {
+                self.opening_price = self.price;
+            }
+            self.sale_allowed = true;
+            self.sale_started = true;
+            self.refunds_guaranteed_until = None;
+        }
+
+        // Circuit breaker for the guardian, which can stop purchases but change nothing else
+        pub fn pause_buying(&mut self) {
+            self.assert_not_finalized("Pause Buying");
+            self.record("pause_buying", Authority::role("guardian"), String::new());
+            self.buying_paused = true;
+        }
+
+        pub fn resume_buying(&mut self) {
+            self.assert_not_finalized("Resume Buying");
+            self.record("resume_buying", Authority::role("guardian"), String::new());
+            self.buying_paused = false;
+        }
+
+        fn assert_buying_open(&self, method: &str) {
+            assert!(
+                !self.buying_paused,
+                "{} [{}]: Purchases are paused by the sale's guardian.",
+                SaleError::BuyingPaused,
+                method
+            );
+        }
+
+        // Set the bool back to false so the sale will no longer be available
+        pub fn end_sale(&mut self, badge: Option<Proof>) {
+            let authority = self.presented(badge, &["admin", "OWNER"], "End Sale");
+            self.end_sale_as(authority);
+        }
+
+        fn end_sale_as(&mut self, authority: Authority) {
+            self.assert_not_finalized("End Sale");
+            self.record("end_sale", authority, String::new());
+            self.sale_allowed = false;
+            if self.refunds_guaranteed_until.is_none() {
+                self.refunds_guaranteed_until =
+                    Some(Runtime::current_epoch().after(REFUND_WINDOW_EPOCHS).unwrap());
+            }
+        }
+
+        // Buy the specified number of NFTs and supply payment. Returns the change, the NFTs and any
+        // purchase rewards such as the bonus token
+        pub fn buy(
+            &mut self,
+            payment: Bucket,
+            number_of_nfts: u16,
+        ) -> (Bucket, NonFungibleBucket, Vec<Bucket>) {
+            self.sell(payment, number_of_nfts, self.price, Buyer::Public, Attribution::default())
+        }
+
+        // Buy like 'buy' with a tag attributing the purchase to a channel, e.g. a campaign code
+        // or storefront id, recorded in the purchase's event for marketing reports
+        pub fn buy_tagged(
+            &mut self,
+            payment: Bucket,
+            number_of_nfts: u16,
+            tag: String,
+        ) -> (Bucket, NonFungibleBucket, Vec<Bucket>) {
+            assert!(
+                tag.len() <= MAX_TAG_LENGTH,
+                "{} [Buy Tagged]: Tags are limited to {} bytes.",
+                SaleError::InvalidTag,
+                MAX_TAG_LENGTH
+            );
+            let attribution = Attribution {
+                tag: Some(tag),
+                ..Default::default()
+            };
+            self.sell(payment, number_of_nfts, self.price, Buyer::Public, attribution)
+        }
+
+        // Buy as a member of the allowlist, proven by the Merkle proof of 'account', which has to
+        // authorize the transaction. The only way to buy at the flat price while an allowlist is set
+        pub fn buy_allowlisted(
+            &mut self,
+            payment: Bucket,
+            number_of_nfts: u16,
+            account: Global<Account>,
+            proof: Vec<Hash>,
+        ) -> (Bucket, NonFungibleBucket, Vec<Bucket>) {
+            Runtime::assert_access_rule(account.get_owner_role().rule);
+            if let Some(root) = self.allowlist_root {
+                assert!(
+                    verify_allowlist_proof(root, account.address(), &proof),
+                    "{} [Buy Allowlisted]: {:?} is not on the allowlist.",
+                    SaleError::NotAllowlisted,
+                    account.address()
+                );
+            }
+            let buyer = Buyer::Allowlisted(account.address());
+            self.sell(payment, number_of_nfts, self.price, buyer, Attribution::default())
+        }
+
+        // Restrict flat-price purchases to the members of an allowlist committed to by its
+        // Merkle root, or open them to everyone again with None. VIP purchases are not affected
+        pub fn set_allowlist_root(&mut self, allowlist_root: Option<Hash>) {
+            self.assert_not_finalized("Set Allowlist Root");
+            self.record(
+                "set_allowlist_root",
+                Authority::role("OWNER"),
+                format!("{:?}", allowlist_root),
+            );
+            self.allowlist_root = allowlist_root;
+        }
+
+        // Buy during a gated phase by presenting a badge it accepts. Gated buyers are not exempt
+        // from the allowlist, so while one is set flat-price purchases go through buy_allowlisted
+        pub fn buy_gated(
+            &mut self,
+            payment: Bucket,
+            number_of_nfts: u16,
+            badge: Proof,
+        ) -> (Bucket, NonFungibleBucket, Vec<Bucket>) {
+            // Badges can be any resource, 'sell' checks this one against the phases
+            let buyer = Buyer::Gated(badge.skip_checking().resource_address());
+            self.sell(payment, number_of_nfts, self.price, buyer, Attribution::default())
+        }
+
+        // Split the sale into named phases gated by the badges buyers already hold, or remove
+        // them with an empty list. VIP purchases are not affected
+        pub fn set_gated_phases(&mut self, gated_phases: Vec<GatedPhase>) {
+            self.assert_not_finalized("Set Gated Phases");
+            self.record(
+                "set_gated_phases",
+                Authority::role("OWNER"),
+                format!("{:?}", gated_phases),
+            );
+            assert!(
+                gated_phases.windows(2).all(|pair| pair[0].starts_at < pair[1].starts_at),
+                "{} [Set Gated Phases]: Phases must be given in the order they start.",
+                SaleError::InvalidConfiguration
+            );
+            self.gated_phases = gated_phases;
+        }
+
+        // Index of the gated phase under way, None before the first one starts
+        fn current_gated_phase(&self) -> Option<usize> {
+            let now = Runtime::current_epoch();
+            self.gated_phases.iter().rposition(|phase| phase.starts_at <= now)
+        }
+
+        // Admit buyers holding a badge of the current or an earlier gated phase, or anyone once a
+        // phase without a badge is under way
+        fn check_gated_phase(&self, buyer: Buyer) {
+            if buyer == Buyer::Vip {
+                return;
+            }
+            if self.gated_phases.is_empty() {
+                assert!(
+                    !matches!(buyer, Buyer::Gated(_)),
+                    "{} [Buy Gated]: This sale has no gated phases.",
+                    SaleError::NotOffered
+                );
+                return;
+            }
+            // Only the badges of the phases count, whatever phase is under way
+            if let Buyer::Gated(badge) = buyer {
+                assert!(
+                    self.gated_phases.iter().any(|phase| phase.badge == Some(badge)),
+                    "{} [Buy Gated]: {:?} is not the badge of any gated phase.",
+                    SaleError::WrongResource,
+                    badge
+                );
+            }
+            let current = self.current_gated_phase().unwrap_or_else(|| {
+                panic!(
+                    "{} [Buy]: The first phase starts at epoch {}.",
+                    SaleError::OutsideWindow,
+                    self.gated_phases[0].starts_at.number()
+                )
+            });
+            let phase = &self.gated_phases[current];
+            let admitted = phase.badge.is_none()
+                || match buyer {
+                    Buyer::Gated(badge) => self.gated_phases[..=current]
+                        .iter()
+                        .any(|phase| phase.badge == Some(badge)),
+                    _ => false,
+                };
+            assert!(
+                admitted,
+                "{} [Buy]: The {} phase is open to holders of its badges only.",
+                SaleError::NotAllowlisted,
+                phase.name
+            );
+        }
+
+        // Buy as 'account', which has to authorize the transaction. The only way to buy at the
+        // flat price while a purchase cooldown is set, as anonymous purchases could not be throttled
+        pub fn buy_as(
+            &mut self,
+            payment: Bucket,
+            number_of_nfts: u16,
+            account: Global<Account>,
+        ) -> (Bucket, NonFungibleBucket, Vec<Bucket>) {
+            Runtime::assert_access_rule(account.get_owner_role().rule);
+            let buyer = Buyer::Account(account.address());
+            self.sell(payment, number_of_nfts, self.price, buyer, Attribution::default())
+        }
+
+        // Let every account buy at most once per the given number of epochs, or lift the cooldown
+        // with None. Unlike the per-transaction cap it slows down repeated purchases: while it is
+        // set, only purchases naming an account with 'buy_as' or proven on the allowlist go
+        // through, and VIP purchases are not affected
+        pub fn set_purchase_cooldown(&mut self, purchase_cooldown: Option<u64>) {
+            self.assert_not_finalized("Set Purchase Cooldown");
+            self.record(
+                "set_purchase_cooldown",
+                Authority::role("OWNER"),
+                format!("{:?}", purchase_cooldown),
+            );
+            assert!(
+                purchase_cooldown != Some(0),
+                "{} [Set Purchase Cooldown]: The cooldown must last at least one epoch.",
+                SaleError::InvalidConfiguration
+            );
+            self.purchase_cooldown = purchase_cooldown;
+        }
+
+        // Buy without the per-transaction cap, for market makers and partners the owner granted
+        // the vip role to, e.g. by handing out VIP badges
+        pub fn buy_vip(
+            &mut self,
+            payment: Bucket,
+            number_of_nfts: u16,
+        ) -> (Bucket, NonFungibleBucket, Vec<Bucket>) {
+            self.sell(payment, number_of_nfts, self.price, Buyer::Vip, Attribution::default())
+        }
+
+        // Buy at the discounted price unlocked by presenting stake receipts. The discount lasts
+        // for as long as the stakes stay locked, and only while they add up to the minimum stake
+        pub fn buy_staked(
+            &mut self,
+            payment: Bucket,
+            number_of_nfts: u16,
+            stake: NonFungibleProof,
+        ) -> (Bucket, NonFungibleBucket, Vec<Bucket>) {
+            let stake = stake.check_with_message(
+                self.stake_receipt_address,
+                format!(
+                    "{} [Buy Staked]: A stake receipt of this sale must be presented.",
+                    SaleError::WrongResource
+                ),
+            );
+            let terms = self.stake_discount.as_ref().unwrap_or_else(|| {
+                panic!(
+                    "{} [Buy Staked]: Staking discounts are not offered.",
+                    SaleError::NotOffered
+                )
+            });
+            let now = Runtime::current_epoch();
+            let mut staked = Decimal::zero();
+            for receipt in stake.non_fungibles::<StakeReceipt>() {
+                let receipt = receipt.data();
+                assert!(
+                    now < receipt.unlocks_at,
+                    "{} [Buy Staked]: The stake unlocked at epoch {} and earns no discount.",
+                    SaleError::OutsideWindow,
+                    receipt.unlocks_at.number()
+                );
+                staked += receipt.amount;
+            }
+            assert!(
+                staked >= terms.minimum_stake,
+                "{} [Buy Staked]: Stakes of at least {} tokens earn the discount.",
+                SaleError::InsufficientPayment,
+                terms.minimum_stake
+            );
+            let price = (self.price * (Decimal::one() - terms.discount))
+                .checked_round(self.payment_divisibility, RoundingMode::ToZero)
+                .unwrap();
+            self.sell(payment, number_of_nfts, price, Buyer::Public, Attribution::default())
+        }
+
+        // Buy at the loyalty discount by presenting a proof of a resource the sale honors
+        pub fn buy_returning(
+            &mut self,
+            payment: Bucket,
+            number_of_nfts: u16,
+            receipt: Proof,
+        ) -> (Bucket, NonFungibleBucket, Vec<Bucket>) {
+            let terms = self.loyalty_discount.as_ref().unwrap_or_else(|| {
+                panic!(
+                    "{} [Buy Returning]: Loyalty discounts are not offered.",
+                    SaleError::NotOffered
+                )
+            });
+            // Any of the honored resources will do, checked against the list below
+            let receipt = receipt.skip_checking();
+            assert!(
+                terms.honored.contains(&receipt.resource_address()) && !receipt.amount().is_zero(),
+                "{} [Buy Returning]: A purchase from an earlier drop must be proven.",
+                SaleError::WrongResource
+            );
+            let price = (self.price * (Decimal::one() - terms.discount))
+                .checked_round(self.payment_divisibility, RoundingMode::ToZero)
+                .unwrap();
+            self.sell(payment, number_of_nfts, price, Buyer::Public, Attribution::default())
+        }
+
+        // Buy NFTs on behalf of someone else, e.g. a guild paying for its members. The sponsor
+        // account has to authorize the transaction so the purchase can be attributed to it, and
+        // only the change is returned to the caller. What the recipient refuses waits for it in the
+        // delivery locker
+        pub fn buy_for(
+            &mut self,
+            payment: Bucket,
+            number_of_nfts: u16,
+            sponsor: Global<Account>,
+            recipient: Global<Account>,
+        ) -> Bucket {
+            Runtime::assert_access_rule(sponsor.get_owner_role().rule);
+
+            let (change, nfts, rewards) = self.sell(
+                payment,
+                number_of_nfts,
+                self.price,
+                Buyer::Account(sponsor.address()),
+                Attribution {
+                    recipient: Some(recipient.address()),
+                    sponsor: Some(sponsor.address()),
+                    tag: None,
+                    affiliate: None,
+                },
+            );
+            self.hand_over(recipient, nfts.into());
+            for reward in rewards {
+                self.hand_over(recipient, reward);
+            }
+            change
+        }
+
+        // Deposit what was bought into 'recipient', or keep it in the delivery locker for the
+        // account to claim if it refuses the deposit
+        fn hand_over(&mut self, mut recipient: Global<Account>, bucket: Bucket) {
+            if bucket.is_empty() {
+                bucket.drop_empty();
+                return;
+            }
+            if let Some(refused) = recipient.try_deposit_or_refund(bucket, None) {
+                self.locker().store(recipient, refused, false);
+            }
+        }
+
+        // Let 'component' pull up to 'number_of_nfts' NFTs at 'price' each, replacing any earlier
+        // grant. The component's budget is kept, zero NFTs stop it pulling until the next grant
+        pub fn grant_allowance(
+            &mut self,
+            component: ComponentAddress,
+            number_of_nfts: u64,
+            price: Decimal,
+        ) {
+            self.assert_not_finalized("Grant Allowance");
+            self.record(
+                "grant_allowance",
+                Authority::role("OWNER"),
+                format!("{:?} {} at {}", component, number_of_nfts, price),
+            );
+            Self::validate_price(price, self.payment_divisibility);
+            if self.allowances.get(&component).is_some() {
+                let mut allowance = self.allowances.get_mut(&component).unwrap();
+                allowance.remaining = number_of_nfts;
+                allowance.price = price;
+            } else {
+                self.allowances.insert(
+                    component,
+                    Allowance {
+                        remaining: number_of_nfts,
+                        price,
+                        budget: Vault::new(self.accepted_payment_token),
+                    },
+                );
+            }
+        }
+
+        // Fund the purchases of a component holding an allowance
+        pub fn deposit_budget(&mut self, component: ComponentAddress, budget: Bucket) {
+            assert_eq!(
+                budget.resource_address(),
+                self.accepted_payment_token,
+                "{} [Deposit Budget]: Budgets are only accepted in {:?}",
+                SaleError::WrongPaymentToken,
+                self.accepted_payment_token
+            );
+            self.assert_not_finalized("Deposit Budget");
+            let mut allowance = self.allowances.get_mut(&component).unwrap_or_else(|| {
+                panic!(
+                    "{} [Deposit Budget]: {:?} holds no allowance.",
+                    SaleError::NotFound,
+                    component
+                )
+            });
+            self.allowance_budgets += budget.amount();
+            allowance.budget.put(budget);
+        }
+
+        // Called by a component holding an allowance, which is paid for from its budget
+        pub fn pull_nfts(
+            &mut self,
+            component: ComponentAddress,
+            number_of_nfts: u16,
+        ) -> NonFungibleBucket {
+            Runtime::assert_access_rule(rule!(require(global_caller(component))));
+            assert!(
+                self.sale_allowed,
+                "{} [Pull NFTs]: Sale is not allowed yet. Please wait until the sale starts.",
+                SaleError::SaleNotOpen
+            );
+            self.assert_buying_open("Pull NFTs");
+            assert!(
+                number_of_nfts > 0,
+                "{} [Pull NFTs]: At least one NFT has to be pulled.",
+                SaleError::InvalidQuantity
+            );
+            self.check_unreserved(number_of_nfts.into(), "Pull NFTs");
+
+            let mut allowance = self.allowances.get_mut(&component).unwrap_or_else(|| {
+                panic!(
+                    "{} [Pull NFTs]: {:?} holds no allowance.",
+                    SaleError::NotFound,
+                    component
+                )
+            });
+            assert!(
+                u64::from(number_of_nfts) <= allowance.remaining,
+                "{} [Pull NFTs]: Only {} NFTs are left on the allowance.",
+                SaleError::TransactionLimitExceeded,
+                allowance.remaining
+            );
+            let price = allowance.price;
+            let cost = price * number_of_nfts;
+            assert!(
+                allowance.budget.amount() >= cost,
+                "{} [Pull NFTs]: The budget does not cover {} tokens.",
+                SaleError::InsufficientPayment,
+                cost
+            );
+            allowance.remaining -= u64::from(number_of_nfts);
+            let payment = allowance.budget.take(cost);
+            drop(allowance);
+            self.allowance_budgets -= cost;
+
+            let fee_per_nft = self.platform_cut(price);
+            self.collect(payment, fee_per_nft * number_of_nfts, "Pull NFTs");
+            let nfts = self.nft_vault.take(number_of_nfts);
+            let nft_ids = nfts.non_fungible_local_ids();
+            for nft_id in &nft_ids {
+                self.record_sale(nft_id.clone(), price);
+            }
+            self.announce_sale(
+                Some(component),
+                NftsSoldEvent {
+                    nft_ids,
+                    revenue: cost,
+                    recipient: None,
+                    sponsor: Some(component),
+                    tag: None,
+                    cohort: None,
+                },
+            );
+            nfts
+        }
+
+        // Return the unspent budget to the component it was deposited for
+        pub fn withdraw_budget(&mut self, component: ComponentAddress) -> Bucket {
+            Runtime::assert_access_rule(rule!(require(global_caller(component))));
+            let mut allowance = self.allowances.get_mut(&component).unwrap_or_else(|| {
+                panic!(
+                    "{} [Withdraw Budget]: {:?} holds no allowance.",
+                    SaleError::NotFound,
+                    component
+                )
+            });
+            let budget = allowance.budget.take_all();
+            drop(allowance);
+            self.allowance_budgets -= budget.amount();
+            budget
+        }
+
+        pub fn allowance(&self, component: ComponentAddress) -> Option<PurchaseAllowance> {
+            self.allowances.get(&component).map(|allowance| PurchaseAllowance {
+                remaining: allowance.remaining,
+                price: allowance.price,
+                budget: allowance.budget.amount(),
+            })
+        }
+
+        // Shared by every purchase path, the NFTs and rewards are returned to the caller for delivery
+        fn sell(
+            &mut self,
+            mut payment: Bucket,
+            number_of_nfts: u16,
+            price: Decimal,
+            buyer: Buyer,
+            attribution: Attribution,
+        ) -> (Bucket, NonFungibleBucket, Vec<Bucket>) {
+            // Check if the sale is allowed
+            assert!(
+                self.sale_allowed,
+                "{} [Buy]: Sale is not allowed yet. Please wait until the sale starts.",
+                SaleError::SaleNotOpen
+            );
+            self.assert_buying_open("Buy");
+            self.admit(buyer);
+            let cohort = self.cohort_of(buyer);
+            let (price, cohort_bonus) = match cohort {
+                Some(index) => {
+                    let cohort = &self.price_cohorts[index as usize];
+                    let adjusted = (price * (Decimal::one() + cohort.adjustment))
+                        .checked_round(self.payment_divisibility, RoundingMode::ToZero)
+                        .unwrap();
+                    (adjusted, cohort.bonus_per_nft)
+                }
+                None => (price, Decimal::zero()),
+            };
+            // At a price of zero the sale turns into a claim gated by VIP badges and mint passes,
+            // as a public free buy could be repeated by bots until the vault is drained. Whatever
+            // discount led to it, and VIPs claim no more than anyone else may buy at once
+            let free = price.is_zero();
+            assert!(
+                !free || buyer == Buyer::Vip,
+                "{} [Buy]: This sale is free and open to VIP badge holders only.",
+                SaleError::FreeSaleGated
+            );
+            let capped = buyer != Buyer::Vip || free;
+            // Enforce the limit of NFTs per purchase before touching any bucket
+            let max_per_transaction = self.max_per_transaction();
+            assert!(
+                !capped || number_of_nfts <= max_per_transaction,
+                "{} [Buy]: You can only buy a maximum of {} NFTs per transaction.",
+                SaleError::TransactionLimitExceeded,
+                max_per_transaction
+            );
+            // Verify the token supplied is the correct resource, gift vouchers count at face value
+            let payment_token = payment.resource_address();
+            let paid_with_vouchers = payment_token == self.voucher_resource_address;
+            assert!(
+                paid_with_vouchers || payment_token == self.accepted_payment_token,
+                "{} [Buy]: Invalid token provided. Payment is only accepted in {:?}",
+                SaleError::WrongPaymentToken,
+                self.accepted_payment_token
+            );
+
+            // Verify the amount supplied is correct
+            let cost = price * number_of_nfts;
+            let mut settlement = self.take_payment(&mut payment, cost, "Buy");
+
+            // NFTs guaranteed to mint pass holders are off limits until the priority window closes
+            self.check_unreserved(number_of_nfts.into(), "Buy");
+
+            // Take the given number of NFTs specified by the user from the vault
+            let nft = self.nft_vault.take(number_of_nfts);
+
+            // Store the payment for the purchase (without change) in the treasury. Vouchers were
+            // paid for when they were issued, so they are burned instead
+            if paid_with_vouchers {
+                settlement.burn();
+            } else {
+                let cut = self.platform_cut(price) * number_of_nfts;
+                if let Some((link_id, commission_rate)) = &attribution.affiliate {
+                    self.pay_commission(&mut settlement, cut, link_id, *commission_rate);
+                }
+                self.collect(settlement, cut, "Buy");
+            }
+
+            let nft_ids = nft.non_fungible_local_ids();
+            for nft_id in &nft_ids {
+                self.record_sale(nft_id.clone(), price);
+            }
+
+            let account = match buyer {
+                Buyer::Account(account) | Buyer::Allowlisted(account) => Some(account),
+                _ => None,
+            };
+            self.announce_sale(
+                account,
+                NftsSoldEvent {
+                    nft_ids,
+                    revenue: cost,
+                    recipient: attribution.recipient,
+                    sponsor: attribution.sponsor,
+                    tag: attribution.tag,
+                    cohort,
+                },
+            );
+            if let Some(account) = account {
+                self.record_buyer(account, number_of_nfts, cost);
+            }
+
+            let mut rewards = Vec::new();
+            if let Some(bonus) = self.take_bonus(number_of_nfts, cohort_bonus) {
+                rewards.push(bonus);
+            }
+            if self.cashback_rate > Decimal::zero() && cost > Decimal::zero() {
+                let cashback = ResourceManager::from_address(self.loyalty_resource_address)
+                    .mint(cost * self.cashback_rate);
+                rewards.push(cashback);
+            }
+
+            let nft = if self.deferred_delivery {
+                self.defer(nft, buyer, attribution.recipient)
+            } else {
+                nft
+            };
+
+            // Return any excess funds, the bucket of NFTs purchased and the rewards
+            (payment, nft, rewards)
+        }
+
+        // Keep the NFTs of a purchase as an intent for the account behind it, or the recipient it
+        // named, and hand back an empty bucket in their place
+        fn defer(
+            &mut self,
+            nfts: NonFungibleBucket,
+            buyer: Buyer,
+            recipient: Option<ComponentAddress>,
+        ) -> NonFungibleBucket {
+            let account = match buyer {
+                Buyer::Account(account) | Buyer::Allowlisted(account) => account,
+                _ => panic!(
+                    "{} [Buy]: Deliveries are deferred to the buyer's account, use buy_as.",
+                    SaleError::AccountRequired
+                ),
+            };
+            let nft_ids = nfts.non_fungible_local_ids();
+            self.intents_queued += nft_ids.len() as u64;
+            self.intent_vault.put(nfts);
+            self.intents
+                .insert(self.intent_length, (recipient.unwrap_or(account), nft_ids));
+            self.intent_length += 1;
+            NonFungibleBucket::new(self.intent_vault.resource_address())
+        }
+
+        fn record_buyer(&mut self, account: ComponentAddress, units: u16, spend: Decimal) {
+            let (bought, spent) = match self.buyer_totals.get(&account) {
+                Some(totals) => *totals,
+                None => {
+                    self.buyers.insert(self.buyer_count, account);
+                    self.buyer_count += 1;
+                    (0, Decimal::zero())
+                }
+            };
+            self.buyer_totals.insert(account, (bought + u64::from(units), spent + spend));
+        }
+
+        // The gates every buyer passes: the allowlist, the gated phases and the cooldown
+        fn admit(&mut self, buyer: Buyer) {
+            assert!(
+                matches!(buyer, Buyer::Allowlisted(_) | Buyer::Vip)
+                    || self.allowlist_root.is_none(),
+                "{} [Buy]: This sale is open to allowlisted buyers only, use buy_allowlisted.",
+                SaleError::NotAllowlisted
+            );
+            self.check_gated_phase(buyer);
+            self.start_cooldown(buyer);
+        }
+
+        // Throttle the account behind a purchase while a cooldown is set. A purchase naming no
+        // account could be repeated without limit, so it is refused
+        fn start_cooldown(&mut self, buyer: Buyer) {
+            let Some(cooldown) = self.purchase_cooldown else {
+                return;
+            };
+            let account = match buyer {
+                Buyer::Account(account) | Buyer::Allowlisted(account) => account,
+                Buyer::Vip => return,
+                Buyer::Public | Buyer::Gated(_) => panic!(
+                    "{} [Buy]: This sale limits purchases per account, use buy_as.",
+                    SaleError::AccountRequired
+                ),
+            };
+            let now = Runtime::current_epoch();
+            if let Some(last_purchase) = self.last_purchases.get(&account).map(|epoch| *epoch) {
+                let next_purchase = last_purchase.after(cooldown).unwrap();
+                assert!(
+                    now >= next_purchase,
+                    "{} [Buy]: {:?} can buy again at epoch {}.",
+                    SaleError::CooldownActive,
+                    account,
+                    next_purchase.number()
+                );
+            }
+            self.last_purchases.insert(account, now);
+        }
+
+        // Bonus tokens earned by buying the given number of NFTs, with the extra bonus of the
+        // buyer's cohort, as much as is left once the bonus vault runs low. Rounded down to what
+        // the bonus token can represent
+        fn take_bonus(&mut self, number_of_nfts: u16, cohort_bonus: Decimal) -> Option<Bucket> {
+            let bonus_vault = self.bonus_vault.as_mut()?;
+            let divisibility = match bonus_vault.resource_manager().resource_type() {
+                ResourceType::Fungible { divisibility } => divisibility,
+                ResourceType::NonFungible { id_type: _ } => return None,
+            };
+            let bonus_per_nft = self.bonus_per_nft + cohort_bonus;
+            let bonus = (bonus_per_nft * number_of_nfts)
+                .min(bonus_vault.amount())
+                .checked_round(divisibility, RoundingMode::ToZero)
+                .unwrap();
+            if bonus > Decimal::zero() {
+                Some(bonus_vault.take(bonus))
+            } else {
+                None
+            }
+        }
+
+
+        // The pricing cohort of a buyer who named their account, while an experiment runs
+        fn cohort_of(&self, buyer: Buyer) -> Option<u32> {
+            match buyer {
+                Buyer::Account(account) | Buyer::Allowlisted(account)
+                    if !self.price_cohorts.is_empty() =>
+                {
+                    Some(price_cohort(account, self.price_cohorts.len()) as u32)
+                }
+                _ => None,
+            }
+        }
+
+        // Lock payment tokens for a discount on purchases. The receipt unlocks 'buy_staked' and
+        // is exchanged back for the stake with 'unstake' once the lock period is over
+        pub fn stake(&mut self, payment: Bucket) -> NonFungibleBucket {
+            self.assert_not_finalized("Stake");
+            let terms = self
+                .stake_discount
+                .clone()
+                .unwrap_or_else(|| {
+                    panic!(
+                        "{} [Stake]: Staking discounts are not offered.",
+                        SaleError::NotOffered
+                    )
+                });
+            assert_eq!(
+                payment.resource_address(),
+                self.accepted_payment_token,
+                "{} [Stake]: Only {:?} can be staked.",
+                SaleError::WrongPaymentToken,
+                self.accepted_payment_token
+            );
+            assert!(
+                payment.amount() >= terms.minimum_stake,
+                "{} [Stake]: At least {} tokens have to be staked.",
+                SaleError::InsufficientPayment,
+                terms.minimum_stake
+            );
+
+            let receipt = StakeReceipt {
+                amount: payment.amount(),
+                unlocks_at: Runtime::current_epoch().after(terms.lock_epochs).unwrap(),
+            };
+            self.stake_vault.put(payment);
+            ResourceManager::from_address(self.stake_receipt_address)
+                .mint_ruid_non_fungible(receipt)
+                .as_non_fungible()
+        }
+
+        // Return a stake receipt after its lock has expired and get the staked tokens back
+        pub fn unstake(&mut self, receipt: NonFungibleBucket) -> Bucket {
+            assert_eq!(
+                receipt.resource_address(),
+                self.stake_receipt_address,
+                "{} [Unstake]: Only stake receipts of this sale can be returned.",
+                SaleError::WrongResource
+            );
+            let mut amount = Decimal::zero();
+            for nft in receipt.non_fungibles::<StakeReceipt>() {
+                let stake = nft.data();
+                assert!(
+                    Runtime::current_epoch() >= stake.unlocks_at,
+                    "{} [Unstake]: The stake is locked until epoch {}.",
+                    SaleError::OutsideWindow,
+                    stake.unlocks_at.number()
+                );
+                amount += stake.amount;
+            }
+            receipt.burn();
+            self.stake_vault.take(amount)
+        }
+
+        // Mint a VIP badge for a buyer with a prior agreement, named after them
+        pub fn grant_vip(&mut self, name: String) -> NonFungibleBucket {
+            self.assert_not_finalized("Grant VIP");
+            self.record("grant_vip", Authority::role("OWNER"), name.clone());
+            ResourceManager::from_address(self.vip_badge_address)
+                .mint_ruid_non_fungible(VipBadge { name })
+                .as_non_fungible()
+        }
+
+        // Update the name on an admin badge, e.g. to the handle of its current holder, so wallets
+        // show who holds which badge as the team changes
+        pub fn rename_admin_badge(&mut self, badge_id: NonFungibleLocalId, name: String) {
+            self.assert_not_finalized("Rename Admin Badge");
+            self.record(
+                "rename_admin_badge",
+                Authority::role("OWNER"),
+                format!("{} to {}", badge_id, name),
+            );
+            ResourceManager::from_address(self.admin_badge_address)
+                .update_non_fungible_data(&badge_id, "name", name);
+        }
+
+        // Issue mint passes, each backed by 'allocation' NFTs of the vault that stay reserved until
+        // the priority window closes. The passes can be sold or airdropped by the owner
+        pub fn mint_passes(&mut self, count: u32, allocation: u16, price: Decimal) -> NonFungibleBucket {
+            self.assert_not_finalized("Mint Passes");
+            self.record(
+                "mint_passes",
+                Authority::role("OWNER"),
+                format!("{} x {} at {}", count, allocation, price),
+            );
+            assert!(
+                count > 0 && allocation > 0,
+                "{} [Mint Passes]: Passes must guarantee at least one NFT.",
+                SaleError::InvalidConfiguration
+            );
+            Self::validate_price(price, self.payment_divisibility);
+
+            self.release_expired_reservation();
+            self.reserved_for_passes += u64::from(count) * u64::from(allocation);
+            assert!(
+                self.nft_vault.amount() >= Decimal::from(self.reserved_for_passes),
+                "{} [Mint Passes]: Every pass has to be backed by NFTs in the vault.",
+                SaleError::InsufficientInventory
+            );
+
+            let manager = ResourceManager::from_address(self.mint_pass_address);
+            let mut passes = NonFungibleBucket::new(self.mint_pass_address);
+            for _ in 0..count {
+                passes.put(manager.mint_ruid_non_fungible(MintPass { allocation, price }).as_non_fungible());
+            }
+            passes
+        }
+
+        // Open the priority window in which mint passes are redeemed
+        pub fn set_pass_window(&mut self, opens_at: Epoch, closes_at: Epoch) {
+            self.assert_not_finalized("Set Pass Window");
+            self.record(
+                "set_pass_window",
+                Authority::role("OWNER"),
+                format!("{:?} to {:?}", opens_at, closes_at),
+            );
+            assert!(
+                opens_at < closes_at,
+                "{} [Set Pass Window]: The priority window must close after it opens.",
+                SaleError::InvalidConfiguration
+            );
+            self.release_expired_reservation();
+            self.pass_window = Some((opens_at, closes_at));
+        }
+
+        // Issue 'count' backer shares, splitting 'revenue_share' of all revenue collected from now
+        // on equally between them. Shares can only be issued once, so their part never dilutes.
+        // Shared revenue is paid out for good and is not available for refunds
+        pub fn issue_backer_shares(&mut self, count: u32, revenue_share: Decimal) -> NonFungibleBucket {
+            self.assert_not_finalized("Issue Backer Shares");
+            self.record(
+                "issue_backer_shares",
+                Authority::role("OWNER"),
+                format!("{} for {}", count, revenue_share),
+            );
+            assert!(
+                self.backer_shares == 0,
+                "{} [Issue Backer Shares]: Backer shares have already been issued.",
+                SaleError::AlreadyDone
+            );
+            assert!(
+                count > 0,
+                "{} [Issue Backer Shares]: At least one share has to be issued.",
+                SaleError::InvalidConfiguration
+            );
+            assert!(
+                revenue_share > Decimal::zero() && revenue_share <= Decimal::one(),
+                "{} [Issue Backer Shares]: The revenue share must be above 0 and at most 1.",
+                SaleError::InvalidConfiguration
+            );
+
+            self.backer_shares = count.into();
+            self.revenue_share = revenue_share;
+            let manager = ResourceManager::from_address(self.backer_share_address);
+            let mut shares = NonFungibleBucket::new(self.backer_share_address);
+            for number in 1..=u64::from(count) {
+                shares.put(
+                    manager
+                        .mint_non_fungible(
+                            &NonFungibleLocalId::integer(number),
+                            BackerShare { name: format!("Backer Share #{}", number) },
+                        )
+                        .as_non_fungible(),
+                );
+            }
+            shares
+        }
+
+        // Mint an affiliate link earning whoever holds it 'commission_rate' of the purchases made
+        // presenting it, after the platform's cut
+        pub fn issue_affiliate_link(
+            &mut self,
+            name: String,
+            commission_rate: Decimal,
+        ) -> NonFungibleBucket {
+            self.assert_not_finalized("Issue Affiliate Link");
+            self.record(
+                "issue_affiliate_link",
+                Authority::role("OWNER"),
+                format!("{} {}", name, commission_rate),
+            );
+            assert!(
+                commission_rate > Decimal::zero() && commission_rate <= Decimal::one(),
+                "{} [Issue Affiliate Link]: The commission rate must be above 0 and at most 1.",
+                SaleError::InvalidConfiguration
+            );
+            ResourceManager::from_address(self.affiliate_link_address)
+                .mint_ruid_non_fungible(AffiliateLink { name, commission_rate })
+                .as_non_fungible()
+        }
+
+        // Buy like 'buy' presenting an affiliate link, which earns its holder a commission on
+        // the purchase
+        pub fn buy_via_affiliate(
+            &mut self,
+            payment: Bucket,
+            number_of_nfts: u16,
+            link: NonFungibleProof,
+        ) -> (Bucket, NonFungibleBucket, Vec<Bucket>) {
+            let link = self.check_affiliate_link(link, "Buy Via Affiliate");
+            let commission_rate = link.data().commission_rate;
+            let attribution = Attribution {
+                affiliate: Some((link.local_id().clone(), commission_rate)),
+                ..Default::default()
+            };
+            self.sell(payment, number_of_nfts, self.price, Buyer::Public, attribution)
+        }
+
+        // Pay the holder of affiliate links the commissions they earned and were not claimed yet
+        pub fn claim_commission(&mut self, links: NonFungibleProof) -> Bucket {
+            let links = links.check_with_message(
+                self.affiliate_link_address,
+                format!(
+                    "{} [Claim Commission]: Affiliate links of this sale must be presented.",
+                    SaleError::WrongResource
+                ),
+            );
+            let mut owed = Decimal::zero();
+            for link_id in links.non_fungible_local_ids() {
+                owed += self.commissions.remove(&link_id).unwrap_or_default();
+            }
+            self.commissions_owed -= owed;
+            self.commission_vault.take(owed)
+        }
+
+        // Commission earned by an affiliate link and not claimed yet
+        pub fn commission_owed(&self, link_id: NonFungibleLocalId) -> Decimal {
+            self.commissions.get(&link_id).map_or(Decimal::zero(), |owed| *owed)
+        }
+
+        fn check_affiliate_link(
+            &self,
+            link: NonFungibleProof,
+            method: &str,
+        ) -> NonFungible<AffiliateLink> {
+            link.check_with_message(
+                self.affiliate_link_address,
+                format!(
+                    "{} [{}]: An affiliate link of this sale must be presented.",
+                    SaleError::WrongResource,
+                    method
+                ),
+            )
+            .non_fungible()
+        }
+
+        // Set aside the commission of an affiliate link out of a purchase's settlement, what is
+        // left after the platform's 'cut'
+        fn pay_commission(
+            &mut self,
+            settlement: &mut Bucket,
+            cut: Decimal,
+            link_id: &NonFungibleLocalId,
+            commission_rate: Decimal,
+        ) {
+            let commission = settlement.take_advanced(
+                (settlement.amount() - cut) * commission_rate,
+                WithdrawStrategy::Rounded(RoundingMode::ToZero),
+            );
+            if commission.is_empty() {
+                commission.drop_empty();
+                return;
+            }
+            let amount = commission.amount();
+            self.commission_vault.put(commission);
+            self.commissions_owed += amount;
+            let owed = self.commission_owed(link_id.clone());
+            self.commissions.insert(link_id.clone(), owed + amount);
+            Runtime::emit_event(CommissionEarnedEvent {
+                link_id: link_id.clone(),
+                commission: amount,
+            });
+        }
+
+        // Pay the holder of backer shares what they are owed and not yet claimed, whoever
+        // claimed with the shares before
+        pub fn claim_revenue_share(&mut self, shares: NonFungibleProof) -> Bucket {
+            let shares = shares.check_with_message(
+                self.backer_share_address,
+                format!(
+                    "{} [Claim Revenue Share]: Backer shares of this sale must be presented.",
+                    SaleError::WrongResource
+                ),
+            );
+            let mut owed = Decimal::zero();
+            for share_id in shares.non_fungible_local_ids() {
+                let claimed = self
+                    .revenue_claimed
+                    .get(&share_id)
+                    .map_or(Decimal::zero(), |claimed| *claimed);
+                owed += self.revenue_per_share - claimed;
+                self.revenue_claimed.insert(share_id, self.revenue_per_share);
+            }
+            self.revenue_claimed_total += owed;
+            self.revenue_share_vault
+                .take_advanced(owed, WithdrawStrategy::Rounded(RoundingMode::ToZero))
+        }
+
+        // Burn mint passes during the priority window and buy their allocations at the price
+        // locked in each pass. Works whether or not the public sale has started, but not once it
+        // has been cancelled
+        pub fn redeem_passes(
+            &mut self,
+            passes: NonFungibleBucket,
+            mut payment: Bucket,
+        ) -> (Bucket, NonFungibleBucket) {
+            self.assert_buying_open("Redeem Passes");
+            assert!(
+                self.refunds_close_at.is_none(),
+                "{} [Redeem Passes]: The sale has been cancelled.",
+                SaleError::SaleCancelled
+            );
+            let (opens_at, closes_at) = self
+                .pass_window
+                .unwrap_or_else(|| {
+                    panic!(
+                        "{} [Redeem Passes]: The priority window has not been scheduled.",
+                        SaleError::NotOffered
+                    )
+                });
+            let now = Runtime::current_epoch();
+            assert!(
+                now >= opens_at && now < closes_at,
+                "{} [Redeem Passes]: Passes can only be redeemed from epoch {} until epoch {}.",
+                SaleError::OutsideWindow,
+                opens_at.number(),
+                closes_at.number()
+            );
+            assert_eq!(
+                passes.resource_address(),
+                self.mint_pass_address,
+                "{} [Redeem Passes]: Only mint passes of this sale can be redeemed.",
+                SaleError::WrongResource
+            );
+            assert_eq!(
+                payment.resource_address(),
+                self.accepted_payment_token,
+                "{} [Redeem Passes]: Payment is only accepted in {:?}",
+                SaleError::WrongPaymentToken,
+                self.accepted_payment_token
+            );
+
+            let mut revenue = Decimal::zero();
+            for pass in passes.non_fungibles::<MintPass>() {
+                let MintPass { allocation, price } = pass.data();
+                revenue += price * allocation;
+            }
+            let mut settlement = self.take_payment(&mut payment, revenue, "Redeem Passes");
+
+            let mut nfts = NonFungibleBucket::new(self.nft_vault.resource_address());
+            for pass in passes.non_fungibles::<MintPass>() {
+                let MintPass { allocation, price } = pass.data();
+                let fee_per_nft = self.platform_cut(price);
+                self.collect(
+                    settlement.take(price * allocation),
+                    fee_per_nft * allocation,
+                    "Redeem Passes",
+                );
+
+                let allocated = self.nft_vault.take(allocation);
+                for nft_id in allocated.non_fungible_local_ids() {
+                    self.record_sale(nft_id, price);
+                }
+                nfts.put(allocated);
+                self.reserved_for_passes =
+                    self.reserved_for_passes.saturating_sub(allocation.into());
+            }
+            passes.burn();
+
+            self.announce_sale(
+                None,
+                NftsSoldEvent {
+                    nft_ids: nfts.non_fungible_local_ids(),
+                    revenue,
+                    recipient: None,
+                    sponsor: None,
+                    tag: None,
+                    cohort: None,
+                },
+            );
+            (payment, nfts)
+        }
+
+        // Make sure taking 'count' NFTs from the vault leaves those reserved for mint pass holders
+        fn check_unreserved(&self, count: Decimal, method: &str) {
+            let reserved = self.reserved_nfts();
+            assert!(
+                self.nft_vault.amount() - count >= Decimal::from(reserved),
+                "{} [{}]: {} of the remaining NFTs are reserved for mint pass holders.",
+                SaleError::ReservedForPasses,
+                method,
+                reserved
+            );
+        }
+
+        // Drop the reservation of passes whose priority window has closed, so new passes or a new
+        // window start from what is still owed
+        fn release_expired_reservation(&mut self) {
+            if self.reserved_nfts() == 0 {
+                self.reserved_for_passes = 0;
+            }
+        }
+
+        // NFTs currently held back for mint pass holders
+        fn reserved_nfts(&self) -> u64 {
+            match self.pass_window {
+                Some((_, closes_at)) if Runtime::current_epoch() >= closes_at => 0,
+                _ => self.reserved_for_passes,
+            }
+        }
+
+        // Escrow funds for 'number_of_nfts' before the sale opens, signalling demand. The receipt
+        // claims the NFTs once the escrow is converted, or the funds back at any time before that
+        pub fn escrow(&mut self, payment: Bucket, number_of_nfts: u16) -> NonFungibleBucket {
+            assert!(
+                !self.sale_started && self.refunds_close_at.is_none(),
+                "{} [Escrow]: Funds can only be escrowed before the sale opens.",
+                SaleError::SaleAlreadyOpen
+            );
+            let max_per_transaction = self.max_per_transaction();
+            assert!(
+                number_of_nfts > 0 && number_of_nfts <= max_per_transaction,
+                "{} [Escrow]: Between 1 and {} NFTs can be requested.",
+                SaleError::InvalidQuantity,
+                max_per_transaction
+            );
+            assert_eq!(
+                payment.resource_address(),
+                self.accepted_payment_token,
+                "{} [Escrow]: Only {:?} can be escrowed.",
+                SaleError::WrongPaymentToken,
+                self.accepted_payment_token
+            );
+            self.assert_not_finalized("Escrow");
+            self.check_minimum_payment(&payment, "Escrow");
+            // Escrows are anonymous, so they are only taken where an anonymous buy would be
+            self.admit(Buyer::Public);
+
+            self.escrowed_funds += payment.amount();
+            let escrow_id = self.next_escrow_id;
+            self.next_escrow_id += 1;
+            self.escrows.insert(
+                escrow_id,
+                Escrow {
+                    funds: Vault::with_bucket(payment),
+                    nfts: NonFungibleVault::new(self.nft_vault.resource_address()),
+                    number_of_nfts,
+                },
+            );
+            ResourceManager::from_address(self.escrow_receipt_address)
+                .mint_ruid_non_fungible(EscrowReceipt { escrow_id })
+                .as_non_fungible()
+        }
+
+        // Turn up to 'limit' escrows into purchases at the opening price, oldest first. Each gets
+        // as many of its NFTs as its funds and the remaining inventory allow. Called right after
+        // 'start_sale', repeatedly if there are more escrows than fit in one transaction
+        pub fn convert_escrows(&mut self, badge: Option<Proof>, limit: u32) {
+            let authority =
+                self.presented(badge, &["admin", "automation", "OWNER"], "Convert Escrows");
+            self.assert_not_finalized("Convert Escrows");
+            self.record("convert_escrows", authority, limit.to_string());
+            self.assert_buying_open("Convert Escrows");
+            assert!(
+                self.sale_allowed,
+                "{} [Convert Escrows]: Escrows are converted at the opening price once the sale starts.",
+                SaleError::SaleNotOpen
+            );
+            assert!(
+                !self.opening_price.is_zero(),
+                "{} [Convert Escrows]: Free sales are open to VIP badge holders only, escrows can only be claimed back.",
+                SaleError::FreeSaleGated
+            );
+
+            let price = self.opening_price;
+            let fee_per_nft = self.platform_cut(price);
+            let max_per_transaction = self.max_per_transaction();
+            let end = Self::batch_end(limit, self.escrows_converted, self.next_escrow_id, |id| {
+                let escrow = self.escrows.get(&id).unwrap();
+                escrow.number_of_nfts.min(max_per_transaction).into()
+            });
+            for escrow_id in self.escrows_converted..end {
+                let available = self.nft_vault.amount() - Decimal::from(self.reserved_nfts());
+                let max_per_transaction = Decimal::from(self.max_per_transaction());
+                let mut escrow = self.escrows.get_mut(&escrow_id).unwrap();
+                let mut number_of_nfts = Decimal::from(escrow.number_of_nfts)
+                    .min(available)
+                    .min(max_per_transaction);
+                if price > Decimal::zero() {
+                    number_of_nfts = number_of_nfts.min(escrow.funds.amount() / price);
+                }
+                let number_of_nfts = number_of_nfts
+                    .checked_round(0, RoundingMode::ToZero)
+                    .unwrap()
+                    .max(Decimal::zero());
+                if number_of_nfts.is_zero() {
+                    continue;
+                }
+
+                let nfts = self.nft_vault.take(number_of_nfts);
+                let cost = price * number_of_nfts;
+                let payment = escrow.funds.take(cost);
+                self.escrowed_funds -= cost;
+                let nft_ids = nfts.non_fungible_local_ids();
+                escrow.nfts.put(nfts);
+                drop(escrow);
+
+                self.collect(payment, fee_per_nft * number_of_nfts, "Convert Escrows");
+                for nft_id in &nft_ids {
+                    self.record_sale(nft_id.clone(), price);
+                }
+                self.announce_sale(
+                    None,
+                    NftsSoldEvent {
+                        nft_ids,
+                        revenue: cost,
+                        recipient: None,
+                        sponsor: None,
+                        tag: None,
+                        cohort: None,
+                    },
+                );
+            }
+            self.escrows_converted = end;
+        }
+
+        // Return escrow receipts for what they hold: the NFTs bought and leftover funds once
+        // converted, or the full deposit before that
+        pub fn claim_escrow(&mut self, receipts: NonFungibleBucket) -> (Bucket, NonFungibleBucket) {
+            assert_eq!(
+                receipts.resource_address(),
+                self.escrow_receipt_address,
+                "{} [Claim Escrow]: Only escrow receipts of this sale can be claimed.",
+                SaleError::WrongResource
+            );
+
+            let mut funds = Bucket::new(self.accepted_payment_token);
+            let mut nfts = NonFungibleBucket::new(self.nft_vault.resource_address());
+            for receipt in receipts.non_fungibles::<EscrowReceipt>() {
+                let mut escrow = self.escrows.get_mut(&receipt.data().escrow_id).unwrap();
+                funds.put(escrow.funds.take_all());
+                nfts.put(escrow.nfts.take_all());
+            }
+            self.escrowed_funds -= funds.amount();
+            receipts.burn();
+            (funds, nfts)
+        }
+
+        // Offer holds on specific NFTs on the given terms, or stop offering them with None.
+        // Existing holds keep the terms they were placed under
+        pub fn set_hold_terms(&mut self, hold_terms: Option<HoldTerms>) {
+            self.assert_not_finalized("Set Hold Terms");
+            self.record("set_hold_terms", Authority::role("OWNER"), format!("{:?}", hold_terms));
+            if let Some(terms) = &hold_terms {
+                assert!(
+                    terms.duration_epochs > 0,
+                    "{} [Set Hold Terms]: Holds must last at least one epoch.",
+                    SaleError::InvalidConfiguration
+                );
+                assert!(
+                    terms.minimum_deposit > Decimal::zero(),
+                    "{} [Set Hold Terms]: The minimum deposit must be positive.",
+                    SaleError::InvalidConfiguration
+                );
+                assert!(
+                    terms.expiry_fee >= Decimal::zero() && terms.expiry_fee <= self.max_fee_rate,
+                    "{} [Set Hold Terms]: The expiry fee must be between 0 and {}.",
+                    SaleError::InvalidConfiguration,
+                    self.max_fee_rate
+                );
+            }
+            self.hold_terms = hold_terms;
+        }
+
+        // Hold a specific NFT at the current price against a deposit credited toward it. The
+        // receipt buys the NFT with 'complete_hold' until the hold expires
+        pub fn hold(&mut self, nft_id: NonFungibleLocalId, deposit: Bucket) -> NonFungibleBucket {
+            self.assert_buying_open("Hold");
+            let terms = self
+                .hold_terms
+                .clone()
+                .unwrap_or_else(|| {
+                    panic!(
+                        "{} [Hold]: This sale does not offer holds.",
+                        SaleError::NotOffered
+                    )
+                });
+            assert!(
+                self.sale_allowed,
+                "{} [Hold]: Sale is not allowed at this moment.",
+                SaleError::SaleNotOpen
+            );
+            self.admit(Buyer::Public);
+            assert_eq!(
+                deposit.resource_address(),
+                self.accepted_payment_token,
+                "{} [Hold]: Deposits are only accepted in {:?}",
+                SaleError::WrongPaymentToken,
+                self.accepted_payment_token
+            );
+            assert!(
+                deposit.amount() >= terms.minimum_deposit && deposit.amount() <= self.price,
+                "{} [Hold]: The deposit must be between {} and the price of {}.",
+                SaleError::InsufficientPayment,
+                terms.minimum_deposit,
+                self.price
+            );
+            assert!(
+                self.nft_vault.contains_non_fungible(&nft_id),
+                "{} [Hold]: NFT {} is not for sale.",
+                SaleError::NotForSale,
+                nft_id
+            );
+            self.check_unreserved(Decimal::one(), "Hold");
+
+            self.held_vault.put(self.nft_vault.take_non_fungible(&nft_id));
+            self.held_deposits += deposit.amount();
+            self.open_holds += 1;
+            let hold_id = self.next_hold_id;
+            self.next_hold_id += 1;
+            let expires_at = Runtime::current_epoch().after(terms.duration_epochs).unwrap();
+            self.holds.insert(
+                hold_id,
+                Hold {
+                    nft_id: nft_id.clone(),
+                    price: self.price,
+                    deposit: Vault::with_bucket(deposit),
+                    expires_at,
+                    expiry_fee: terms.expiry_fee,
+                    closed: false,
+                },
+            );
+            ResourceManager::from_address(self.hold_receipt_address)
+                .mint_ruid_non_fungible(HoldReceipt {
+                    hold_id,
+                    nft_id,
+                    expires_at,
+                })
+                .as_non_fungible()
+        }
+
+        // Buy a held NFT for the rest of its held price before the hold expires
+        pub fn complete_hold(
+            &mut self,
+            receipt: NonFungibleBucket,
+            mut payment: Bucket,
+        ) -> (Bucket, NonFungibleBucket) {
+            assert!(
+                self.sale_allowed && self.refunds_close_at.is_none(),
+                "{} [Complete Hold]: Sale is not allowed at this moment.",
+                SaleError::SaleNotOpen
+            );
+            self.assert_buying_open("Complete Hold");
+            self.admit(Buyer::Public);
+            let hold_id = self.hold_receipt(&receipt, "Complete Hold");
+            let mut hold = self.holds.get_mut(&hold_id).unwrap();
+            assert!(
+                !hold.closed && Runtime::current_epoch() < hold.expires_at,
+                "{} [Complete Hold]: The hold expired at epoch {}.",
+                SaleError::OutsideWindow,
+                hold.expires_at.number()
+            );
+            assert_eq!(
+                payment.resource_address(),
+                self.accepted_payment_token,
+                "{} [Complete Hold]: Payment is only accepted in {:?}",
+                SaleError::WrongPaymentToken,
+                self.accepted_payment_token
+            );
+            hold.closed = true;
+            self.open_holds -= 1;
+            let (nft_id, price) = (hold.nft_id.clone(), hold.price);
+            let mut settlement = hold.deposit.take_all();
+            drop(hold);
+            self.held_deposits -= settlement.amount();
+            let rest = price - settlement.amount();
+            settlement.put(self.take_payment(&mut payment, rest, "Complete Hold"));
+
+            let fee_per_nft = self.platform_cut(price);
+            self.collect(settlement, fee_per_nft, "Complete Hold");
+            self.record_sale(nft_id.clone(), price);
+            let nft = self.held_vault.take_non_fungible(&nft_id);
+            receipt.burn();
+
+            self.announce_sale(
+                None,
+                NftsSoldEvent {
+                    nft_ids: nft.non_fungible_local_ids(),
+                    revenue: price,
+                    recipient: None,
+                    sponsor: None,
+                    tag: None,
+                    cohort: None,
+                },
+            );
+            (payment, nft)
+        }
+
+        // Put the NFT of an expired hold back on sale and keep the expiry fee. Callable by anyone,
+        // so stale holds never lock up inventory
+        pub fn release_hold(&mut self, hold_id: u64) {
+            let mut hold = self
+                .holds
+                .get_mut(&hold_id)
+                .unwrap_or_else(|| {
+                    panic!(
+                        "{} [Release Hold]: No such hold.",
+                        SaleError::NotFound
+                    )
+                });
+            assert!(
+                !hold.closed,
+                "{} [Release Hold]: The hold is already over.",
+                SaleError::AlreadyDone
+            );
+            assert!(
+                Runtime::current_epoch() >= hold.expires_at,
+                "{} [Release Hold]: The hold lasts until epoch {}.",
+                SaleError::OutsideWindow,
+                hold.expires_at.number()
+            );
+            hold.closed = true;
+            self.open_holds -= 1;
+            let nft_id = hold.nft_id.clone();
+            let fee = hold.deposit.amount() * hold.expiry_fee;
+            let fee = hold
+                .deposit
+                .take_advanced(fee, WithdrawStrategy::Rounded(RoundingMode::ToZero));
+            drop(hold);
+
+            self.held_deposits -= fee.amount();
+            self.deposit_to_treasury(fee);
+            self.nft_vault.put(self.held_vault.take_non_fungible(&nft_id));
+        }
+
+        // Return the deposit of an expired hold, less the expiry fee
+        pub fn reclaim_hold(&mut self, receipt: NonFungibleBucket) -> Bucket {
+            let hold_id = self.hold_receipt(&receipt, "Reclaim Hold");
+            if !self.holds.get(&hold_id).unwrap().closed {
+                self.release_hold(hold_id);
+            }
+            receipt.burn();
+            let deposit = self.holds.get_mut(&hold_id).unwrap().deposit.take_all();
+            self.held_deposits -= deposit.amount();
+            deposit
+        }
+
+        // The hold id of a single receipt of this sale
+        fn hold_receipt(&self, receipt: &NonFungibleBucket, method: &str) -> u64 {
+            assert!(
+                receipt.resource_address() == self.hold_receipt_address
+                    && receipt.amount() == Decimal::one(),
+                "{} [{}]: Exactly one hold receipt of this sale has to be returned.",
+                SaleError::WrongResource,
+                method
+            );
+            receipt.non_fungible::<HoldReceipt>().data().hold_id
+        }
+
+        // Sell gift vouchers one-to-one for the payment token. The payment is collected right away
+        // and the vouchers can be handed to anyone to spend in 'buy'
+        pub fn buy_vouchers(&mut self, payment: Bucket) -> Bucket {
+            assert_eq!(
+                payment.resource_address(),
+                self.accepted_payment_token,
+                "{} [Buy Vouchers]: Invalid token provided. Vouchers are only sold for {:?}",
+                SaleError::WrongPaymentToken,
+                self.accepted_payment_token
+            );
+            self.assert_not_finalized("Buy Vouchers");
+            self.check_minimum_payment(&payment, "Buy Vouchers");
+
+            let vouchers = ResourceManager::from_address(self.voucher_resource_address).mint(payment.amount());
+            let fee = self.platform_cut(payment.amount());
+            self.collect(payment, fee, "Buy Vouchers");
+            vouchers
+        }
+
+        // Announce NFTs handed over for payment, surfacing purchases at or above the large
+        // purchase threshold to bots watching the sale. Every paid delivery goes through here
+        fn announce_sale(&self, buyer: Option<ComponentAddress>, sold: NftsSoldEvent) {
+            let (amount, number_of_nfts) = (sold.revenue, sold.nft_ids.len() as u16);
+            Runtime::emit_event(sold);
+            if self.large_purchase_threshold.is_some_and(|threshold| amount >= threshold) {
+                Runtime::emit_event(LargePurchaseEvent {
+                    buyer,
+                    amount,
+                    number_of_nfts,
+                });
+            }
+        }
+
+        // Take 'cost' out of a payment and leave the change as the change policy has it: returned,
+        // refused, or kept as a tip. Change in gift vouchers is always returned
+        fn take_payment(&mut self, payment: &mut Bucket, cost: Decimal, method: &str) -> Bucket {
+            self.check_minimum_payment(payment, method);
+            assert!(
+                payment.amount() >= cost,
+                "{} [{}]: This purchase can only go through when {} tokens are provided.",
+                SaleError::InsufficientPayment,
+                method,
+                cost
+            );
+            assert!(
+                self.change_policy != ChangePolicy::Reject || payment.amount() == cost,
+                "{} [{}]: This sale only accepts the exact payment of {} tokens.",
+                SaleError::ExactPaymentRequired,
+                method,
+                cost
+            );
+            let settlement = payment.take(cost);
+            if self.change_policy == ChangePolicy::Tip
+                && payment.resource_address() != self.voucher_resource_address
+            {
+                let change = payment.amount();
+                self.tips.put(payment.take(change));
+            }
+            settlement
+        }
+
+        // Turn away dust before anything is taken from a vault or deposited into the treasury
+        fn check_minimum_payment(&self, payment: &Bucket, method: &str) {
+            assert!(
+                payment.amount() >= self.minimum_payment,
+                "{} [{}]: Payments below {} tokens are not accepted.",
+                SaleError::InsufficientPayment,
+                method,
+                self.minimum_payment
+            );
+        }
+
+        // Remember the sale of an NFT at the price its buyer paid after every discount and cohort
+        // adjustment, which is exactly what a refund pays back. NFTs handed out for free have
+        // nothing to refund
+        fn record_sale(&mut self, nft_id: NonFungibleLocalId, price: Decimal) {
+            if price > Decimal::zero() {
+                self.sale_prices.insert(nft_id.clone(), price);
+                self.refundable += price;
+            }
+            self.total_revenue += price;
+            self.total_units += 1;
+            let sale = ItemSale {
+                price,
+                epoch: Runtime::current_epoch(),
+                refunded: false,
+            };
+            let mut history = self.item_history(nft_id.clone());
+            history.push(sale);
+            self.item_sales.insert(nft_id, history);
+        }
+
+        // Every primary sale of the given NFT in order, including refunded ones
+        pub fn item_history(&self, nft_id: NonFungibleLocalId) -> Vec<ItemSale> {
+            self.item_sales
+                .get(&nft_id)
+                .map(|history| history.clone())
+                .unwrap_or_default()
+        }
+
+        // Pay the platform its cut of a payment and keep the rest as revenue, unless the current
+        // gated phase routes its proceeds to an account
+        fn collect(&mut self, mut payment: Bucket, cut: Decimal, method: &str) {
+            if let Some(fee) = &self.platform_fee {
+                if cut > Decimal::zero() {
+                    Self::pay(fee.recipient, payment.take(cut), method);
+                }
+            }
+            if self.backer_shares > 0 {
+                let shared = payment.take_advanced(
+                    payment.amount() * self.revenue_share,
+                    WithdrawStrategy::Rounded(RoundingMode::ToZero),
+                );
+                self.revenue_per_share += shared.amount() / self.backer_shares;
+                self.revenue_share_vault.put(shared);
+            }
+            let route = self.current_gated_phase().and_then(|index| {
+                let phase = &self.gated_phases[index];
+                phase.proceeds_to.map(|recipient| (phase.name.clone(), recipient))
+            });
+            match route {
+                Some((phase, recipient)) => {
+                    let amount = payment.amount();
+                    Self::pay(recipient, payment, method);
+                    Runtime::emit_event(ProceedsRoutedEvent {
+                        phase,
+                        recipient,
+                        amount,
+                    });
+                }
+                None => self.deposit_to_treasury(payment),
+            }
+        }
+
+        // Deposit into an account the sale pays out to, naming the account when it refuses
+        fn pay(recipient: ComponentAddress, payment: Bucket, method: &str) {
+            let mut account: Global<Account> = recipient.into();
+            if account.try_deposit_or_refund(payment, None).is_some() {
+                panic!(
+                    "{} [{}]: Account {:?} refuses deposits of the payment token.",
+                    SaleError::DepositRefused,
+                    method,
+                    recipient
+                );
+            }
+        }
+
+        fn deposit_to_treasury(&mut self, bucket: Bucket) {
+            let resource = bucket.resource_address();
+            if resource == self.accepted_payment_token {
+                self.treasury_funds += bucket.amount();
+            }
+            if self.treasury.get(&resource).is_some() {
+                self.treasury.get_mut(&resource).unwrap().put(bucket);
+            } else {
+                self.treasury.insert(resource, Vault::with_bucket(bucket));
+            }
+        }
+
+        fn treasury_balance(&self, resource: ResourceAddress) -> Decimal {
+            self.treasury
+                .get(&resource)
+                .map(|vault| vault.amount())
+                .unwrap_or_default()
+        }
+
+        // The platform fee on an amount, rounded down so it can always be taken from the payment
+        fn platform_cut(&self, amount: Decimal) -> Decimal {
+            match &self.platform_fee {
+                Some(fee) => (amount * fee.rate)
+                    .checked_round(self.payment_divisibility, RoundingMode::ToZero)
+                    .unwrap(),
+                None => Decimal::zero(),
+            }
+        }
+
+        // Send promo NFTs from the sale inventory to a partner account without taking payment
+        pub fn gift_from_vault(&mut self, selection: NftSelection, recipient: Global<Account>) {
+            self.assert_not_finalized("Gift From Vault");
+            self.record(
+                "gift_from_vault",
+                Authority::role("OWNER"),
+                format!("{:?} to {:?}", selection, recipient.address()),
+            );
+            let count = match &selection {
+                NftSelection::Count(count) => Decimal::from(*count),
+                NftSelection::Ids(ids) => Decimal::from(ids.len()),
+            };
+            assert!(
+                count <= Decimal::from(MAX_BATCH),
+                "{} [Gift From Vault]: At most {} NFTs can be gifted per call.",
+                SaleError::InvalidQuantity,
+                MAX_BATCH
+            );
+            self.check_unreserved(count, "Gift From Vault");
+            let nfts = match selection {
+                NftSelection::Count(count) => self.nft_vault.take(count),
+                NftSelection::Ids(ids) => self.nft_vault.take_non_fungibles(&ids),
+            };
+            let shares = indexmap!(recipient.address() => nfts.non_fungible_local_ids());
+            self.deliver(nfts, shares);
+        }
+
+        // Announce a recall of stolen NFTs from the given vault. Only works for collections that
+        // are recallable by this component, and can only be executed after the timelock
+        pub fn schedule_recall(
+            &mut self,
+            vault: InternalAddress,
+            nft_ids: IndexSet<NonFungibleLocalId>,
+        ) -> u64 {
+            self.assert_not_finalized("Schedule Recall");
+            self.record(
+                "schedule_recall",
+                Authority::role("OWNER"),
+                format!("{:?} {:?}", vault, nft_ids),
+            );
+            assert!(
+                !nft_ids.is_empty(),
+                "{} [Schedule Recall]: At least one NFT id must be provided.",
+                SaleError::InvalidQuantity
+            );
+
+            let recall_id = self.next_recall_id;
+            self.next_recall_id += 1;
+
+            let executable_at = Runtime::current_epoch()
+                .after(RECALL_TIMELOCK_EPOCHS)
+                .unwrap();
+            self.pending_recalls.insert(
+                recall_id,
+                PendingRecall {
+                    vault,
+                    nft_ids: nft_ids.clone(),
+                    executable_at,
+                },
+            );
+
+            Runtime::emit_event(RecallScheduledEvent {
+                recall_id,
+                vault,
+                nft_ids,
+                executable_at,
+            });
+            recall_id
+        }
+
+        // Drop an announced recall before it is executed
+        pub fn cancel_recall(&mut self, recall_id: u64) {
+            self.assert_not_finalized("Cancel Recall");
+            self.record("cancel_recall", Authority::role("OWNER"), recall_id.to_string());
+            assert!(
+                self.pending_recalls.remove(&recall_id).is_some(),
+                "{} [Cancel Recall]: No pending recall with id {}.",
+                SaleError::NotFound,
+                recall_id
+            );
+            Runtime::emit_event(RecallCancelledEvent { recall_id });
+        }
+
+        // Execute an announced recall whose timelock has passed and hand the NFTs to the owner
+        pub fn recall_item(&mut self, recall_id: u64) -> NonFungibleBucket {
+            self.assert_not_finalized("Recall Item");
+            self.record("recall_item", Authority::role("OWNER"), recall_id.to_string());
+            let recall = self
+                .pending_recalls
+                .remove(&recall_id)
+                .unwrap_or_else(|| {
+                    panic!(
+                        "{} [Recall Item]: No pending recall with this id.",
+                        SaleError::NotFound
+                    )
+                });
+            assert!(
+                Runtime::current_epoch() >= recall.executable_at,
+                "{} [Recall Item]: The recall timelock only expires at epoch {}.",
+                SaleError::OutsideWindow,
+                recall.executable_at.number()
+            );
+
+            let recalled: Bucket = scrypto_decode(&ScryptoVmV1Api::object_call_direct(
+                recall.vault.as_node_id(),
+                NON_FUNGIBLE_VAULT_RECALL_NON_FUNGIBLES_IDENT,
+                scrypto_encode(&NonFungibleVaultRecallNonFungiblesInput {
+                    non_fungible_local_ids: recall.nft_ids.clone(),
+                })
+                .unwrap(),
+            ))
+            .unwrap();
+            // The owner badge must never become a way to recall unrelated resources
+            assert_eq!(
+                recalled.resource_address(),
+                self.nft_vault.resource_address(),
+                "{} [Recall Item]: Only NFTs of the sale collection can be recalled.",
+                SaleError::WrongResource
+            );
+
+            Runtime::emit_event(RecallExecutedEvent {
+                recall_id,
+                vault: recall.vault,
+                nft_ids: recall.nft_ids,
+            });
+            recalled.as_non_fungible()
+        }
+
+        // Claim the physical item behind an NFT of a phygital drop. The NFT data must have a
+        // mutable 'redeemed' field and this component must hold the collection's data updater role
+        pub fn redeem_physical(&mut self, nft_proof: NonFungibleProof, shipping_hash: Hash) {
+            let nft_id = nft_proof
+                .check_with_message(
+                    self.nft_vault.resource_address(),
+                    format!(
+                        "{} [Redeem Physical]: Only NFTs of the sale collection can be redeemed.",
+                        SaleError::WrongResource
+                    ),
+                )
+                .non_fungible_local_id();
+            assert!(
+                self.physical_redemptions.get(&nft_id).is_none(),
+                "{} [Redeem Physical]: The physical item of NFT {} has already been claimed.",
+                SaleError::AlreadyDone,
+                nft_id
+            );
+
+            self.physical_redemptions.insert(nft_id.clone(), shipping_hash);
+            ResourceManager::from_address(self.nft_vault.resource_address())
+                .update_non_fungible_data(&nft_id, "redeemed", true);
+
+            Runtime::emit_event(PhysicalRedeemedEvent {
+                nft_id,
+                shipping_hash,
+            });
+        }
+
+        // Name the accounts unsold NFTs are donated to once the sale ends, e.g. a community
+        // treasury or a museum wallet. NFTs are dealt out in proportion to the weights, equal
+        // weights making a round robin
+        pub fn set_donation_list(&mut self, donation_list: Vec<(ComponentAddress, u32)>) {
+            self.assert_not_finalized("Set Donation List");
+            self.record(
+                "set_donation_list",
+                Authority::role("OWNER"),
+                format!("{:?}", donation_list),
+            );
+            assert!(
+                !donation_list.is_empty() && donation_list.iter().all(|(_, weight)| *weight > 0),
+                "{} [Set Donation List]: The list needs at least one account with a positive weight.",
+                SaleError::InvalidConfiguration
+            );
+            self.donation_list = donation_list;
+        }
+
+        // Donate up to 'limit' unsold NFTs to the donation list once the sale has ended, in as
+        // many calls as the inventory needs. The cycle through the list carries over between them
+        pub fn donate_unsold(&mut self, limit: u32) {
+            self.assert_not_finalized("Donate Unsold");
+            self.record("donate_unsold", Authority::role("OWNER"), limit.to_string());
+            assert!(
+                !self.sale_allowed,
+                "{} [Donate Unsold]: End the sale before donating its unsold NFTs.",
+                SaleError::SaleAlreadyOpen
+            );
+            assert!(
+                !self.donation_list.is_empty(),
+                "{} [Donate Unsold]: No donation list has been set.",
+                SaleError::NotOffered
+            );
+
+            let unreserved = self.nft_vault.amount() - Decimal::from(self.reserved_nfts());
+            let count = unreserved.min(Self::batch_size(limit).into());
+            let nfts = self.nft_vault.take(count);
+            let total_weight: u64 =
+                self.donation_list.iter().map(|(_, weight)| u64::from(*weight)).sum();
+            let mut shares: IndexMap<ComponentAddress, IndexSet<NonFungibleLocalId>> =
+                IndexMap::new();
+            for nft_id in nfts.non_fungible_local_ids() {
+                let mut position = self.donated % total_weight;
+                let (recipient, _) = self
+                    .donation_list
+                    .iter()
+                    .find(|(_, weight)| {
+                        let found = position < u64::from(*weight);
+                        position = position.saturating_sub(u64::from(*weight));
+                        found
+                    })
+                    .unwrap();
+                shares.entry(*recipient).or_default().insert(nft_id);
+                self.donated += 1;
+            }
+            self.deliver(nfts, shares);
+        }
+
+        // Entries of a batch asked for with 'limit', bounded by 'MAX_BATCH'
+        fn batch_size(limit: u32) -> u64 {
+            limit.min(MAX_BATCH).into()
+        }
+
+        // End of the batch of entries from 'cursor' asked for with 'limit', where 'size' gives
+        // the NFTs of an entry. Stops before the entry that would take the batch past 'MAX_BATCH'
+        // NFTs, unless it is the first
+        fn batch_end(limit: u32, cursor: u64, length: u64, size: impl Fn(u64) -> u64) -> u64 {
+            let end = length.min(cursor + Self::batch_size(limit));
+            let mut nfts = 0u64;
+            for index in cursor..end {
+                nfts += size(index);
+                if index > cursor && nfts > u64::from(MAX_BATCH) {
+                    return index;
+                }
+            }
+            end
+        }
+
+        // Queue accounts for an airdrop of one NFT each, an account added n times receiving n.
+        // Lists too long for one transaction are added over several
+        pub fn add_airdrop_recipients(&mut self, recipients: Vec<ComponentAddress>) {
+            self.assert_not_finalized("Add Airdrop Recipients");
+            self.record(
+                "add_airdrop_recipients",
+                Authority::role("OWNER"),
+                format!("{} recipients", recipients.len()),
+            );
+            for recipient in recipients {
+                self.airdrop_recipients.insert(self.airdrop_length, recipient);
+                self.airdrop_length += 1;
+            }
+        }
+
+        // Send NFTs from the vault to the next 'limit' queued recipients. The cursor only moves
+        // past recipients that were served, so an airdrop is completed over as many calls as it
+        // needs without anyone receiving twice. Returns the number of recipients still waiting
+        pub fn airdrop_batch(&mut self, badge: Option<Proof>, limit: u32) -> u64 {
+            let authority = self.presented(badge, &["automation", "OWNER"], "Airdrop Batch");
+            self.assert_not_finalized("Airdrop Batch");
+            self.record("airdrop_batch", authority, limit.to_string());
+            let end = self.airdrop_length.min(self.airdrop_cursor + Self::batch_size(limit));
+            let count = end - self.airdrop_cursor;
+            assert!(
+                self.nft_vault.amount() >= Decimal::from(count),
+                "{} [Airdrop Batch]: The vault holds fewer than the {} NFTs of this batch.",
+                SaleError::InsufficientInventory,
+                count
+            );
+            self.check_unreserved(count.into(), "Airdrop Batch");
+
+            let nfts = self.nft_vault.take(count);
+            let mut nft_ids = nfts.non_fungible_local_ids().into_iter();
+            let mut shares: IndexMap<ComponentAddress, IndexSet<NonFungibleLocalId>> =
+                IndexMap::new();
+            for index in self.airdrop_cursor..end {
+                let recipient = *self.airdrop_recipients.get(&index).unwrap();
+                shares.entry(recipient).or_default().insert(nft_ids.next().unwrap());
+            }
+            self.airdrop_cursor = end;
+            self.deliver(nfts, shares);
+            self.airdrop_length - self.airdrop_cursor
+        }
+
+        // Hand out NFTs given away by the sale. Under the locker policy they go through the
+        // delivery locker, which deposits them straight into accounts that accept it and keeps the
+        // rest for their owners to claim. Otherwise accounts that refuse are skipped, without
+        // failing the batch, and their NFTs queued for a retry or put back on sale
+        fn deliver(
+            &mut self,
+            mut nfts: NonFungibleBucket,
+            shares: IndexMap<ComponentAddress, IndexSet<NonFungibleLocalId>>,
+        ) {
+            for (recipient, nft_ids) in shares {
+                let share = nfts.take_non_fungibles(&nft_ids);
+                let refused = match self.delivery_policy {
+                    DeliveryPolicy::Locker => {
+                        self.locker().store(recipient.into(), share.into(), true);
+                        None
+                    }
+                    DeliveryPolicy::Retry | DeliveryPolicy::Restock => {
+                        let mut account: Global<Account> = recipient.into();
+                        account.try_deposit_or_refund(share.into(), None)
+                    }
+                };
+                let Some(refused) = refused else {
+                    Runtime::emit_event(NftsSoldEvent {
+                        nft_ids,
+                        revenue: Decimal::zero(),
+                        recipient: Some(recipient),
+                        sponsor: None,
+                        tag: None,
+                        cohort: None,
+                    });
+                    continue;
+                };
+
+                Runtime::emit_event(DeliveryRefusedEvent {
+                    recipient,
+                    nft_ids: nft_ids.clone(),
+                    policy: self.delivery_policy,
+                });
+                if self.delivery_policy == DeliveryPolicy::Restock {
+                    self.nft_vault.put(refused.as_non_fungible());
+                } else {
+                    self.retry_vault.put(refused.as_non_fungible());
+                    self.retry_queued += nft_ids.len() as u64;
+                    self.retry_queue.insert(self.retry_length, (recipient, nft_ids));
+                    self.retry_length += 1;
+                }
+            }
+            nfts.drop_empty();
+        }
+
+        fn locker(&mut self) -> Global<AccountLocker> {
+            *self.delivery_locker.get_or_insert_with(|| {
+                let storer = rule!(require(global_caller(Runtime::global_address())));
+                Blueprint::<AccountLocker>::instantiate(
+                    OwnerRole::None,
+                    storer,
+                    rule!(deny_all),
+                    rule!(deny_all),
+                    rule!(deny_all),
+                    None,
+                )
+            })
+        }
+
+        // Defer the delivery of new purchases until 'fulfil', e.g. while each NFT is paired with a
+        // physical item off-ledger. Purchases then have to name the buyer's account
+        pub fn set_deferred_delivery(&mut self, deferred_delivery: bool) {
+            self.assert_not_finalized("Set Deferred Delivery");
+            self.record(
+                "set_deferred_delivery",
+                Authority::role("OWNER"),
+                deferred_delivery.to_string(),
+            );
+            self.deferred_delivery = deferred_delivery;
+        }
+
+        // Deliver the next 'limit' purchase intents in the order they were paid for, through the
+        // delivery locker for accounts that refuse them. Returns the number still waiting
+        pub fn fulfil(&mut self, badge: Option<Proof>, limit: u32) -> u64 {
+            let authority = self.presented(badge, &["admin", "automation", "OWNER"], "Fulfil");
+            self.assert_not_finalized("Fulfil");
+            self.record("fulfil", authority, limit.to_string());
+            let end = Self::batch_end(limit, self.intent_cursor, self.intent_length, |id| {
+                self.intents.get(&id).unwrap().1.len() as u64
+            });
+            for intent_id in self.intent_cursor..end {
+                let (recipient, nft_ids) = self.intents.remove(&intent_id).unwrap();
+                let nfts = self.intent_vault.take_non_fungibles(&nft_ids);
+                self.intents_queued -= nft_ids.len() as u64;
+                self.locker().store(recipient.into(), nfts.into(), true);
+                Runtime::emit_event(IntentFulfilledEvent {
+                    intent_id,
+                    recipient,
+                    nft_ids,
+                });
+            }
+            self.intent_cursor = end;
+            self.intent_length - self.intent_cursor
+        }
+
+        // Choose what happens to gifts, donations and airdrops an account refuses. Deliveries
+        // already queued for a retry follow the policy in place when they are retried
+        pub fn set_delivery_policy(&mut self, delivery_policy: DeliveryPolicy) {
+            self.assert_not_finalized("Set Delivery Policy");
+            self.record(
+                "set_delivery_policy",
+                Authority::role("OWNER"),
+                format!("{:?}", delivery_policy),
+            );
+            self.delivery_policy = delivery_policy;
+        }
+
+        // Deliver the next 'limit' refused deliveries again under the current policy, an account
+        // refusing once more going to the back of the queue. Returns the number still waiting
+        pub fn retry_deliveries(&mut self, badge: Option<Proof>, limit: u32) -> u64 {
+            let authority = self.presented(badge, &["automation", "OWNER"], "Retry Deliveries");
+            self.assert_not_finalized("Retry Deliveries");
+            self.record("retry_deliveries", authority, limit.to_string());
+            let end = Self::batch_end(limit, self.retry_cursor, self.retry_length, |index| {
+                self.retry_queue.get(&index).unwrap().1.len() as u64
+            });
+            let mut nfts = NonFungibleBucket::new(self.retry_vault.resource_address());
+            let mut shares: IndexMap<ComponentAddress, IndexSet<NonFungibleLocalId>> =
+                IndexMap::new();
+            for index in self.retry_cursor..end {
+                let (recipient, nft_ids) = self.retry_queue.remove(&index).unwrap();
+                nfts.put(self.retry_vault.take_non_fungibles(&nft_ids));
+                self.retry_queued -= nft_ids.len() as u64;
+                shares.entry(recipient).or_default().extend(nft_ids);
+            }
+            self.retry_cursor = end;
+            self.deliver(nfts, shares);
+            self.retry_length - self.retry_cursor
+        }
+
+        // Where recipients claim donations and airdrops their accounts did not accept, once the
+        // sale has given any away
+        pub fn delivery_locker(&self) -> Option<ComponentAddress> {
+            self.delivery_locker.map(|locker| locker.address())
+        }
+
+        // Open, replace or (with None) close the burn-to-redeem exchange of an older collection
+        pub fn set_upgrade_path(&mut self, upgrade_path: Option<UpgradePath>) {
+            self.assert_not_finalized("Set Upgrade Path");
+            self.record(
+                "set_upgrade_path",
+                Authority::role("OWNER"),
+                format!("{:?}", upgrade_path),
+            );
+            if let Some(path) = &upgrade_path {
+                assert!(
+                    path.old_collection != self.nft_vault.resource_address(),
+                    "{} [Set Upgrade Path]: The sale collection cannot be upgraded into itself.",
+                    SaleError::InvalidConfiguration
+                );
+                assert!(
+                    path.burned_per_nft > 0,
+                    "{} [Set Upgrade Path]: At least one old NFT must be burned per NFT received.",
+                    SaleError::InvalidConfiguration
+                );
+                assert!(
+                    path.opens_at < path.closes_at,
+                    "{} [Set Upgrade Path]: The upgrade window must close after it opens.",
+                    SaleError::InvalidConfiguration
+                );
+            }
+            self.upgrade_path = upgrade_path;
+        }
+
+        // Burn NFTs of the configured older collection and receive NFTs from the vault at the
+        // configured rate. Only whole multiples of the rate are accepted
+        pub fn burn_to_redeem(&mut self, old_nft_bucket: NonFungibleBucket) -> NonFungibleBucket {
+            let path = self
+                .upgrade_path
+                .clone()
+                .unwrap_or_else(|| {
+                    panic!(
+                        "{} [Burn To Redeem]: No upgrade path is open.",
+                        SaleError::NotOffered
+                    )
+                });
+            let now = Runtime::current_epoch();
+            assert!(
+                now >= path.opens_at && now < path.closes_at,
+                "{} [Burn To Redeem]: Upgrades are only accepted from epoch {} until epoch {}.",
+                SaleError::OutsideWindow,
+                path.opens_at.number(),
+                path.closes_at.number()
+            );
+            assert_eq!(
+                old_nft_bucket.resource_address(),
+                path.old_collection,
+                "{} [Burn To Redeem]: Only NFTs of {:?} can be upgraded.",
+                SaleError::WrongResource,
+                path.old_collection
+            );
+
+            let burned_per_nft = Decimal::from(path.burned_per_nft);
+            let received = old_nft_bucket.amount() / burned_per_nft;
+            assert!(
+                received > Decimal::zero() && received * burned_per_nft == old_nft_bucket.amount(),
+                "{} [Burn To Redeem]: NFTs must be upgraded in multiples of {}.",
+                SaleError::InvalidQuantity,
+                path.burned_per_nft
+            );
+
+            self.check_unreserved(received, "Burn To Redeem");
+            let nfts = self.nft_vault.take(received);
+            let burned_ids = old_nft_bucket.non_fungible_local_ids();
+            old_nft_bucket.burn();
+
+            Runtime::emit_event(UpgradeRedeemedEvent {
+                burned_ids,
+                nft_ids: nfts.non_fungible_local_ids(),
+            });
+            nfts
+        }
+
+        // End the life of a sale that has nothing left to hand out: no NFTs, no funds and no
+        // outstanding escrows or holds. Every owner and admin method is locked for good and the
+        // component is marked finalized in its metadata. Stakes are the stakers' own and stay
+        // claimable through 'unstake'
+        pub fn finalize(&mut self) {
+            self.assert_not_finalized("Finalize");
+            self.record("finalize", Authority::role("OWNER"), String::new());
+            let payment_token = self.accepted_payment_token;
+            let unsettled = [
+                ("sale vault", self.nft_vault.amount()),
+                ("delisted vault", self.delisted_vault.amount()),
+                ("listing vault", self.scheduled_vault.amount()),
+                ("hold vault", self.held_vault.amount()),
+                ("retry queue", self.retry_vault.amount()),
+                ("purchase intents", self.intent_vault.amount()),
+                ("treasury", self.treasury_balance(payment_token)),
+                ("tips", self.tips.amount()),
+                ("revenue share", self.revenue_share_vault.amount()),
+                ("commissions", self.commission_vault.amount()),
+                ("insurance", self.insurance_vault.amount()),
+                (
+                    "bonus vault",
+                    self.bonus_vault.as_ref().map_or(Decimal::zero(), |vault| vault.amount()),
+                ),
+                ("allowance budgets", self.allowance_budgets),
+                ("escrows", Self::outstanding(self.escrow_receipt_address)),
+                ("holds", Self::outstanding(self.hold_receipt_address)),
+            ];
+            for (what, amount) in unsettled {
+                assert!(
+                    amount.is_zero(),
+                    "{} [Finalize]: The {} still holds {}.",
+                    SaleError::NotSettled,
+                    what,
+                    amount
+                );
+            }
+
+            self.sale_allowed = false;
+            self.finalized = true;
+            Runtime::global_component().set_metadata("finalized", true);
+            Runtime::emit_event(SaleFinalizedEvent {
+                epoch: Runtime::current_epoch(),
+            });
+        }
+
+        // Receipts of the given resource still in circulation
+        fn outstanding(receipt_address: ResourceAddress) -> Decimal {
+            ResourceManager::from_address(receipt_address)
+                .total_supply()
+                .unwrap_or_default()
+        }
+
+        // Turn away calls that would bring a finalized sale back to life
+        fn assert_not_finalized(&self, method: &str) {
+            assert!(
+                !self.finalized,
+                "{} [{}]: The sale has been finalized.",
+                SaleError::SaleFinalized,
+                method
+            );
+        }
+
+        // Permanently close the sale and let buyers return their NFTs for the price they paid,
+        // until the refund window closes
+        pub fn cancel_sale(&mut self) {
+            self.assert_not_finalized("Cancel Sale");
+            self.record("cancel_sale", Authority::role("OWNER"), String::new());
+            assert!(
+                self.refunds_close_at.is_none(),
+                "{} [Cancel Sale]: The sale has already been cancelled.",
+                SaleError::SaleCancelled
+            );
+            self.assert_refunds_guaranteed("Cancel Sale");
+            self.open_refunds();
+        }
+
+        // Close the sale for good and open the refund window
+        fn open_refunds(&mut self) {
+            let refunds_close_at = Runtime::current_epoch()
+                .after(REFUND_WINDOW_EPOCHS)
+                .unwrap();
+            self.sale_allowed = false;
+            self.refunds_close_at = Some(refunds_close_at);
+            Runtime::emit_event(SaleCancelledEvent { refunds_close_at });
+        }
+
+        // Return NFTs bought from a cancelled sale and get back exactly the price paid for them.
+        // What the treasury no longer holds, such as fees and shares paid out at the time of the
+        // sale, comes out of the insurance deposit. Gifted NFTs were never paid for and cannot be
+        // refunded
+        pub fn claim_refund(&mut self, nfts: NonFungibleBucket) -> Bucket {
+            let refunds_close_at = self
+                .refunds_close_at
+                .unwrap_or_else(|| {
+                    panic!(
+                        "{} [Claim Refund]: Refunds are only available after the sale is cancelled.",
+                        SaleError::NotOffered
+                    )
+                });
+            assert!(
+                Runtime::current_epoch() < refunds_close_at,
+                "{} [Claim Refund]: The refund window closed at epoch {}.",
+                SaleError::OutsideWindow,
+                refunds_close_at.number()
+            );
+            assert_eq!(
+                nfts.resource_address(),
+                self.nft_vault.resource_address(),
+                "{} [Claim Refund]: Only NFTs of the sale collection can be refunded.",
+                SaleError::WrongResource
+            );
+
+            let nft_ids = nfts.non_fungible_local_ids();
+            let mut refund = Decimal::zero();
+            for nft_id in &nft_ids {
+                refund += self
+                    .sale_prices
+                    .remove(nft_id)
+                    .unwrap_or_else(|| {
+                        panic!(
+                            "{} [Claim Refund]: NFT {} was not paid into this sale's treasury.",
+                            SaleError::NotFound,
+                            nft_id
+                        )
+                    });
+                if let Some(sale) = self.item_sales.get_mut(nft_id).unwrap().last_mut() {
+                    sale.refunded = true;
+                }
+            }
+            let revenue = self.treasury_balance(self.accepted_payment_token);
+            assert!(
+                revenue + self.insurance_vault.amount() >= refund,
+                "{} [Claim Refund]: Not enough revenue is left to refund {} tokens.",
+                SaleError::InsufficientFunds,
+                refund
+            );
+
+            self.nft_vault.put(nfts);
+            self.total_refunds += refund;
+            self.refundable -= refund;
+            Runtime::emit_event(RefundClaimedEvent { nft_ids, refund });
+            let mut payout = Bucket::new(self.accepted_payment_token);
+            if revenue > Decimal::zero() {
+                self.treasury_funds -= refund.min(revenue);
+                payout.put(
+                    self.treasury
+                        .get_mut(&self.accepted_payment_token)
+                        .unwrap()
+                        .take(refund.min(revenue)),
+                );
+            }
+            if refund > revenue {
+                let amount = refund - revenue;
+                payout.put(self.insurance_vault.take(amount));
+                Runtime::emit_event(InsuranceSlashedEvent { amount });
+            }
+            payout
+        }
+
+        // Lock an insurance deposit in the payment token until at least 'locked_until'. Should
+        // the sale be cancelled, it pays the refunds the treasury no longer covers and stays
+        // locked until the refund window closes. Topping up can only extend the lock. The owner
+        // may also pledge to reveal the collection by 'reveal_by', within the lock, and can only
+        // bring a pledged deadline forward
+        pub fn lock_insurance(
+            &mut self,
+            deposit: Bucket,
+            locked_until: Epoch,
+            reveal_by: Option<Epoch>,
+        ) {
+            self.assert_not_finalized("Lock Insurance");
+            self.record(
+                "lock_insurance",
+                Authority::role("OWNER"),
+                format!("{} until {:?} reveal by {:?}", deposit.amount(), locked_until, reveal_by),
+            );
+            let now = Runtime::current_epoch();
+            assert!(
+                locked_until > now,
+                "{} [Lock Insurance]: The insurance has to be locked until a future epoch.",
+                SaleError::InvalidConfiguration
+            );
+            if let Some(reveal_by) = reveal_by {
+                assert!(
+                    reveal_by > now && reveal_by <= locked_until,
+                    "{} [Lock Insurance]: The reveal deadline must fall within the insurance lock.",
+                    SaleError::InvalidConfiguration
+                );
+                assert!(
+                    self.reveal_deadline.is_none_or(|current| reveal_by <= current),
+                    "{} [Lock Insurance]: The reveal deadline can only be brought forward.",
+                    SaleError::InvalidConfiguration
+                );
+                self.reveal_deadline = Some(reveal_by);
+            }
+            assert_eq!(
+                deposit.resource_address(),
+                self.accepted_payment_token,
+                "{} [Lock Insurance]: Insurance is only accepted in {:?}",
+                SaleError::WrongPaymentToken,
+                self.accepted_payment_token
+            );
+            assert!(
+                self.insurance_locked_until.is_none_or(|current| locked_until >= current),
+                "{} [Lock Insurance]: The insurance lock can only be extended.",
+                SaleError::InvalidConfiguration
+            );
+            self.insurance_vault.put(deposit);
+            self.insurance_locked_until = Some(locked_until);
+        }
+
+        // Take back what is left of the insurance deposit once its lock is over
+        pub fn withdraw_insurance(&mut self) -> Bucket {
+            self.assert_not_finalized("Withdraw Insurance");
+            self.record("withdraw_insurance", Authority::role("OWNER"), String::new());
+            let unlocks_at = match self.refunds_close_at {
+                Some(refunds_close_at) => refunds_close_at,
+                None => self.insurance_locked_until.unwrap_or_else(|| {
+                    panic!(
+                        "{} [Withdraw Insurance]: No insurance was deposited.",
+                        SaleError::NotFound
+                    )
+                }),
+            };
+            assert!(
+                Runtime::current_epoch() >= unlocks_at,
+                "{} [Withdraw Insurance]: The insurance is locked until epoch {}.",
+                SaleError::OutsideWindow,
+                unlocks_at.number()
+            );
+            self.insurance_vault.take_all()
+        }
+
+        // Keep the pledge to reveal the collection, before its deadline
+        pub fn reveal(&mut self) {
+            self.assert_not_finalized("Reveal");
+            self.record("reveal", Authority::role("OWNER"), String::new());
+            let reveal_deadline = self.reveal_deadline.unwrap_or_else(|| {
+                panic!(
+                    "{} [Reveal]: No reveal was pledged.",
+                    SaleError::NotFound
+                )
+            });
+            assert!(
+                Runtime::current_epoch() < reveal_deadline,
+                "{} [Reveal]: The reveal deadline passed at epoch {}.",
+                SaleError::OutsideWindow,
+                reveal_deadline.number()
+            );
+            self.reveal_deadline = None;
+        }
+
+        // Cancel a sale whose owner missed the reveal deadline, so buyers can claim refunds backed
+        // by the insurance deposit. Callable by anyone once the deadline has passed
+        pub fn slash_insurance(&mut self) {
+            self.assert_not_finalized("Slash Insurance");
+            let reveal_deadline = self.reveal_deadline.unwrap_or_else(|| {
+                panic!(
+                    "{} [Slash Insurance]: No reveal is pending.",
+                    SaleError::NotFound
+                )
+            });
+            assert!(
+                Runtime::current_epoch() >= reveal_deadline,
+                "{} [Slash Insurance]: The owner has until epoch {} to reveal.",
+                SaleError::OutsideWindow,
+                reveal_deadline.number()
+            );
+            assert!(
+                self.refunds_close_at.is_none(),
+                "{} [Slash Insurance]: The sale has already been cancelled.",
+                SaleError::SaleCancelled
+            );
+            self.reveal_deadline = None;
+            self.open_refunds();
+        }
+
+        // The insurance deposit, the epoch it is locked until unless the sale is cancelled, and
+        // the pending reveal deadline
+        pub fn insurance(&self) -> (Decimal, Option<Epoch>, Option<Epoch>) {
+            (self.insurance_vault.amount(), self.insurance_locked_until, self.reveal_deadline)
+        }
+
+        // Load the bonus vault, e.g. with the project's utility token. Only one fungible bonus
+        // token can be used per sale
+        pub fn deposit_bonus(&mut self, bonus: Bucket) {
+            self.assert_not_finalized("Deposit Bonus");
+            self.record(
+                "deposit_bonus",
+                Authority::role("OWNER"),
+                format!("{} of {:?}", bonus.amount(), bonus.resource_address()),
+            );
+            assert!(
+                matches!(
+                    ResourceManager::from_address(bonus.resource_address()).resource_type(),
+                    ResourceType::Fungible { divisibility: _ }
+                ),
+                "{} [Deposit Bonus]: The bonus must be a fungible token.",
+                SaleError::InvalidConfiguration
+            );
+            match &mut self.bonus_vault {
+                Some(bonus_vault) => bonus_vault.put(bonus),
+                None => self.bonus_vault = Some(Vault::with_bucket(bonus)),
+            }
+        }
+
+        // Set the bonus handed out with every NFT bought
+        pub fn set_bonus_per_nft(&mut self, bonus_per_nft: Decimal) {
+            self.assert_not_finalized("Set Bonus");
+            self.record("set_bonus_per_nft", Authority::role("OWNER"), bonus_per_nft.to_string());
+            assert!(
+                bonus_per_nft >= Decimal::zero(),
+                "{} [Set Bonus]: The bonus cannot be negative.",
+                SaleError::InvalidConfiguration
+            );
+            self.bonus_per_nft = bonus_per_nft;
+        }
+
+        // Offer (or with None withdraw) the staking discount. Existing stakes keep their lock
+        // period, and lose their discount when the offer is withdrawn
+        pub fn set_stake_discount(&mut self, stake_discount: Option<StakeDiscount>) {
+            self.assert_not_finalized("Set Stake Discount");
+            self.record(
+                "set_stake_discount",
+                Authority::role("OWNER"),
+                format!("{:?}", stake_discount),
+            );
+            if let Some(terms) = &stake_discount {
+                assert!(
+                    terms.discount > Decimal::zero() && terms.discount < Decimal::one(),
+                    "{} [Set Stake Discount]: The discount must be above 0 and below 1.",
+                    SaleError::InvalidConfiguration
+                );
+                self.check_free_mode(self.price, true, "Set Stake Discount");
+                assert!(
+                    terms.minimum_stake > Decimal::zero(),
+                    "{} [Set Stake Discount]: The minimum stake must be positive.",
+                    SaleError::InvalidConfiguration
+                );
+            }
+            self.stake_discount = stake_discount;
+        }
+
+        // Honor the given resources from earlier drops with a discount, or stop with None
+        pub fn set_loyalty_discount(&mut self, loyalty_discount: Option<LoyaltyDiscount>) {
+            self.assert_not_finalized("Set Loyalty Discount");
+            self.record(
+                "set_loyalty_discount",
+                Authority::role("OWNER"),
+                format!("{:?}", loyalty_discount),
+            );
+            if let Some(terms) = &loyalty_discount {
+                assert!(
+                    terms.discount > Decimal::zero() && terms.discount < Decimal::one(),
+                    "{} [Set Loyalty Discount]: The discount must be above 0 and below 1.",
+                    SaleError::InvalidConfiguration
+                );
+                self.check_free_mode(self.price, true, "Set Loyalty Discount");
+                assert!(
+                    !terms.honored.is_empty(),
+                    "{} [Set Loyalty Discount]: At least one resource has to be honored.",
+                    SaleError::InvalidConfiguration
+                );
+            }
+            self.loyalty_discount = loyalty_discount;
+        }
+
+        // Split buyers who name their account into cohorts paying slightly different prices or
+        // earning extra bonus tokens, recorded in every 'NftsSoldEvent', to measure how price
+        // sensitive buyers are. An empty list ends the experiment
+        pub fn set_price_cohorts(&mut self, price_cohorts: Vec<PriceCohort>) {
+            self.assert_not_finalized("Set Price Cohorts");
+            self.record(
+                "set_price_cohorts",
+                Authority::role("OWNER"),
+                format!("{:?}", price_cohorts),
+            );
+            for cohort in &price_cohorts {
+                assert!(
+                    cohort.adjustment.checked_abs().unwrap() <= self.max_cohort_adjustment,
+                    "{} [Set Price Cohorts]: Cohort prices may differ by at most {}.",
+                    SaleError::InvalidConfiguration,
+                    self.max_cohort_adjustment
+                );
+                assert!(
+                    cohort.bonus_per_nft >= Decimal::zero()
+                        && cohort.bonus_per_nft <= self.max_cohort_bonus,
+                    "{} [Set Price Cohorts]: Cohort bonuses must be between 0 and {}.",
+                    SaleError::InvalidConfiguration,
+                    self.max_cohort_bonus
+                );
+            }
+            self.price_cohorts = price_cohorts;
+        }
+
+        pub fn price_cohorts(&self) -> Vec<PriceCohort> {
+            self.price_cohorts.clone()
+        }
+
+        // Set the share of every purchase's cost paid back in loyalty tokens, 0 to stop cashback
+        pub fn set_cashback_rate(&mut self, cashback_rate: Decimal) {
+            self.assert_not_finalized("Set Cashback");
+            self.record("set_cashback_rate", Authority::role("OWNER"), cashback_rate.to_string());
+            assert!(
+                cashback_rate >= Decimal::zero() && cashback_rate <= Decimal::one(),
+                "{} [Set Cashback]: The cashback rate must be between 0 and 1.",
+                SaleError::InvalidConfiguration
+            );
+            self.cashback_rate = cashback_rate;
+        }
+
+        // Reject payments below 'minimum_payment', e.g. micro-payments far below the price or
+        // accidental tiny transfers, 0 to accept any amount
+        pub fn set_minimum_payment(&mut self, minimum_payment: Decimal) {
+            self.assert_not_finalized("Set Minimum Payment");
+            self.record(
+                "set_minimum_payment",
+                Authority::role("OWNER"),
+                minimum_payment.to_string(),
+            );
+            Self::validate_price(minimum_payment, self.payment_divisibility);
+            self.minimum_payment = minimum_payment;
+        }
+
+        // Emit a 'LargePurchaseEvent' for every purchase costing at least the given amount, or
+        // stop with None
+        pub fn set_large_purchase_threshold(&mut self, large_purchase_threshold: Option<Decimal>) {
+            self.assert_not_finalized("Set Large Purchase Threshold");
+            self.record(
+                "set_large_purchase_threshold",
+                Authority::role("OWNER"),
+                format!("{:?}", large_purchase_threshold),
+            );
+            if let Some(threshold) = large_purchase_threshold {
+                Self::validate_price(threshold, self.payment_divisibility);
+            }
+            self.large_purchase_threshold = large_purchase_threshold;
+        }
 
+        // Take back whatever is left in the bonus vault
+        pub fn withdraw_bonus(&mut self) -> Bucket {
+            self.assert_not_finalized("Withdraw Bonus");
+            self.record("withdraw_bonus", Authority::role("OWNER"), String::new());
+            self.bonus_vault
+                .as_mut()
+                .unwrap_or_else(|| {
+                    panic!(
+                        "{} [Withdraw Bonus]: No bonus has been deposited.",
+                        SaleError::NotFound
+                    )
+                })
+                .take_all()
+        }
 
-        // Once the vault holds some funds they can be withdrawn using this method
+        // Once the vault holds some funds they can be withdrawn using this method. Revenue stays
+        // escrowed for refunds while the refund window of a cancelled sale is open, anything
+        // beyond what the refunds could take remains withdrawable
         pub fn withdraw_profits(&mut self) -> Bucket {
+            self.withdraw_from_treasury(self.accepted_payment_token)
+        }
+
+        // Where the sale's payment tokens are, and how much of them the owner can withdraw
+        pub fn treasury_breakdown(&self) -> TreasuryBreakdown {
+            let locked_for_refunds = self.locked_for_refunds();
+            TreasuryBreakdown {
+                free: self.treasury_balance(self.accepted_payment_token) - locked_for_refunds,
+                locked_for_refunds,
+                escrowed: self.escrowed_funds,
+                held_deposits: self.held_deposits,
+            }
+        }
+
+        // Cross-check the running totals of the sale against the vaults they track, emitting an
+        // 'InvariantViolatedEvent' for every mismatch. Returns whether all of them hold. Costs the
+        // same however many escrows, holds or deliveries there are, which leaves the funds of
+        // individual escrows and holds unchecked
+        pub fn verify_invariants(&self) -> bool {
+            let shares_owed =
+                self.revenue_per_share * self.backer_shares - self.revenue_claimed_total;
+            let sold = self.total_revenue - self.total_refunds;
+            let reserved = Decimal::from(self.reserved_nfts());
+            let (held, retried, intended) = (
+                Decimal::from(self.open_holds),
+                Decimal::from(self.retry_queued),
+                Decimal::from(self.intents_queued),
+            );
+            let treasury = self.treasury_balance(self.accepted_payment_token);
+            let commissions = self.commission_vault.amount();
+            let revenue_share = self.revenue_share_vault.amount();
+            let nfts = self.nft_vault.amount();
+            // What should be there and what is, matching exactly or within a bound
+            let checks = [
+                ("held NFTs", held, self.held_vault.amount()),
+                ("retry queue", retried, self.retry_vault.amount()),
+                ("purchase intents", intended, self.intent_vault.amount()),
+                ("treasury", self.treasury_funds, treasury),
+                ("commissions", self.commissions_owed, commissions),
+            ]
+            .map(|(invariant, expected, actual)| (invariant, expected, actual, expected == actual));
+            let bounds = [
+                ("revenue share", shares_owed, revenue_share, revenue_share >= shares_owed),
+                ("refundable", sold, self.refundable, self.refundable <= sold),
+                ("pass reservation", reserved, nfts, nfts >= reserved),
+            ];
+            let mut holds = true;
+            for (invariant, expected, actual, fine) in checks.into_iter().chain(bounds) {
+                if !fine {
+                    holds = false;
+                    Runtime::emit_event(InvariantViolatedEvent {
+                        invariant: invariant.to_string(),
+                        expected,
+                        actual,
+                    });
+                }
+            }
+            holds
+        }
+
+        // Whether the guarantee given with every sale has run out, a sale that ended long
+        // enough ago can no longer be cancelled and keeps no revenue back for refunds
+        fn refund_guarantee_lapsed(&self) -> bool {
+            self.refunds_guaranteed_until
+                .is_some_and(|until| Runtime::current_epoch() >= until)
+        }
+
+        fn assert_refunds_guaranteed(&self, method: &str) {
+            assert!(
+                !self.refund_guarantee_lapsed(),
+                "{} [{}]: The refund guarantee of the ended sale has run out.",
+                SaleError::OutsideWindow,
+                method
+            );
+        }
+
+        // Revenue refunds could still claim, reserved from the first sale until either the
+        // refund window or the guarantee of the ended sale closes
+        fn locked_for_refunds(&self) -> Decimal {
+            let open = match self.refunds_close_at {
+                Some(refunds_close_at) => Runtime::current_epoch() < refunds_close_at,
+                None => !self.refund_guarantee_lapsed(),
+            };
+            if open {
+                self.refundable
+                    .min(self.treasury_balance(self.accepted_payment_token))
+            } else {
+                Decimal::zero()
+            }
+        }
+
+        // Withdraw the change buyers left as tips
+        pub fn withdraw_tips(&mut self) -> Bucket {
+            self.assert_not_finalized("Withdraw Tips");
+            self.record("withdraw_tips", Authority::role("OWNER"), String::new());
+            self.tips.take_all()
+        }
+
+        // Withdraw everything the treasury holds of one token, but what refunds could still take
+        pub fn withdraw_from_treasury(&mut self, resource: ResourceAddress) -> Bucket {
+            self.assert_not_finalized("Withdraw Payment");
+            self.record(
+                "withdraw_from_treasury",
+                Authority::role("OWNER"),
+                format!("{:?}", resource),
+            );
+            let locked = if resource == self.accepted_payment_token {
+                self.locked_for_refunds()
+            } else {
+                Decimal::zero()
+            };
+            let available = self.treasury_balance(resource) - locked;
+            if available <= Decimal::zero() && locked > Decimal::zero() {
+                match self.refunds_close_at.or(self.refunds_guaranteed_until) {
+                    Some(until) => panic!(
+                        "{} [Withdraw Payment]: Revenue is held for refunds until epoch {}.",
+                        SaleError::OutsideWindow,
+                        until.number()
+                    ),
+                    None => panic!(
+                        "{} [Withdraw Payment]: Revenue is held for refunds until the sale ends.",
+                        SaleError::OutsideWindow
+                    ),
+                }
+            }
             // Check if the tokens have been sold or not
             assert!(
-                self.is_sold(),
-                "[Withdraw Payment]: Cannot withdraw funds when the payment vault is empty."
+                available > Decimal::zero(),
+                "{} [Withdraw Payment]: Cannot withdraw funds when the payment vault is empty.",
+                SaleError::InsufficientFunds
             );
-            return self.xrd_vault.take_all();
+            if resource == self.accepted_payment_token {
+                self.treasury_funds -= available;
+            }
+            self.treasury.get_mut(&resource).unwrap().take(available)
         }
 
         // Re set the price from the original set at instantiation
-        pub fn change_price(&mut self, price: Decimal) {
+        pub fn change_price(&mut self, badge: Option<Proof>, price: Decimal) {
+            let authority = self.presented(badge, &["pricer", "admin", "OWNER"], "Change Price");
+            self.change_price_as(authority, price);
+        }
+
+        fn change_price_as(&mut self, authority: Authority, price: Decimal) {
+            self.assert_not_finalized("Change Price");
+            self.record("change_price", authority, price.to_string());
             // Checking that the new price can be set
+            Self::validate_price(price, self.payment_divisibility);
+
+            if let Some(limit) = &self.price_change_limit {
+                if let Some(last_price_change) = self.last_price_change {
+                    let allowed_at = last_price_change.after(limit.min_interval_epochs).unwrap();
+                    assert!(
+                        Runtime::current_epoch() >= allowed_at,
+                        "{} [Change Price]: The price cannot be changed again before epoch {}.",
+                        SaleError::OutsideWindow,
+                        allowed_at.number()
+                    );
+                }
+                // A free sale has no base to measure a relative change against
+                if self.price > Decimal::zero() {
+                    let change = (price - self.price).checked_abs().unwrap();
+                    assert!(
+                        change <= self.price * limit.max_change,
+                        "{} [Change Price]: The price can change by at most {} of {} at once.",
+                        SaleError::PriceChangeTooLarge,
+                        limit.max_change,
+                        self.price
+                    );
+                }
+            }
+            self.set_price(price);
+        }
+
+        // A free sale only hands out NFTs to VIP badge holders, so nothing may be left that is
+        // bought at the price: discount offers, or escrows still waiting for their conversion
+        fn check_free_mode(&self, price: Decimal, discounted: bool, method: &str) {
+            if !price.is_zero() {
+                return;
+            }
             assert!(
-                price >= Decimal::zero(),
-                "[Change Price]: The tokens can not be sold for a negative amount."
+                !discounted,
+                "{} [{}]: Discounts cannot be offered on a free sale.",
+                SaleError::FreeSaleGated,
+                method
+            );
+            assert!(
+                self.escrows_converted == self.next_escrow_id || self.escrowed_funds.is_zero(),
+                "{} [{}]: Escrows have to be converted or claimed back before the sale turns free.",
+                SaleError::FreeSaleGated,
+                method
             );
+        }
+
+        // Change the price without the rate limit, for the owner to correct mistakes or react to
+        // a compromised admin or pricer badge
+        pub fn override_price(&mut self, price: Decimal) {
+            self.assert_not_finalized("Override Price");
+            self.record("override_price", Authority::role("OWNER"), price.to_string());
+            Self::validate_price(price, self.payment_divisibility);
+            self.set_price(price);
+        }
+
+        fn set_price(&mut self, price: Decimal) {
+            let discounted = self.stake_discount.is_some() || self.loyalty_discount.is_some();
+            self.check_free_mode(price, discounted, "Set Price");
             self.price = price;
+            Runtime::global_component().set_metadata("price", price);
+            self.last_price_change = Some(Runtime::current_epoch());
+        }
+
+        // Set (or with None lift) the limit on how often and how far 'change_price' may move the
+        // price
+        pub fn set_price_change_limit(&mut self, price_change_limit: Option<PriceChangeLimit>) {
+            self.assert_not_finalized("Set Price Change Limit");
+            self.record(
+                "set_price_change_limit",
+                Authority::role("OWNER"),
+                format!("{:?}", price_change_limit),
+            );
+            if let Some(limit) = &price_change_limit {
+                assert!(
+                    limit.max_change >= Decimal::zero(),
+                    "{} [Set Price Change Limit]: The maximum change cannot be negative.",
+                    SaleError::InvalidConfiguration
+                );
+            }
+            self.price_change_limit = price_change_limit;
+        }
+
+        // Replace the table of per-transaction limits by remaining supply, empty to only apply the
+        // limit set at instantiation
+        pub fn set_limit_tiers(&mut self, badge: Option<Proof>, limit_tiers: Vec<LimitTier>) {
+            let authority = self.presented(badge, &["admin", "OWNER"], "Set Limit Tiers");
+            self.set_limit_tiers_as(authority, limit_tiers);
+        }
+
+        fn set_limit_tiers_as(&mut self, authority: Authority, limit_tiers: Vec<LimitTier>) {
+            self.assert_not_finalized("Set Limit Tiers");
+            self.record("set_limit_tiers", authority, format!("{:?}", limit_tiers));
+            assert!(
+                limit_tiers.iter().all(|tier| tier.max_nfts_per_transaction > 0),
+                "{} [Set Limit Tiers]: At least one NFT must be purchasable per transaction.",
+                SaleError::InvalidConfiguration
+            );
+            self.limit_tiers = limit_tiers;
+        }
+
+        // Apply several changes in one call, in order, so buyers never see a half-updated sale.
+        // Each change is checked and audited as if made on its own, under the authority of the
+        // caller. Admins can batch the changes they may make on their own, the rest is the owner's
+        pub fn configure(&mut self, badge: Option<Proof>, batch: Vec<AdminAction>) {
+            let authority = self.presented(badge, &["admin", "OWNER"], "Configure");
+            assert!(
+                !batch.is_empty(),
+                "{} [Configure]: At least one change has to be given.",
+                SaleError::InvalidQuantity
+            );
+            for action in batch.clone() {
+                let owner_only = !matches!(
+                    action,
+                    AdminAction::StartSale
+                        | AdminAction::EndSale
+                        | AdminAction::ChangePrice(_)
+                        | AdminAction::SetLimitTiers(_)
+                );
+                assert!(
+                    !owner_only || authority.role == "OWNER",
+                    "{} [Configure]: Only the owner can make the change {:?}.",
+                    SaleError::WrongResource,
+                    action
+                );
+                match action {
+                    AdminAction::StartSale => self.start_sale_as(authority.clone()),
+                    AdminAction::EndSale => self.end_sale_as(authority.clone()),
+                    AdminAction::ChangePrice(price) => {
+                        self.change_price_as(authority.clone(), price)
+                    }
+                    AdminAction::SetLimitTiers(limit_tiers) => {
+                        self.set_limit_tiers_as(authority.clone(), limit_tiers)
+                    }
+                    AdminAction::SetPurchaseCooldown(cooldown) => {
+                        self.set_purchase_cooldown(cooldown)
+                    }
+                    AdminAction::SetMinimumPayment(minimum) => self.set_minimum_payment(minimum),
+                    AdminAction::SetPassWindow(opens_at, closes_at) => {
+                        self.set_pass_window(opens_at, closes_at)
+                    }
+                    AdminAction::SetGatedPhases(gated_phases) => {
+                        self.set_gated_phases(gated_phases)
+                    }
+                    AdminAction::SetStakeDiscount(discount) => self.set_stake_discount(discount),
+                    AdminAction::SetLoyaltyDiscount(discount) => {
+                        self.set_loyalty_discount(discount)
+                    }
+                    AdminAction::SetCashbackRate(rate) => self.set_cashback_rate(rate),
+                    AdminAction::SetBonusPerNft(bonus) => self.set_bonus_per_nft(bonus),
+                }
+            }
+            Runtime::emit_event(ConfigurationChangedEvent { actions: batch });
+        }
+
+        // The number of NFTs a single purchase may take right now
+        pub fn max_per_transaction(&self) -> u16 {
+            let remaining = self.nft_vault.amount();
+            self.limit_tiers
+                .iter()
+                .filter(|tier| remaining <= Decimal::from(tier.up_to_remaining))
+                .map(|tier| tier.max_nfts_per_transaction)
+                .fold(self.max_nfts_per_transaction, u16::min)
+        }
+
+        // The current phase of the sale with what is left in it, for dApps rendering countdowns
+        pub fn current_phase_info(&self) -> PhaseInfo {
+            let now = Runtime::current_epoch();
+            let epochs_until = |epoch: Epoch| epoch.number().checked_sub(now.number());
+            let inventory = self.nft_vault.amount();
+            let reserved = Decimal::from(self.reserved_nfts());
+
+            let (phase, remaining, epochs_until_next_phase) = match (self.refunds_close_at, self.pass_window) {
+                (Some(refunds_close_at), _) => {
+                    (SalePhase::Refunding, Decimal::zero(), epochs_until(refunds_close_at))
+                }
+                (None, Some((opens_at, closes_at))) if now >= opens_at && now < closes_at => {
+                    (SalePhase::Priority, reserved, epochs_until(closes_at))
+                }
+                (None, pass_window) => {
+                    let phase = if self.sale_allowed && !self.buying_paused {
+                        SalePhase::Public
+                    } else {
+                        SalePhase::Closed
+                    };
+                    let window_opens_in = pass_window
+                        .and_then(|(opens_at, _)| epochs_until(opens_at))
+                        .filter(|epochs| *epochs > 0);
+                    (phase, inventory - reserved, window_opens_in)
+                }
+            };
+            // Outside refunds, the next gated phase starting may come before any other change
+            let next_gated_phase = self
+                .gated_phases
+                .iter()
+                .find(|phase| phase.starts_at > now)
+                .and_then(|phase| epochs_until(phase.starts_at))
+                .filter(|_| self.refunds_close_at.is_none());
+            let epochs_until_next_phase = match (epochs_until_next_phase, next_gated_phase) {
+                (Some(epochs), Some(gated)) => Some(epochs.min(gated)),
+                (epochs, gated) => epochs.or(gated),
+            };
+            PhaseInfo {
+                phase,
+                price: self.price,
+                remaining,
+                epochs_until_next_phase,
+                gated_phase: self
+                    .current_gated_phase()
+                    .map(|index| self.gated_phases[index].name.clone()),
+            }
+        }
+
+        // Link the sale to the dApp definition account of the project selling it
+        pub fn set_dapp_definition(&mut self, dapp_definition: ComponentAddress) {
+            self.assert_not_finalized("Set dApp Definition");
+            self.record(
+                "set_dapp_definition",
+                Authority::role("OWNER"),
+                format!("{:?}", dapp_definition),
+            );
+            Runtime::global_component().set_metadata("dapp_definition", GlobalAddress::from(dapp_definition));
+        }
+
+        // Set the website of the collection shown by wallets and explorers
+        pub fn set_info_url(&mut self, info_url: String) {
+            self.assert_not_finalized("Set Info URL");
+            self.record("set_info_url", Authority::role("OWNER"), info_url.clone());
+            Runtime::global_component().set_metadata("info_url", Url::of(info_url));
+        }
+
+        // Replace the project's social links, e.g. X, Discord and Telegram
+        pub fn set_social_urls(&mut self, social_urls: Vec<String>) {
+            self.assert_not_finalized("Set Social URLs");
+            self.record("set_social_urls", Authority::role("OWNER"), format!("{:?}", social_urls));
+            let social_urls: Vec<Url> = social_urls.into_iter().map(Url::of).collect();
+            Runtime::global_component().set_metadata("social_urls", social_urls);
+        }
+
+        // Page through the audit log from sequence number 'start'. Returns the total number of
+        // entries alongside at most 'limit' of them
+        pub fn audit_log(&self, start: u64, limit: u32) -> (u64, Vec<AuditEntry>) {
+            let end = self.audit_log_length.min(start.saturating_add(limit.into()));
+            let entries = (start..end)
+                .map(|sequence| self.audit_log.get(&sequence).unwrap().clone())
+                .collect();
+            (self.audit_log_length, entries)
+        }
+
+        // Close the current accounting period, recording the sales and refunds since the last
+        // one in an immutable period record
+        pub fn close_accounting_period(&mut self, badge: Option<Proof>) -> AccountingPeriod {
+            let authority = self.presented(
+                badge,
+                &["admin", "automation", "OWNER"],
+                "Close Accounting Period",
+            );
+            self.assert_not_finalized("Close Accounting Period");
+            self.record("close_accounting_period", authority, String::new());
+            let (revenue, units, refunds) = match self.accounting_period_count.checked_sub(1) {
+                Some(last) => {
+                    let last = self.accounting_periods.get(&last).unwrap();
+                    (
+                        self.total_revenue - last.total_revenue,
+                        self.total_units - last.total_units,
+                        self.total_refunds - last.total_refunds,
+                    )
+                }
+                None => (self.total_revenue, self.total_units, self.total_refunds),
+            };
+            let closed_at = Runtime::current_epoch();
+            let period = AccountingPeriod {
+                opened_at: self.period_opened_at,
+                closed_at,
+                revenue,
+                units,
+                refunds,
+                total_revenue: self.total_revenue,
+                total_units: self.total_units,
+                total_refunds: self.total_refunds,
+            };
+
+            let index = self.accounting_period_count;
+            self.accounting_periods.insert(index, period.clone());
+            self.accounting_period_count += 1;
+            self.period_opened_at = closed_at;
+            Runtime::emit_event(AccountingPeriodClosedEvent {
+                index,
+                period: period.clone(),
+            });
+            period
+        }
+
+        // Page through the closed accounting periods from index 'start'. Returns the total number
+        // of periods alongside at most 'limit' of them
+        pub fn accounting_periods(&self, start: u64, limit: u32) -> (u64, Vec<AccountingPeriod>) {
+            let end = self.accounting_period_count.min(start.saturating_add(limit.into()));
+            let periods = (start..end)
+                .map(|index| self.accounting_periods.get(&index).unwrap().clone())
+                .collect();
+            (self.accounting_period_count, periods)
+        }
+
+        // Page through the buyers from index 'cursor' for snapshot-based airdrops by other
+        // components. Returns the total number of buyers alongside at most 'limit' of them as
+        // SBOR-encoded (account, units, spend) tuples
+        pub fn export_buyers(&self, cursor: u64, limit: u32) -> (u64, Vec<u8>) {
+            let end = self.buyer_count.min(cursor.saturating_add(limit.into()));
+            let buyers: Vec<(ComponentAddress, u64, Decimal)> = (cursor..end)
+                .map(|index| {
+                    let account = *self.buyers.get(&index).unwrap();
+                    let (units, spend) = *self.buyer_totals.get(&account).unwrap();
+                    (account, units, spend)
+                })
+                .collect();
+            (self.buyer_count, scrypto_encode(&buyers).unwrap())
+        }
+
+        // Export the sale state page by page for indexers and auditors: the summary at cursor 0,
+        // then the inventory ids. Returns the cursor of the next page, if there is one. Pages
+        // walk the stocked NFTs in the order they were stocked and leave out those no longer in
+        // the vault, so sales between pages neither repeat nor skip an id
+        pub fn export_state_snapshot(&self, cursor: u64) -> (SnapshotChunk, Option<u64>) {
+            let inventory = self.nft_vault.amount();
+            if cursor == 0 {
+                let summary = SaleSnapshot {
+                    nft_resource_address: self.nft_vault.resource_address(),
+                    accepted_payment_token: self.accepted_payment_token,
+                    price: self.price,
+                    max_nfts_per_transaction: self.max_nfts_per_transaction,
+                    limit_tiers: self.limit_tiers.clone(),
+                    inventory_rules: self.inventory_rules.clone(),
+                    platform_fee: self.platform_fee.clone(),
+                    stake_discount: self.stake_discount.clone(),
+                    price_change_limit: self.price_change_limit.clone(),
+                    upgrade_path: self.upgrade_path.clone(),
+                    bonus_per_nft: self.bonus_per_nft,
+                    cashback_rate: self.cashback_rate,
+                    pass_window: self.pass_window,
+                    sale_allowed: self.sale_allowed,
+                    refunds_close_at: self.refunds_close_at,
+                    inventory,
+                    delisted: self.delisted_vault.amount(),
+                    reserved_for_passes: self.reserved_for_passes,
+                    treasury: self.treasury_balance(self.accepted_payment_token),
+                    recalls: self.next_recall_id,
+                    escrows: self.next_escrow_id,
+                    audit_log_length: self.audit_log_length,
+                    change_policy: self.change_policy,
+                    purchase_cooldown: self.purchase_cooldown,
+                    minimum_payment: self.minimum_payment,
+                    gated_phases: self.gated_phases.clone(),
+                    max_fee_rate: self.max_fee_rate,
+                    max_cohort_adjustment: self.max_cohort_adjustment,
+                    max_cohort_bonus: self.max_cohort_bonus,
+                    loyalty_discount: self.loyalty_discount.clone(),
+                    price_cohorts: self.price_cohorts.clone(),
+                    allowlist_root: self.allowlist_root,
+                    hold_terms: self.hold_terms.clone(),
+                    large_purchase_threshold: self.large_purchase_threshold,
+                    donation_list: self.donation_list.clone(),
+                    delivery_policy: self.delivery_policy,
+                    deferred_delivery: self.deferred_delivery,
+                };
+                let next = (self.stocked_length > 0).then_some(1);
+                return (SnapshotChunk::Summary(Box::new(summary)), next);
+            }
+
+            let start = cursor - 1;
+            let end = self.stocked_length.min(start + SNAPSHOT_CHUNK_IDS);
+            let ids: Vec<NonFungibleLocalId> = (start..end)
+                .map(|position| self.stocked_ids.get(&position).unwrap().clone())
+                .filter(|nft_id| self.nft_vault.contains_non_fungible(nft_id))
+                .collect();
+            let next = (end < self.stocked_length).then_some(end + 1);
+            (SnapshotChunk::Inventory(ids), next)
+        }
+
+        // The role the caller of a method several roles may call acts under. Auth zones are out
+        // of a component's sight, so callers other than the owner hand over their badge to be
+        // told apart, and a call without one is held to the owner rule
+        fn presented(&self, badge: Option<Proof>, roles: &[&str], method: &str) -> Authority {
+            let component = Runtime::global_component();
+            let rule_of = |role: &str| match role {
+                "OWNER" => component.get_owner_role().rule,
+                role => component.get_role(role).unwrap_or(AccessRule::DenyAll),
+            };
+            let Some(badge) = badge else {
+                Runtime::assert_access_rule(rule_of("OWNER"));
+                return Authority::role("OWNER");
+            };
+            let resource = badge.resource_address();
+            let role = roles
+                .iter()
+                .find(|role| rule_of(role) == rule!(require(resource)))
+                .unwrap_or_else(|| {
+                    panic!(
+                        "{} [{}]: The badge of one of the roles {:?} must be presented.",
+                        SaleError::WrongResource,
+                        method,
+                        roles
+                    )
+                });
+            let badge = badge.check(resource);
+            let badge = if resource.is_fungible() {
+                ResourceOrNonFungible::Resource(resource)
+            } else {
+                let badge_id = badge.as_non_fungible().non_fungible_local_id();
+                ResourceOrNonFungible::NonFungible(NonFungibleGlobalId::new(resource, badge_id))
+            };
+            Authority {
+                role: role.to_string(),
+                badge: Some(badge),
+            }
+        }
+
+        // Append a restricted method call to the audit log
+        fn record(&mut self, action: &str, authority: Authority, params: String) {
+            self.audit_log.insert(
+                self.audit_log_length,
+                AuditEntry {
+                    action: action.to_owned(),
+                    epoch: Runtime::current_epoch(),
+                    params,
+                    authority,
+                },
+            );
+            self.audit_log_length += 1;
         }
 
         // Returns the current price of the nft
         pub fn price(&self) -> (ResourceAddress, Decimal) {
-            return (self.accepted_payment_token, self.price);
+            (self.accepted_payment_token, self.price)
         }
 
-        // Check the xrd vault to verify if any sales have happened yet
+        // Check the treasury to verify if any sales have happened yet
         pub fn is_sold(&self) -> bool {
-            return !self.xrd_vault.is_empty();
+            self.treasury_balance(self.accepted_payment_token) > Decimal::zero()
         }
     }
 }
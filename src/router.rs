@@ -0,0 +1,55 @@
+use crate::nft_sale::NFTSale;
+use nft_sale_interface::{PurchaseLeg, SaleError};
+use scrypto::prelude::*;
+
+#[blueprint]
+mod purchase_router {
+    // Stateless helper for buying from several sales in one transaction
+    struct PurchaseRouter {}
+
+    impl PurchaseRouter {
+        // Buy from every leg in order, each paid out of the same bucket. Any leg whose price is
+        // above its limit, or which is paid in another token, aborts the whole sweep. Returns the
+        // unspent payment, the NFTs bought (one bucket per leg) and the purchase rewards of all legs
+        pub fn sweep(
+            mut payment: Bucket,
+            legs: Vec<PurchaseLeg>,
+        ) -> (Bucket, Vec<NonFungibleBucket>, Vec<Bucket>) {
+            assert!(
+                !legs.is_empty(),
+                "{} [Sweep]: At least one sale must be given.",
+                SaleError::InvalidQuantity
+            );
+
+            let mut purchases = Vec::with_capacity(legs.len());
+            let mut rewards = Vec::new();
+            for leg in legs {
+                let sale: Global<NFTSale> = leg.sale.into();
+                let (payment_token, price) = sale.price();
+                assert_eq!(
+                    payment_token,
+                    payment.resource_address(),
+                    "{} [Sweep]: Sale {:?} is paid in {:?}.",
+                    SaleError::WrongPaymentToken,
+                    leg.sale,
+                    payment_token
+                );
+                assert!(
+                    price <= leg.max_price,
+                    "{} [Sweep]: Sale {:?} costs {} per NFT, above the limit of {}.",
+                    SaleError::PriceAboveLimit,
+                    leg.sale,
+                    price,
+                    leg.max_price
+                );
+
+                let (change, nfts, leg_rewards) =
+                    sale.buy(payment.take(price * leg.number_of_nfts), leg.number_of_nfts);
+                payment.put(change);
+                purchases.push(nfts);
+                rewards.extend(leg_rewards);
+            }
+            (payment, purchases, rewards)
+        }
+    }
+}
@@ -0,0 +1,61 @@
+mod common;
+
+use common::TestSale;
+use nft_sale_interface::AccountingPeriod;
+use scrypto_test::prelude::*;
+
+fn close_period(sale: &mut TestSale) -> AccountingPeriod {
+    let component = sale.component;
+    sale.as_owner(|builder| {
+        builder.call_method(
+            component,
+            "close_accounting_period",
+            manifest_args!(TestSale::NO_BADGE),
+        )
+    })
+    .expect_commit_success()
+    .output(1)
+}
+
+#[test]
+fn periods_record_the_sales_since_the_last_close() {
+    let mut sale = TestSale::new(5, 5, dec!(10));
+    sale.start_sale().expect_commit_success();
+    sale.buy(dec!(20), 2).expect_commit_success();
+
+    let first = close_period(&mut sale);
+    assert_eq!(first.revenue, dec!(20));
+    assert_eq!(first.units, 2);
+
+    sale.buy(dec!(10), 1).expect_commit_success();
+    let second = close_period(&mut sale);
+    assert_eq!(second.revenue, dec!(10));
+    assert_eq!(second.units, 1);
+    assert_eq!(second.total_revenue, dec!(30));
+    assert_eq!(second.total_units, 3);
+    assert_eq!(second.opened_at, first.closed_at);
+
+    let component = sale.component;
+    let (count, periods): (u64, Vec<AccountingPeriod>) = sale
+        .as_public(|builder| {
+            builder.call_method(component, "accounting_periods", manifest_args!(0u64, 10u32))
+        })
+        .expect_commit_success()
+        .output(1);
+    assert_eq!(count, 2);
+    assert_eq!(periods, vec![first, second]);
+}
+
+#[test]
+fn only_the_team_closes_periods() {
+    let mut sale = TestSale::new(5, 5, dec!(10));
+    let component = sale.component;
+    sale.as_public(|builder| {
+        builder.call_method(
+            component,
+            "close_accounting_period",
+            manifest_args!(TestSale::NO_BADGE),
+        )
+    })
+    .expect_auth_failure();
+}
@@ -0,0 +1,123 @@
+mod common;
+
+use common::TestSale;
+use scrypto_test::prelude::*;
+
+// Issue an affiliate link to the test account and return its id
+fn issue_link(sale: &mut TestSale, commission_rate: Decimal) -> NonFungibleLocalId {
+    let (account, component, affiliate_link) = (sale.account, sale.component, sale.affiliate_link);
+    sale.as_owner(|builder| {
+        builder.call_method(
+            component,
+            "issue_affiliate_link",
+            manifest_args!("Storefront", commission_rate),
+        )
+    })
+    .expect_commit_success();
+    let vault = sale.ledger.get_component_vaults(account, affiliate_link)[0];
+    let (_, mut link_ids) = sale.ledger.inspect_non_fungible_vault(vault).unwrap();
+    link_ids.next().unwrap()
+}
+
+fn buy_via_affiliate(sale: &mut TestSale, payment: Decimal) -> TransactionReceipt {
+    let (account, component, payment_token, affiliate_link) = (
+        sale.account,
+        sale.component,
+        sale.payment_token,
+        sale.affiliate_link,
+    );
+    sale.as_public(|builder| {
+        builder
+            .create_proof_from_account_of_amount(account, affiliate_link, dec!(1))
+            .pop_from_auth_zone("link")
+            .withdraw_from_account(account, payment_token, payment)
+            .take_all_from_worktop(payment_token, "payment")
+            .with_name_lookup(|builder, lookup| {
+                let (link, payment) = (lookup.proof("link"), lookup.bucket("payment"));
+                builder.call_method(
+                    component,
+                    "buy_via_affiliate",
+                    manifest_args!(payment, 1u16, link),
+                )
+            })
+    })
+}
+
+fn commission_owed(sale: &mut TestSale, link_id: &NonFungibleLocalId) -> Decimal {
+    let component = sale.component;
+    let receipt = sale.as_public(|builder| {
+        builder.call_method(component, "commission_owed", manifest_args!(link_id))
+    });
+    receipt.expect_commit_success().output(1)
+}
+
+#[test]
+fn purchases_through_a_link_earn_its_holder_a_commission() {
+    let mut sale = TestSale::new(5, 5, dec!(10));
+    sale.start_sale().expect_commit_success();
+    let link_id = issue_link(&mut sale, dec!("0.1"));
+
+    buy_via_affiliate(&mut sale, dec!(10)).expect_commit_success();
+    buy_via_affiliate(&mut sale, dec!(10)).expect_commit_success();
+    assert_eq!(commission_owed(&mut sale, &link_id), dec!(2));
+
+    // A new holder of the link claims what it earned
+    let (account, component, affiliate_link, payment_token) = (
+        sale.account,
+        sale.component,
+        sale.affiliate_link,
+        sale.payment_token,
+    );
+    let (holder_key, _, holder) = sale.ledger.new_allocated_account();
+    sale.as_public(|builder| {
+        builder
+            .withdraw_from_account(account, affiliate_link, dec!(1))
+            .try_deposit_entire_worktop_or_abort(holder, None)
+    })
+    .expect_commit_success();
+
+    let manifest = ManifestBuilder::new()
+        .lock_fee_from_faucet()
+        .create_proof_from_account_of_amount(holder, affiliate_link, dec!(1))
+        .pop_from_auth_zone("link")
+        .with_name_lookup(|builder, lookup| {
+            let link = lookup.proof("link");
+            builder.call_method(component, "claim_commission", manifest_args!(link))
+        })
+        .try_deposit_entire_worktop_or_abort(holder, None)
+        .build();
+    sale.ledger
+        .execute_manifest(
+            manifest,
+            vec![NonFungibleGlobalId::from_public_key(&holder_key)],
+        )
+        .expect_commit_success();
+    assert_eq!(sale.balance(holder, payment_token), dec!(2));
+    assert_eq!(commission_owed(&mut sale, &link_id), dec!(0));
+
+    // The commissions came out of the revenue
+    sale.end_refund_guarantee();
+    sale.withdraw_profits().expect_commit_success();
+}
+
+#[test]
+fn only_the_owner_issues_links_within_bounds() {
+    let mut sale = TestSale::new(5, 5, dec!(10));
+    let component = sale.component;
+    sale.as_public(|builder| {
+        builder.call_method(
+            component,
+            "issue_affiliate_link",
+            manifest_args!("Storefront", dec!("0.1")),
+        )
+    })
+    .expect_auth_failure();
+    sale.as_owner(|builder| {
+        builder.call_method(
+            component,
+            "issue_affiliate_link",
+            manifest_args!("Storefront", dec!("1.5")),
+        )
+    })
+    .expect_commit_failure();
+}
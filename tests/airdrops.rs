@@ -0,0 +1,84 @@
+mod common;
+
+use common::TestSale;
+use scrypto_test::prelude::*;
+
+fn airdrop_batch(sale: &mut TestSale, limit: u32) -> u64 {
+    let component = sale.component;
+    sale.as_owner(|builder| {
+        builder.call_method(
+            component,
+            "airdrop_batch",
+            manifest_args!(TestSale::NO_BADGE, limit),
+        )
+    })
+    .expect_commit_success()
+    .output(1)
+}
+
+#[test]
+fn airdrops_resume_where_the_last_batch_stopped() {
+    let mut sale = TestSale::new(5, 5, dec!(10));
+    let (_, _, alice) = sale.ledger.new_allocated_account();
+    let (_, _, bob) = sale.ledger.new_allocated_account();
+    let (component, nft_resource) = (sale.component, sale.nft_resource);
+    sale.as_owner(|builder| {
+        builder.call_method(
+            component,
+            "add_airdrop_recipients",
+            manifest_args!(vec![alice, bob, alice]),
+        )
+    })
+    .expect_commit_success();
+
+    assert_eq!(airdrop_batch(&mut sale, 2), 1);
+    assert_eq!(sale.balance(alice, nft_resource), dec!(1));
+    assert_eq!(sale.balance(bob, nft_resource), dec!(1));
+
+    assert_eq!(airdrop_batch(&mut sale, 2), 0);
+    assert_eq!(airdrop_batch(&mut sale, 2), 0);
+    assert_eq!(sale.balance(alice, nft_resource), dec!(2));
+    assert_eq!(sale.balance(bob, nft_resource), dec!(1));
+    assert_eq!(sale.balance(component, nft_resource), dec!(2));
+}
+
+#[test]
+fn airdrop_batches_need_the_inventory_to_cover_them() {
+    let mut sale = TestSale::new(1, 1, dec!(10));
+    let (component, account) = (sale.component, sale.account);
+    sale.as_owner(|builder| {
+        builder.call_method(
+            component,
+            "add_airdrop_recipients",
+            manifest_args!(vec![account, account]),
+        )
+    })
+    .expect_commit_success();
+
+    sale.as_owner(|builder| {
+        builder.call_method(
+            component,
+            "airdrop_batch",
+            manifest_args!(TestSale::NO_BADGE, 2u32),
+        )
+    })
+    .expect_commit_failure();
+}
+
+#[test]
+fn oversized_batches_are_split_across_calls() {
+    let mut sale = TestSale::new(60, 60, dec!(10));
+    let (component, account, nft_resource) = (sale.component, sale.account, sale.nft_resource);
+    sale.as_owner(|builder| {
+        builder.call_method(
+            component,
+            "add_airdrop_recipients",
+            manifest_args!(vec![account; 60]),
+        )
+    })
+    .expect_commit_success();
+
+    assert_eq!(airdrop_batch(&mut sale, u32::MAX), 10);
+    assert_eq!(airdrop_batch(&mut sale, u32::MAX), 0);
+    assert_eq!(sale.balance(account, nft_resource), dec!(60));
+}
@@ -0,0 +1,60 @@
+mod common;
+
+use common::TestSale;
+use nft_sale_interface::PurchaseAllowance;
+use scrypto_test::prelude::*;
+
+// Stands in for a game contract, which would call `pull_nfts` itself
+const GAME: ComponentAddress = FAUCET;
+
+fn allowance(sale: &mut TestSale) -> Option<PurchaseAllowance> {
+    let component = sale.component;
+    sale.as_public(|builder| builder.call_method(component, "allowance", manifest_args!(GAME)))
+        .expect_commit_success()
+        .output(1)
+}
+
+#[test]
+fn owners_grant_allowances_funded_by_budgets() {
+    let mut sale = TestSale::new(10, 10, dec!(10));
+    let (account, component, payment_token) = (sale.account, sale.component, sale.payment_token);
+    let deposit = |builder: ManifestBuilder| {
+        builder
+            .withdraw_from_account(account, payment_token, dec!(30))
+            .take_all_from_worktop(payment_token, "budget")
+            .with_bucket("budget", |builder, bucket| {
+                builder.call_method(component, "deposit_budget", manifest_args!(GAME, bucket))
+            })
+    };
+    sale.as_public(deposit).expect_commit_failure();
+    assert_eq!(allowance(&mut sale), None);
+
+    sale.as_owner(|builder| {
+        builder.call_method(
+            component,
+            "grant_allowance",
+            manifest_args!(GAME, 5u64, dec!(6)),
+        )
+    })
+    .expect_commit_success();
+    sale.as_public(deposit).expect_commit_success();
+    assert_eq!(
+        allowance(&mut sale),
+        Some(PurchaseAllowance {
+            remaining: 5,
+            price: dec!(6),
+            budget: dec!(30),
+        })
+    );
+
+    // Only the game itself can spend or take back its budget
+    sale.start_sale().expect_commit_success();
+    sale.as_public(|builder| {
+        builder.call_method(component, "pull_nfts", manifest_args!(GAME, 1u16))
+    })
+    .expect_auth_failure();
+    sale.as_public(|builder| {
+        builder.call_method(component, "withdraw_budget", manifest_args!(GAME))
+    })
+    .expect_auth_failure();
+}
@@ -0,0 +1,55 @@
+mod common;
+
+use common::TestSale;
+use nft_sale_interface::{allowlist_proof, allowlist_root};
+use scrypto_test::prelude::*;
+
+fn buy_allowlisted(sale: &mut TestSale, proof: Vec<Hash>) -> TransactionReceipt {
+    let (account, component, payment_token) = (sale.account, sale.component, sale.payment_token);
+    sale.as_public(|builder| {
+        builder
+            .withdraw_from_account(account, payment_token, dec!(10))
+            .take_all_from_worktop(payment_token, "payment")
+            .with_bucket("payment", |builder, bucket| {
+                builder.call_method(
+                    component,
+                    "buy_allowlisted",
+                    manifest_args!(bucket, 1u16, account, proof),
+                )
+            })
+    })
+}
+
+fn open_with_allowlist(sale: &mut TestSale, members: &[ComponentAddress]) {
+    let component = sale.component;
+    let root = allowlist_root(members);
+    sale.as_owner(|builder| {
+        builder
+            .call_method(component, "set_allowlist_root", manifest_args!(Some(root)))
+            .call_method(component, "start_sale", manifest_args!(TestSale::NO_BADGE))
+    })
+    .expect_commit_success();
+}
+
+#[test]
+fn allowlisted_accounts_buy_with_their_proof() {
+    let mut sale = TestSale::new(5, 5, dec!(10));
+    let members = vec![FAUCET, sale.account, CONSENSUS_MANAGER];
+    open_with_allowlist(&mut sale, &members);
+
+    let proof = allowlist_proof(&members, sale.account).unwrap();
+    sale.buy(dec!(10), 1).expect_commit_failure();
+    buy_allowlisted(&mut sale, proof).expect_commit_success();
+
+    let (account, nft_resource) = (sale.account, sale.nft_resource);
+    assert_eq!(sale.balance(account, nft_resource), dec!(1));
+}
+
+#[test]
+fn accounts_off_the_allowlist_are_turned_away() {
+    let mut sale = TestSale::new(5, 5, dec!(10));
+    open_with_allowlist(&mut sale, &[FAUCET]);
+    let proof = allowlist_proof(&[FAUCET], FAUCET).unwrap();
+
+    buy_allowlisted(&mut sale, proof).expect_commit_failure();
+}
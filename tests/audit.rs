@@ -0,0 +1,66 @@
+mod common;
+
+use common::TestSale;
+use nft_sale_interface::{AuditEntry, Authority, SaleError};
+use scrypto_test::prelude::*;
+
+fn audit_log(sale: &mut TestSale, start: u64, limit: u32) -> (u64, Vec<AuditEntry>) {
+    let component = sale.component;
+    let receipt = sale.as_public(|builder| {
+        builder.call_method(component, "audit_log", manifest_args!(start, limit))
+    });
+    receipt.expect_commit_success().output(1)
+}
+
+#[test]
+fn restricted_calls_are_logged_in_order() {
+    // Stocking the vault is the first logged action
+    let mut sale = TestSale::new(2, 2, dec!(10));
+    sale.start_sale().expect_commit_success();
+    sale.change_price(dec!(15)).expect_commit_success();
+    // Failed calls are reverted together with their log entry
+    sale.change_price(dec!(-1)).expect_commit_failure();
+    sale.buy(dec!(15), 1).expect_commit_success();
+
+    let (length, entries) = audit_log(&mut sale, 0, 10);
+    assert_eq!(length, 3);
+    let actions: Vec<&str> = entries.iter().map(|entry| entry.action.as_str()).collect();
+    assert_eq!(actions, ["add_nfts_to_vault", "start_sale", "change_price"]);
+    assert_eq!(entries[2].params, "15");
+    assert_eq!(entries[2].authority, Authority::role("OWNER"));
+
+    let (_, page) = audit_log(&mut sale, 1, 1);
+    assert_eq!(page, entries[1..2]);
+}
+
+#[test]
+fn entries_name_the_badge_a_role_presented() {
+    let mut sale = TestSale::new(1, 1, dec!(10));
+    let (component, pricer_badge) = (sale.component, sale.pricer_badge);
+    sale.with_badge(pricer_badge, |builder| {
+        TestSale::presenting(builder, pricer_badge, |builder, badge| {
+            builder.call_method(component, "change_price", manifest_args!(badge, dec!(12)))
+        })
+    })
+    .expect_commit_success();
+
+    let (_, entries) = audit_log(&mut sale, 1, 1);
+    let authority = &entries[0].authority;
+    assert_eq!(authority.role, "pricer");
+    assert!(matches!(
+        &authority.badge,
+        Some(ResourceOrNonFungible::NonFungible(badge)) if badge.resource_address() == pricer_badge
+    ));
+
+    // Handing over the badge of a role the method is not open to is refused
+    let (account, automation_badge) = (sale.account, sale.automation_badge);
+    sale.with_badge(pricer_badge, |builder| {
+        let builder = builder.create_proof_from_account_of_amount(account, automation_badge, 1);
+        TestSale::presenting(builder, automation_badge, |builder, badge| {
+            builder.call_method(component, "change_price", manifest_args!(badge, dec!(12)))
+        })
+    })
+    .expect_specific_failure(|error| {
+        SaleError::from_message(&format!("{:?}", error)) == Some(SaleError::WrongResource)
+    });
+}
@@ -0,0 +1,70 @@
+mod common;
+
+use common::TestSale;
+use scrypto_test::prelude::*;
+
+fn issue_automation_badge(sale: &mut TestSale) {
+    let component = sale.component;
+    sale.as_owner(|builder| {
+        builder.call_method(
+            component,
+            "issue_automation_badge",
+            manifest_args!("Keeper"),
+        )
+    })
+    .expect_commit_success();
+}
+
+#[test]
+fn automation_badges_run_maintenance() {
+    let mut sale = TestSale::new(5, 5, dec!(10));
+    let (component, automation_badge) = (sale.component, sale.automation_badge);
+    sale.start_sale().expect_commit_success();
+    issue_automation_badge(&mut sale);
+
+    for method in [
+        "convert_escrows",
+        "retry_deliveries",
+        "airdrop_batch",
+        "fulfil",
+    ] {
+        sale.with_badge(automation_badge, |builder| {
+            TestSale::presenting(builder, automation_badge, |builder, badge| {
+                builder.call_method(component, method, manifest_args!(badge, 10u32))
+            })
+        })
+        .expect_commit_success();
+    }
+    sale.with_badge(automation_badge, |builder| {
+        TestSale::presenting(builder, automation_badge, |builder, badge| {
+            builder.call_method(component, "close_accounting_period", manifest_args!(badge))
+        })
+    })
+    .expect_commit_success();
+}
+
+#[test]
+fn automation_badges_cannot_move_assets() {
+    let mut sale = TestSale::new(5, 5, dec!(10));
+    let (component, automation_badge) = (sale.component, sale.automation_badge);
+    sale.start_sale().expect_commit_success();
+    sale.buy(dec!(10), 1).expect_commit_success();
+    issue_automation_badge(&mut sale);
+
+    sale.with_badge(automation_badge, |builder| {
+        builder.call_method(component, "withdraw_profits", manifest_args!())
+    })
+    .expect_auth_failure();
+    sale.with_badge(automation_badge, |builder| {
+        builder.call_method(
+            component,
+            "change_price",
+            manifest_args!(TestSale::NO_BADGE, dec!(1)),
+        )
+    })
+    .expect_auth_failure();
+    sale.with_badge(automation_badge, |builder| {
+        builder.call_method(component, "end_sale", manifest_args!(TestSale::NO_BADGE))
+    })
+    .expect_auth_failure();
+}
@@ -0,0 +1,67 @@
+mod common;
+
+use common::TestSale;
+use scrypto_test::prelude::*;
+
+#[test]
+fn bonus_is_paid_per_nft_until_the_vault_runs_dry() {
+    let mut sale = TestSale::new(5, 5, dec!(10));
+    let (account, component) = (sale.account, sale.component);
+    let bonus_token = sale.ledger.create_fungible_resource(dec!(5), 18, account);
+
+    sale.as_owner(|builder| {
+        builder
+            .withdraw_from_account(account, bonus_token, dec!(5))
+            .take_all_from_worktop(bonus_token, "bonus")
+            .with_bucket("bonus", |builder, bucket| {
+                builder.call_method(component, "deposit_bonus", manifest_args!(bucket))
+            })
+            .call_method(component, "set_bonus_per_nft", manifest_args!(dec!(2)))
+    })
+    .expect_commit_success();
+    sale.start_sale().expect_commit_success();
+
+    sale.buy(dec!(20), 2).expect_commit_success();
+    assert_eq!(sale.balance(account, bonus_token), dec!(4));
+
+    // Only 1 bonus token is left for the next 2 NFTs, after that purchases carry no bonus
+    sale.buy(dec!(20), 2).expect_commit_success();
+    assert_eq!(sale.balance(account, bonus_token), dec!(5));
+    sale.buy(dec!(10), 1).expect_commit_success();
+    assert_eq!(sale.balance(account, bonus_token), dec!(5));
+}
+
+#[test]
+fn only_the_owner_manages_the_bonus() {
+    let mut sale = TestSale::new(1, 1, dec!(10));
+    let component = sale.component;
+    sale.as_public(|builder| {
+        builder.call_method(component, "set_bonus_per_nft", manifest_args!(dec!(1)))
+    })
+    .expect_auth_failure();
+}
+
+#[test]
+fn bonus_is_rounded_down_to_the_bonus_token() {
+    let mut sale = TestSale::new(5, 5, dec!(10));
+    let (account, component) = (sale.account, sale.component);
+    let bonus_token = sale.ledger.create_fungible_resource(dec!(5), 0, account);
+
+    sale.as_owner(|builder| {
+        builder
+            .withdraw_from_account(account, bonus_token, dec!(5))
+            .take_all_from_worktop(bonus_token, "bonus")
+            .with_bucket("bonus", |builder, bucket| {
+                builder.call_method(component, "deposit_bonus", manifest_args!(bucket))
+            })
+            .call_method(component, "set_bonus_per_nft", manifest_args!(dec!("0.5")))
+    })
+    .expect_commit_success();
+    sale.start_sale().expect_commit_success();
+
+    // Half a token cannot be paid out, one and a half is paid as one
+    sale.buy(dec!(10), 1).expect_commit_success();
+    assert_eq!(sale.balance(account, bonus_token), dec!(0));
+    sale.buy(dec!(30), 3).expect_commit_success();
+    assert_eq!(sale.balance(account, bonus_token), dec!(1));
+}
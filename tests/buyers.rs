@@ -0,0 +1,48 @@
+mod common;
+
+use common::TestSale;
+use scrypto_test::prelude::*;
+
+fn buy_as(sale: &mut TestSale, number_of_nfts: u16) -> TransactionReceipt {
+    let (account, component, payment_token) = (sale.account, sale.component, sale.payment_token);
+    sale.as_public(|builder| {
+        builder
+            .withdraw_from_account(account, payment_token, dec!(100))
+            .take_all_from_worktop(payment_token, "payment")
+            .with_bucket("payment", |builder, bucket| {
+                builder.call_method(
+                    component,
+                    "buy_as",
+                    manifest_args!(bucket, number_of_nfts, account),
+                )
+            })
+    })
+}
+
+fn export_buyers(sale: &mut TestSale, cursor: u64) -> (u64, Vec<(ComponentAddress, u64, Decimal)>) {
+    let component = sale.component;
+    let receipt = sale.as_public(|builder| {
+        builder.call_method(component, "export_buyers", manifest_args!(cursor, 10u32))
+    });
+    let (total, encoded): (u64, Vec<u8>) = receipt.expect_commit_success().output(1);
+    (total, scrypto_decode(&encoded).unwrap())
+}
+
+#[test]
+fn buyers_are_exported_with_their_totals() {
+    let mut sale = TestSale::new(5, 5, dec!(10));
+    let account = sale.account;
+    sale.start_sale().expect_commit_success();
+
+    buy_as(&mut sale, 1).expect_commit_success();
+    buy_as(&mut sale, 2).expect_commit_success();
+    // Anonymous purchases are not attributed to any buyer
+    sale.buy(dec!(10), 1).expect_commit_success();
+
+    let (total, buyers) = export_buyers(&mut sale, 0);
+    assert_eq!(total, 1);
+    assert_eq!(buyers, vec![(account, 3, dec!(30))]);
+
+    let (_, buyers) = export_buyers(&mut sale, 1);
+    assert!(buyers.is_empty());
+}
@@ -0,0 +1,31 @@
+mod common;
+
+use common::TestSale;
+use scrypto_test::prelude::*;
+
+#[test]
+fn purchases_earn_loyalty_token_cashback() {
+    let mut sale = TestSale::new(3, 3, dec!(10));
+    let (account, component, loyalty_token) = (sale.account, sale.component, sale.loyalty_token);
+    sale.start_sale().expect_commit_success();
+
+    sale.buy(dec!(10), 1).expect_commit_success();
+    assert_eq!(sale.balance(account, loyalty_token), dec!(0));
+
+    sale.as_owner(|builder| {
+        builder.call_method(component, "set_cashback_rate", manifest_args!(dec!("0.1")))
+    })
+    .expect_commit_success();
+    sale.buy(dec!(20), 2).expect_commit_success();
+    assert_eq!(sale.balance(account, loyalty_token), dec!(2));
+}
+
+#[test]
+fn cashback_rate_is_at_most_the_full_cost() {
+    let mut sale = TestSale::new(1, 1, dec!(10));
+    let component = sale.component;
+    sale.as_owner(|builder| {
+        builder.call_method(component, "set_cashback_rate", manifest_args!(dec!("1.5")))
+    })
+    .expect_commit_failure();
+}
@@ -0,0 +1,84 @@
+mod common;
+
+use common::TestSale;
+use nft_sale_interface::ChangePolicy;
+use scrypto_test::prelude::*;
+
+fn started_sale(change_policy: ChangePolicy) -> TestSale {
+    let mut sale = TestSale::new_with(3, 3, |builder| {
+        builder.price(10).change_policy(change_policy)
+    });
+    sale.start_sale().expect_commit_success();
+    sale
+}
+
+#[test]
+fn change_is_returned_by_default() {
+    let mut sale = started_sale(ChangePolicy::Return);
+    let (account, payment_token) = (sale.account, sale.payment_token);
+    let before = sale.balance(account, payment_token);
+
+    sale.buy(dec!(15), 1).expect_commit_success();
+    assert_eq!(sale.balance(account, payment_token), before - dec!(10));
+}
+
+#[test]
+fn overpayment_is_rejected_under_the_exact_policy() {
+    let mut sale = started_sale(ChangePolicy::Reject);
+
+    sale.buy(dec!(15), 1).expect_commit_failure();
+    sale.buy(dec!(10), 1).expect_commit_success();
+}
+
+#[test]
+fn change_is_kept_as_tips_under_the_tip_policy() {
+    let mut sale = started_sale(ChangePolicy::Tip);
+    let (account, component, payment_token) = (sale.account, sale.component, sale.payment_token);
+    let before = sale.balance(account, payment_token);
+
+    sale.buy(dec!(15), 1).expect_commit_success();
+    assert_eq!(sale.balance(account, payment_token), before - dec!(15));
+
+    sale.as_owner(|builder| builder.call_method(component, "withdraw_tips", manifest_args!()))
+        .expect_commit_success();
+    assert_eq!(sale.balance(account, payment_token), before - dec!(10));
+}
+
+#[test]
+fn pass_redemptions_follow_the_change_policy() {
+    let mut sale = started_sale(ChangePolicy::Reject);
+    let (account, component, mint_pass, payment_token) = (
+        sale.account,
+        sale.component,
+        sale.mint_pass,
+        sale.payment_token,
+    );
+    let now = sale.ledger.get_current_epoch();
+    let closes_at = now.after(10).unwrap();
+    sale.as_owner(|builder| {
+        builder
+            .call_method(
+                component,
+                "mint_passes",
+                manifest_args!(1u32, 1u16, dec!(6)),
+            )
+            .call_method(component, "set_pass_window", manifest_args!(now, closes_at))
+    })
+    .expect_commit_success();
+    let mut redeem = |payment: Decimal| {
+        sale.as_public(|builder| {
+            builder
+                .withdraw_from_account(account, mint_pass, 1)
+                .take_all_from_worktop(mint_pass, "passes")
+                .withdraw_from_account(account, payment_token, payment)
+                .take_all_from_worktop(payment_token, "payment")
+                .with_name_lookup(|builder, lookup| {
+                    let (passes, payment) = (lookup.bucket("passes"), lookup.bucket("payment"));
+                    builder.call_method(component, "redeem_passes", manifest_args!(passes, payment))
+                })
+        })
+    };
+
+    redeem(dec!(8)).expect_commit_failure();
+    redeem(dec!(6)).expect_commit_success();
+}
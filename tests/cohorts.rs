@@ -0,0 +1,138 @@
+mod common;
+
+use common::TestSale;
+use nft_sale_interface::{price_cohort, PriceCohort};
+use scrypto_test::prelude::*;
+
+// Mirror of the blueprint's event, decoded from the receipt
+#[derive(ScryptoSbor)]
+struct NftsSoldEvent {
+    nft_ids: IndexSet<NonFungibleLocalId>,
+    revenue: Decimal,
+    recipient: Option<ComponentAddress>,
+    sponsor: Option<ComponentAddress>,
+    tag: Option<String>,
+    cohort: Option<u32>,
+}
+
+fn buy_as(sale: &mut TestSale, payment: Decimal) -> TransactionReceipt {
+    let (account, component, payment_token) = (sale.account, sale.component, sale.payment_token);
+    sale.as_public(|builder| {
+        builder
+            .withdraw_from_account(account, payment_token, payment)
+            .take_all_from_worktop(payment_token, "payment")
+            .with_bucket("payment", |builder, bucket| {
+                builder.call_method(component, "buy_as", manifest_args!(bucket, 1u16, account))
+            })
+    })
+}
+
+fn set_price_cohorts(sale: &mut TestSale, cohorts: Vec<PriceCohort>) -> TransactionReceipt {
+    let component = sale.component;
+    sale.as_owner(|builder| {
+        builder.call_method(component, "set_price_cohorts", manifest_args!(cohorts))
+    })
+}
+
+#[test]
+fn buyers_pay_the_price_of_their_cohort() {
+    let mut sale = TestSale::new(5, 5, dec!(10));
+    let (account, payment_token) = (sale.account, sale.payment_token);
+    let cohorts = vec![
+        PriceCohort {
+            adjustment: dec!("-0.1"),
+            bonus_per_nft: dec!(0),
+        },
+        PriceCohort {
+            adjustment: dec!("0.1"),
+            bonus_per_nft: dec!(0),
+        },
+    ];
+    let cohort = price_cohort(account, cohorts.len());
+    let price = dec!(10) + cohorts[cohort].adjustment * 10;
+    set_price_cohorts(&mut sale, cohorts).expect_commit_success();
+    sale.start_sale().expect_commit_success();
+
+    let before = sale.balance(account, payment_token);
+    let receipt = buy_as(&mut sale, price);
+    let (_, data) = receipt
+        .expect_commit_success()
+        .application_events
+        .iter()
+        .find(|(event_type, _)| event_type.1 == "NftsSoldEvent")
+        .unwrap();
+    let event: NftsSoldEvent = scrypto_decode(data).unwrap();
+    assert_eq!(event.cohort, Some(cohort as u32));
+    assert_eq!(event.revenue, price);
+    assert_eq!(sale.balance(account, payment_token), before - price);
+
+    // Anonymous purchases stay outside the experiment
+    sale.buy(dec!(10), 1).expect_commit_success();
+}
+
+#[test]
+fn refunds_pay_back_the_cohort_price() {
+    let mut sale = TestSale::new(1, 1, dec!(10));
+    let (account, component, nft_resource, payment_token) = (
+        sale.account,
+        sale.component,
+        sale.nft_resource,
+        sale.payment_token,
+    );
+    let cohorts = vec![
+        PriceCohort {
+            adjustment: dec!("-0.2"),
+            bonus_per_nft: dec!(0),
+        };
+        2
+    ];
+    set_price_cohorts(&mut sale, cohorts).expect_commit_success();
+    sale.start_sale().expect_commit_success();
+    let before = sale.balance(account, payment_token);
+    buy_as(&mut sale, dec!(8)).expect_commit_success();
+
+    sale.as_owner(|builder| builder.call_method(component, "cancel_sale", manifest_args!()))
+        .expect_commit_success();
+    sale.as_public(|builder| {
+        builder
+            .withdraw_from_account(account, nft_resource, 1)
+            .take_all_from_worktop(nft_resource, "nfts")
+            .with_bucket("nfts", |builder, bucket| {
+                builder.call_method(component, "claim_refund", manifest_args!(bucket))
+            })
+    })
+    .expect_commit_success();
+    assert_eq!(sale.balance(account, payment_token), before);
+}
+
+#[test]
+fn cohort_prices_stay_within_bounds() {
+    let mut sale = TestSale::new(1, 1, dec!(10));
+    let cohort = |adjustment| PriceCohort {
+        adjustment,
+        bonus_per_nft: dec!(0),
+    };
+    set_price_cohorts(&mut sale, vec![cohort(dec!("0.2")), cohort(dec!("-0.2"))])
+        .expect_commit_success();
+    set_price_cohorts(&mut sale, vec![cohort(dec!("0.25"))]).expect_commit_failure();
+    set_price_cohorts(&mut sale, vec![cohort(dec!("-0.5"))]).expect_commit_failure();
+}
+
+#[test]
+fn cohort_limits_are_set_by_the_owner() {
+    let cohort = |adjustment, bonus_per_nft| PriceCohort {
+        adjustment,
+        bonus_per_nft,
+    };
+
+    // By default cohorts earn no extra bonus
+    let mut sale = TestSale::new(1, 1, dec!(10));
+    set_price_cohorts(&mut sale, vec![cohort(dec!(0), dec!(1))]).expect_commit_failure();
+
+    let mut sale = TestSale::new_with(1, 1, |builder| {
+        builder.price(10).cohort_limits(dec!("0.5"), 1)
+    });
+    set_price_cohorts(&mut sale, vec![cohort(dec!("-0.5"), dec!(1))]).expect_commit_success();
+    set_price_cohorts(&mut sale, vec![cohort(dec!("0.6"), dec!(0))]).expect_commit_failure();
+    set_price_cohorts(&mut sale, vec![cohort(dec!(0), dec!(2))]).expect_commit_failure();
+}
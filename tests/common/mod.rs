@@ -0,0 +1,274 @@
+#![allow(dead_code)]
+
+use nft_sale_interface::NFTSaleBuilder;
+use scrypto_test::prelude::*;
+
+// A deployed sale together with the ledger and the account that owns it
+pub struct TestSale {
+    pub ledger: DefaultLedgerSimulator,
+    pub public_key: Secp256k1PublicKey,
+    pub account: ComponentAddress,
+    pub package_address: PackageAddress,
+    pub component: ComponentAddress,
+    pub owner_badge: ResourceAddress,
+    pub admin_badge: ResourceAddress,
+    pub pricer_badge: ResourceAddress,
+    pub voucher: ResourceAddress,
+    pub loyalty_token: ResourceAddress,
+    pub stake_receipt: ResourceAddress,
+    pub vip_badge: ResourceAddress,
+    pub mint_pass: ResourceAddress,
+    pub escrow_receipt: ResourceAddress,
+    pub hold_receipt: ResourceAddress,
+    pub backer_share: ResourceAddress,
+    pub listing_manager: ResourceAddress,
+    pub affiliate_link: ResourceAddress,
+    pub automation_badge: ResourceAddress,
+    pub nft_resource: ResourceAddress,
+    pub payment_token: ResourceAddress,
+}
+
+impl TestSale {
+    // Mint `minted` integer-id NFTs to a fresh account, deploy a sale for them at `price`
+    // and move the first `stocked` NFTs into the sale vault
+    pub fn new(minted: usize, stocked: u64, price: Decimal) -> Self {
+        Self::new_with(minted, stocked, |builder| builder.price(price))
+    }
+
+    // Same as `new`, with the sale options chosen by `configure`
+    pub fn new_with(
+        minted: usize,
+        stocked: u64,
+        configure: impl FnOnce(NFTSaleBuilder) -> NFTSaleBuilder,
+    ) -> Self {
+        let mut ledger = LedgerSimulatorBuilder::new().build();
+        let (public_key, _private_key, account) = ledger.new_allocated_account();
+        let nft_resource = ledger.create_non_fungible_resource_advanced(
+            NonFungibleResourceRoles::default(),
+            account,
+            minted,
+        );
+
+        let mut sale = Self::deploy(ledger, public_key, account, nft_resource, configure);
+        if stocked > 0 {
+            sale.add_nfts(stocked).expect_commit_success();
+        }
+        sale
+    }
+
+    // Publish the package and instantiate a sale of `nft_resource`, paid in a fresh token
+    // that is minted to `account`
+    pub fn deploy(
+        mut ledger: DefaultLedgerSimulator,
+        public_key: Secp256k1PublicKey,
+        account: ComponentAddress,
+        nft_resource: ResourceAddress,
+        configure: impl FnOnce(NFTSaleBuilder) -> NFTSaleBuilder,
+    ) -> Self {
+        let package_address = ledger.compile_and_publish(this_package!());
+        let payment_token = ledger.create_fungible_resource(dec!(1000000), 18, account);
+        let config = configure(NFTSaleBuilder::new(nft_resource, payment_token))
+            .build()
+            .unwrap();
+
+        let manifest = ManifestBuilder::new()
+            .lock_fee_from_faucet()
+            .call_function(
+                package_address,
+                "NFTSale",
+                "instantiate_nft_sale",
+                manifest_args!(config),
+            )
+            .try_deposit_entire_worktop_or_abort(account, None)
+            .build();
+        let receipt = ledger.execute_manifest(
+            manifest,
+            vec![NonFungibleGlobalId::from_public_key(&public_key)],
+        );
+        let commit = receipt.expect_commit_success();
+        let component = commit.new_component_addresses()[0];
+        let owner_badge = commit.new_resource_addresses()[0];
+        let admin_badge = commit.new_resource_addresses()[1];
+        let pricer_badge = commit.new_resource_addresses()[2];
+        let voucher = commit.new_resource_addresses()[3];
+        let loyalty_token = commit.new_resource_addresses()[4];
+        let stake_receipt = commit.new_resource_addresses()[5];
+        let vip_badge = commit.new_resource_addresses()[6];
+        let mint_pass = commit.new_resource_addresses()[7];
+        let escrow_receipt = commit.new_resource_addresses()[8];
+        let hold_receipt = commit.new_resource_addresses()[9];
+        let backer_share = commit.new_resource_addresses()[10];
+        let listing_manager = commit.new_resource_addresses()[11];
+        let affiliate_link = commit.new_resource_addresses()[12];
+        let automation_badge = commit.new_resource_addresses()[13];
+
+        Self {
+            ledger,
+            public_key,
+            account,
+            package_address,
+            component,
+            owner_badge,
+            admin_badge,
+            pricer_badge,
+            voucher,
+            loyalty_token,
+            stake_receipt,
+            vip_badge,
+            mint_pass,
+            escrow_receipt,
+            hold_receipt,
+            backer_share,
+            listing_manager,
+            affiliate_link,
+            automation_badge,
+            nft_resource,
+            payment_token,
+        }
+    }
+
+    // The badge argument of methods several roles may call, which the owner leaves out
+    pub const NO_BADGE: Option<ManifestProof> = None;
+
+    // Hand a proof of a badge in the auth zone to a method several roles may call, which takes
+    // it to record the role of the caller
+    pub fn presenting(
+        builder: ManifestBuilder,
+        badge: ResourceAddress,
+        call: impl FnOnce(ManifestBuilder, Option<ManifestProof>) -> ManifestBuilder,
+    ) -> ManifestBuilder {
+        let name = builder.generate_proof_name("badge");
+        let builder = builder.create_proof_from_auth_zone_of_all(badge, name.clone());
+        let proof = builder.proof(name);
+        call(builder, Some(proof))
+    }
+
+    pub fn signer(&self) -> Vec<NonFungibleGlobalId> {
+        vec![NonFungibleGlobalId::from_public_key(&self.public_key)]
+    }
+
+    // Run a manifest that presents the owner badge before the given instructions
+    pub fn as_owner(
+        &mut self,
+        build: impl FnOnce(ManifestBuilder) -> ManifestBuilder,
+    ) -> TransactionReceipt {
+        let owner_badge = self.owner_badge;
+        self.with_badge(owner_badge, build)
+    }
+
+    // Run a manifest that presents one of the account's badges before the given instructions
+    pub fn with_badge(
+        &mut self,
+        badge: ResourceAddress,
+        build: impl FnOnce(ManifestBuilder) -> ManifestBuilder,
+    ) -> TransactionReceipt {
+        let manifest = build(
+            ManifestBuilder::new()
+                .lock_fee_from_faucet()
+                .create_proof_from_account_of_amount(self.account, badge, dec!(1)),
+        )
+        .try_deposit_entire_worktop_or_abort(self.account, None)
+        .build();
+        let signer = self.signer();
+        self.ledger.execute_manifest(manifest, signer)
+    }
+
+    // Run a manifest without presenting any badge
+    pub fn as_public(
+        &mut self,
+        build: impl FnOnce(ManifestBuilder) -> ManifestBuilder,
+    ) -> TransactionReceipt {
+        let manifest = build(ManifestBuilder::new().lock_fee_from_faucet())
+            .try_deposit_entire_worktop_or_abort(self.account, None)
+            .build();
+        let signer = self.signer();
+        self.ledger.execute_manifest(manifest, signer)
+    }
+
+    pub fn add_nfts(&mut self, count: u64) -> TransactionReceipt {
+        let (account, component, nft_resource) = (self.account, self.component, self.nft_resource);
+        self.as_owner(|builder| {
+            builder
+                .withdraw_from_account(account, nft_resource, count)
+                .take_all_from_worktop(nft_resource, "nfts")
+                .with_bucket("nfts", |builder, bucket| {
+                    builder.call_method(
+                        component,
+                        "add_nfts_to_vault",
+                        manifest_args!(TestSale::NO_BADGE, bucket),
+                    )
+                })
+        })
+    }
+
+    pub fn start_sale(&mut self) -> TransactionReceipt {
+        let component = self.component;
+        self.as_owner(|builder| {
+            builder.call_method(component, "start_sale", manifest_args!(TestSale::NO_BADGE))
+        })
+    }
+
+    pub fn change_price(&mut self, price: Decimal) -> TransactionReceipt {
+        let component = self.component;
+        self.as_owner(|builder| {
+            builder.call_method(
+                component,
+                "change_price",
+                manifest_args!(TestSale::NO_BADGE, price),
+            )
+        })
+    }
+
+    // End the sale and let its refund guarantee run out, releasing the revenue to the owner
+    pub fn end_refund_guarantee(&mut self) {
+        let component = self.component;
+        self.as_owner(|builder| {
+            builder.call_method(component, "end_sale", manifest_args!(TestSale::NO_BADGE))
+        })
+        .expect_commit_success();
+        let now = self.ledger.get_current_epoch();
+        self.ledger.set_current_epoch(now.after(2016).unwrap());
+    }
+
+    pub fn withdraw_profits(&mut self) -> TransactionReceipt {
+        let component = self.component;
+        self.as_owner(|builder| {
+            builder.call_method(component, "withdraw_profits", manifest_args!())
+        })
+    }
+
+    pub fn buy(&mut self, payment: Decimal, number_of_nfts: u16) -> TransactionReceipt {
+        let payment_token = self.payment_token;
+        self.buy_with(payment_token, payment, number_of_nfts)
+    }
+
+    // Buy paying with any resource held by the account, e.g. gift vouchers
+    pub fn buy_with(
+        &mut self,
+        payment_token: ResourceAddress,
+        payment: Decimal,
+        number_of_nfts: u16,
+    ) -> TransactionReceipt {
+        let (account, component) = (self.account, self.component);
+        self.as_public(|builder| {
+            builder
+                .withdraw_from_account(account, payment_token, payment)
+                .take_all_from_worktop(payment_token, "payment")
+                .with_bucket("payment", |builder, bucket| {
+                    builder.call_method(component, "buy", manifest_args!(bucket, number_of_nfts))
+                })
+        })
+    }
+
+    pub fn price(&mut self) -> Decimal {
+        let component = self.component;
+        let receipt =
+            self.as_public(|builder| builder.call_method(component, "price", manifest_args!()));
+        let (_, price): (ResourceAddress, Decimal) = receipt.expect_commit_success().output(1);
+        price
+    }
+
+    pub fn balance(&mut self, owner: ComponentAddress, resource: ResourceAddress) -> Decimal {
+        self.ledger.get_component_balance(owner, resource)
+    }
+}
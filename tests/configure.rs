@@ -0,0 +1,100 @@
+mod common;
+
+use common::TestSale;
+use nft_sale_interface::{AdminAction, LimitTier, SaleError};
+use scrypto_test::prelude::*;
+
+fn configure(sale: &mut TestSale, batch: Vec<AdminAction>) -> TransactionReceipt {
+    let component = sale.component;
+    sale.as_owner(|builder| {
+        builder.call_method(
+            component,
+            "configure",
+            manifest_args!(TestSale::NO_BADGE, batch),
+        )
+    })
+}
+
+#[test]
+fn changes_are_applied_together_with_one_event() {
+    let mut sale = TestSale::new(10, 10, dec!(10));
+    let receipt = configure(
+        &mut sale,
+        vec![
+            AdminAction::ChangePrice(dec!(12)),
+            AdminAction::SetLimitTiers(vec![LimitTier {
+                up_to_remaining: 10,
+                max_nfts_per_transaction: 2,
+            }]),
+            AdminAction::StartSale,
+        ],
+    );
+    let events = receipt
+        .expect_commit_success()
+        .application_events
+        .iter()
+        .filter(|(event_type, _)| event_type.1 == "ConfigurationChangedEvent")
+        .count();
+    assert_eq!(events, 1);
+
+    assert_eq!(sale.price(), dec!(12));
+    sale.buy(dec!(36), 3).expect_commit_failure();
+    sale.buy(dec!(24), 2).expect_commit_success();
+}
+
+#[test]
+fn one_invalid_change_rejects_the_batch() {
+    let mut sale = TestSale::new(10, 10, dec!(10));
+    configure(
+        &mut sale,
+        vec![
+            AdminAction::ChangePrice(dec!(12)),
+            AdminAction::SetCashbackRate(dec!(-1)),
+        ],
+    )
+    .expect_commit_failure();
+    assert_eq!(sale.price(), dec!(10));
+    configure(&mut sale, vec![]).expect_commit_failure();
+
+    let component = sale.component;
+    sale.as_public(|builder| {
+        builder.call_method(
+            component,
+            "configure",
+            manifest_args!(TestSale::NO_BADGE, vec![AdminAction::StartSale]),
+        )
+    })
+    .expect_auth_failure();
+}
+
+fn configure_as_admin(sale: &mut TestSale, batch: Vec<AdminAction>) -> TransactionReceipt {
+    let (component, admin_badge) = (sale.component, sale.admin_badge);
+    sale.with_badge(admin_badge, |builder| {
+        TestSale::presenting(builder, admin_badge, |builder, badge| {
+            builder.call_method(component, "configure", manifest_args!(badge, batch))
+        })
+    })
+}
+
+#[test]
+fn admins_batch_only_the_changes_they_may_make() {
+    let mut sale = TestSale::new(10, 10, dec!(10));
+    configure_as_admin(
+        &mut sale,
+        vec![AdminAction::ChangePrice(dec!(12)), AdminAction::StartSale],
+    )
+    .expect_commit_success();
+    assert_eq!(sale.price(), dec!(12));
+
+    configure_as_admin(
+        &mut sale,
+        vec![
+            AdminAction::ChangePrice(dec!(11)),
+            AdminAction::SetCashbackRate(dec!("0.1")),
+        ],
+    )
+    .expect_specific_failure(|error| {
+        SaleError::from_message(&format!("{:?}", error)) == Some(SaleError::WrongResource)
+    });
+    assert_eq!(sale.price(), dec!(12));
+}
@@ -0,0 +1,72 @@
+mod common;
+
+use common::TestSale;
+use nft_sale_interface::SaleError;
+use scrypto_test::prelude::*;
+
+fn buy_as(sale: &mut TestSale) -> TransactionReceipt {
+    let (account, component, payment_token) = (sale.account, sale.component, sale.payment_token);
+    sale.as_public(|builder| {
+        builder
+            .withdraw_from_account(account, payment_token, dec!(10))
+            .take_all_from_worktop(payment_token, "payment")
+            .with_bucket("payment", |builder, bucket| {
+                builder.call_method(component, "buy_as", manifest_args!(bucket, 1u16, account))
+            })
+    })
+}
+
+fn open_with_cooldown(sale: &mut TestSale, cooldown: u64) {
+    let component = sale.component;
+    sale.as_owner(|builder| {
+        builder
+            .call_method(
+                component,
+                "set_purchase_cooldown",
+                manifest_args!(Some(cooldown)),
+            )
+            .call_method(component, "start_sale", manifest_args!(TestSale::NO_BADGE))
+    })
+    .expect_commit_success();
+}
+
+#[test]
+fn accounts_wait_out_the_cooldown_between_purchases() {
+    let mut sale = TestSale::new(5, 5, dec!(10));
+    open_with_cooldown(&mut sale, 10);
+
+    buy_as(&mut sale).expect_commit_success();
+    buy_as(&mut sale).expect_commit_failure();
+
+    let now = sale.ledger.get_current_epoch();
+    sale.ledger.set_current_epoch(now.after(10).unwrap());
+    buy_as(&mut sale).expect_commit_success();
+
+    let (account, nft_resource) = (sale.account, sale.nft_resource);
+    assert_eq!(sale.balance(account, nft_resource), dec!(2));
+}
+
+#[test]
+fn anonymous_purchases_are_refused_under_a_cooldown() {
+    let mut sale = TestSale::new(5, 5, dec!(10));
+    open_with_cooldown(&mut sale, 10);
+
+    sale.buy(dec!(10), 1).expect_specific_failure(|error| {
+        SaleError::from_message(&format!("{:?}", error)) == Some(SaleError::AccountRequired)
+    });
+    buy_as(&mut sale).expect_commit_success();
+}
+
+#[test]
+fn cooldowns_must_last_an_epoch() {
+    let mut sale = TestSale::new(5, 5, dec!(10));
+    let component = sale.component;
+    sale.as_owner(|builder| {
+        builder.call_method(
+            component,
+            "set_purchase_cooldown",
+            manifest_args!(Some(0u64)),
+        )
+    })
+    .expect_commit_failure();
+}
@@ -0,0 +1,210 @@
+mod common;
+
+use common::TestSale;
+use nft_sale_interface::{
+    estimate_execution_cost, estimate_fee_lock, NFTSaleBuilder, PriceSchedule, SaleFlow,
+};
+use scrypto_test::prelude::*;
+
+// NFTs minted and deposited per restock transaction, kept well under the substate limits
+const RESTOCK_BATCH: u64 = 250;
+
+// A single purchase may cost at most this much more on a full vault than on a nearly empty one
+const MAX_INVENTORY_COST_GROWTH: u32 = 2;
+
+struct Costs {
+    inventory: u64,
+    buy_one: u32,
+    buy_max: u32,
+    buy_specific: u32,
+    price: u32,
+}
+
+// Deploy a sale over a freely mintable collection and stock it with `inventory` NFTs, the last
+// of them listed for `buy_specific` at the flat price
+fn stocked_sale(inventory: u64) -> TestSale {
+    let mut ledger = LedgerSimulatorBuilder::new().build();
+    let (public_key, _private_key, account) = ledger.new_allocated_account();
+    let nft_resource = ledger.create_freely_mintable_and_burnable_non_fungible_resource(
+        OwnerRole::None,
+        NonFungibleIdType::Integer,
+        None::<Vec<(NonFungibleLocalId, EmptyNonFungibleData)>>,
+        account,
+    );
+    let mut sale = TestSale::deploy(ledger, public_key, account, nft_resource, |builder| {
+        builder.price(1)
+    });
+
+    let mut minted = 0u64;
+    while minted < inventory {
+        let batch = RESTOCK_BATCH.min(inventory - minted);
+        let entries: Vec<(NonFungibleLocalId, EmptyNonFungibleData)> = (minted + 1
+            ..=minted + batch)
+            .map(|id| (NonFungibleLocalId::integer(id), EmptyNonFungibleData {}))
+            .collect();
+        let component = sale.component;
+        sale.as_owner(|builder| {
+            builder
+                .mint_non_fungible(nft_resource, entries)
+                .take_all_from_worktop(nft_resource, "nfts")
+                .with_bucket("nfts", |builder, bucket| {
+                    builder.call_method(
+                        component,
+                        "add_nfts_to_vault",
+                        manifest_args!(TestSale::NO_BADGE, bucket),
+                    )
+                })
+        })
+        .expect_commit_success();
+        minted += batch;
+    }
+
+    let component = sale.component;
+    let schedule = PriceSchedule {
+        start_price: dec!(1),
+        floor_price: dec!(1),
+        starts_at: sale.ledger.get_current_epoch(),
+        decline_per_epoch: dec!(0),
+    };
+    sale.as_owner(|builder| {
+        builder
+            .call_method(
+                component,
+                "schedule_listing",
+                manifest_args!(
+                    TestSale::NO_BADGE,
+                    NonFungibleLocalId::integer(inventory),
+                    Some(schedule)
+                ),
+            )
+            .call_method(component, "start_sale", manifest_args!(TestSale::NO_BADGE))
+    })
+    .expect_commit_success();
+    sale
+}
+
+fn execution_cost(receipt: &TransactionReceipt) -> u32 {
+    receipt.expect_commit_success();
+    receipt.fee_summary.total_execution_cost_units_consumed
+}
+
+fn buy_specific(sale: &mut TestSale, nft_id: u64) -> TransactionReceipt {
+    let (account, component, payment_token) = (sale.account, sale.component, sale.payment_token);
+    sale.as_public(|builder| {
+        builder
+            .withdraw_from_account(account, payment_token, dec!(1))
+            .take_all_from_worktop(payment_token, "payment")
+            .with_bucket("payment", |builder, bucket| {
+                builder.call_method(
+                    component,
+                    "buy_specific",
+                    manifest_args!(bucket, NonFungibleLocalId::integer(nft_id)),
+                )
+            })
+    })
+}
+
+// Every measured flow must stay within the fee estimate frontends lock for it
+fn assert_estimated(flow: SaleFlow, number_of_nfts: u16, measured: u32, inventory: u64) {
+    let estimate = estimate_execution_cost(flow, number_of_nfts, 0);
+    assert!(
+        measured <= estimate,
+        "{:?} of {} NFTs cost {} with {} NFTs in stock, over the estimate of {}",
+        flow,
+        number_of_nfts,
+        measured,
+        inventory,
+        estimate
+    );
+}
+
+fn measure(inventory: u64) -> Costs {
+    let mut sale = stocked_sale(inventory);
+
+    // The last id minted, the one listed on its own
+    let buy_specific = execution_cost(&buy_specific(&mut sale, inventory));
+    let buy_one = execution_cost(&sale.buy(dec!(1), 1));
+    let buy_max = execution_cost(&sale.buy(dec!(10), 10));
+    let component = sale.component;
+    let price = execution_cost(
+        &sale.as_public(|builder| builder.call_method(component, "price", manifest_args!())),
+    );
+
+    assert_estimated(SaleFlow::Buy, 1, buy_one, inventory);
+    assert_estimated(SaleFlow::Buy, 10, buy_max, inventory);
+    assert_estimated(SaleFlow::BuySpecific, 1, buy_specific, inventory);
+    Costs {
+        inventory,
+        buy_one,
+        buy_max,
+        buy_specific,
+        price,
+    }
+}
+
+fn assert_flat(small: &Costs, large: &Costs) {
+    assert!(
+        large.buy_one <= small.buy_one * MAX_INVENTORY_COST_GROWTH,
+        "buy(1) cost grew from {} to {} between {} and {} NFTs",
+        small.buy_one,
+        large.buy_one,
+        small.inventory,
+        large.inventory
+    );
+    assert!(
+        large.buy_max <= small.buy_max * MAX_INVENTORY_COST_GROWTH,
+        "buy(10) cost grew from {} to {} between {} and {} NFTs",
+        small.buy_max,
+        large.buy_max,
+        small.inventory,
+        large.inventory
+    );
+    assert!(
+        large.buy_specific <= small.buy_specific * MAX_INVENTORY_COST_GROWTH,
+        "buy_specific cost grew from {} to {} between {} and {} NFTs",
+        small.buy_specific,
+        large.buy_specific,
+        small.inventory,
+        large.inventory
+    );
+    assert!(
+        large.price <= small.price * MAX_INVENTORY_COST_GROWTH,
+        "price cost grew from {} to {} between {} and {} NFTs",
+        small.price,
+        large.price,
+        small.inventory,
+        large.inventory
+    );
+}
+
+#[test]
+fn test_costs_do_not_scale_with_inventory() {
+    let small = measure(12);
+    let large = measure(10_000);
+    assert_flat(&small, &large);
+}
+
+#[test]
+fn test_fee_estimates_cover_measured_costs() {
+    let mut sale = stocked_sale(12);
+    let config = NFTSaleBuilder::new(sale.nft_resource, XRD)
+        .price(1)
+        .build()
+        .unwrap();
+
+    let receipt = buy_specific(&mut sale, 12);
+    assert!(execution_cost(&receipt) <= estimate_execution_cost(SaleFlow::BuySpecific, 1, 0));
+    assert!(
+        receipt.fee_summary.total_cost() <= estimate_fee_lock(&config, SaleFlow::BuySpecific, 1, 0)
+    );
+    for number_of_nfts in [1u16, 10] {
+        let receipt = sale.buy(Decimal::from(number_of_nfts), number_of_nfts);
+        assert!(
+            execution_cost(&receipt) <= estimate_execution_cost(SaleFlow::Buy, number_of_nfts, 0)
+        );
+        assert!(
+            receipt.fee_summary.total_cost()
+                <= estimate_fee_lock(&config, SaleFlow::Buy, number_of_nfts, 0)
+        );
+    }
+}
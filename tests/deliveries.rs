@@ -0,0 +1,90 @@
+mod common;
+
+use common::TestSale;
+use nft_sale_interface::DeliveryPolicy;
+use scrypto_test::prelude::*;
+
+// Set the default deposit rule of an account signed for by `public_key`
+fn set_deposit_rule(
+    sale: &mut TestSale,
+    public_key: Secp256k1PublicKey,
+    account: ComponentAddress,
+    rule: DefaultDepositRule,
+) {
+    let manifest = ManifestBuilder::new()
+        .lock_fee_from_faucet()
+        .call_method(account, "set_default_deposit_rule", manifest_args!(rule))
+        .build();
+    sale.ledger
+        .execute_manifest(
+            manifest,
+            vec![NonFungibleGlobalId::from_public_key(&public_key)],
+        )
+        .expect_commit_success();
+}
+
+fn airdrop_to(sale: &mut TestSale, recipient: ComponentAddress, policy: DeliveryPolicy) {
+    let component = sale.component;
+    let receipt = sale.as_owner(|builder| {
+        builder
+            .call_method(component, "set_delivery_policy", manifest_args!(policy))
+            .call_method(
+                component,
+                "add_airdrop_recipients",
+                manifest_args!(vec![recipient]),
+            )
+            .call_method(
+                component,
+                "airdrop_batch",
+                manifest_args!(TestSale::NO_BADGE, 1u32),
+            )
+    });
+    let refused = receipt
+        .expect_commit_success()
+        .application_events
+        .iter()
+        .any(|(event_type, _)| event_type.1 == "DeliveryRefusedEvent");
+    assert!(refused);
+}
+
+fn retry_deliveries(sale: &mut TestSale) -> u64 {
+    let component = sale.component;
+    sale.as_owner(|builder| {
+        builder.call_method(
+            component,
+            "retry_deliveries",
+            manifest_args!(TestSale::NO_BADGE, 10u32),
+        )
+    })
+    .expect_commit_success()
+    .output(1)
+}
+
+#[test]
+fn refused_deliveries_are_restocked() {
+    let mut sale = TestSale::new(2, 2, dec!(10));
+    let (public_key, _, recipient) = sale.ledger.new_allocated_account();
+    set_deposit_rule(&mut sale, public_key, recipient, DefaultDepositRule::Reject);
+
+    airdrop_to(&mut sale, recipient, DeliveryPolicy::Restock);
+    let (component, nft_resource) = (sale.component, sale.nft_resource);
+    assert_eq!(sale.balance(recipient, nft_resource), dec!(0));
+    assert_eq!(sale.balance(component, nft_resource), dec!(2));
+    assert_eq!(retry_deliveries(&mut sale), 0);
+}
+
+#[test]
+fn refused_deliveries_are_retried_until_accepted() {
+    let mut sale = TestSale::new(2, 2, dec!(10));
+    let (public_key, _, recipient) = sale.ledger.new_allocated_account();
+    set_deposit_rule(&mut sale, public_key, recipient, DefaultDepositRule::Reject);
+    let nft_resource = sale.nft_resource;
+
+    airdrop_to(&mut sale, recipient, DeliveryPolicy::Retry);
+    assert_eq!(retry_deliveries(&mut sale), 1);
+    assert_eq!(sale.balance(recipient, nft_resource), dec!(0));
+
+    set_deposit_rule(&mut sale, public_key, recipient, DefaultDepositRule::Accept);
+    assert_eq!(retry_deliveries(&mut sale), 0);
+    assert_eq!(sale.balance(recipient, nft_resource), dec!(1));
+}
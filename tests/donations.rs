@@ -0,0 +1,52 @@
+mod common;
+
+use common::TestSale;
+use scrypto_test::prelude::*;
+
+fn donate_unsold(sale: &mut TestSale, limit: u32) -> TransactionReceipt {
+    let component = sale.component;
+    sale.as_owner(|builder| builder.call_method(component, "donate_unsold", manifest_args!(limit)))
+}
+
+#[test]
+fn unsold_nfts_are_dealt_out_in_batches() {
+    let mut sale = TestSale::new(4, 4, dec!(10));
+    let (_, _, museum) = sale.ledger.new_allocated_account();
+    let (account, component, nft_resource) = (sale.account, sale.component, sale.nft_resource);
+    let donation_list = vec![(account, 1u32), (museum, 1u32)];
+    sale.as_owner(|builder| {
+        builder.call_method(
+            component,
+            "set_donation_list",
+            manifest_args!(donation_list),
+        )
+    })
+    .expect_commit_success();
+
+    donate_unsold(&mut sale, 3).expect_commit_success();
+    assert_eq!(sale.balance(account, nft_resource), dec!(2));
+    assert_eq!(sale.balance(museum, nft_resource), dec!(1));
+
+    // The second batch picks up the round robin where the first left off
+    donate_unsold(&mut sale, 10).expect_commit_success();
+    assert_eq!(sale.balance(account, nft_resource), dec!(2));
+    assert_eq!(sale.balance(museum, nft_resource), dec!(2));
+    assert_eq!(sale.balance(component, nft_resource), dec!(0));
+}
+
+#[test]
+fn donations_wait_for_the_sale_to_end() {
+    let mut sale = TestSale::new(4, 4, dec!(10));
+    let (account, component) = (sale.account, sale.component);
+    sale.as_owner(|builder| {
+        builder.call_method(
+            component,
+            "set_donation_list",
+            manifest_args!(vec![(account, 1u32)]),
+        )
+    })
+    .expect_commit_success();
+    sale.start_sale().expect_commit_success();
+
+    donate_unsold(&mut sale, 4).expect_commit_failure();
+}
@@ -0,0 +1,127 @@
+mod common;
+
+use common::TestSale;
+use nft_sale_interface::PriceSchedule;
+use scrypto_test::prelude::*;
+
+// A started sale of 3 NFTs at 10 where NFT #1 drops from 100 by 20 per epoch down to 30
+fn sale_with_schedule() -> TestSale {
+    let mut sale = TestSale::new(3, 3, dec!(10));
+    let component = sale.component;
+    let schedule = PriceSchedule {
+        start_price: dec!(100),
+        floor_price: dec!(30),
+        starts_at: sale.ledger.get_current_epoch(),
+        decline_per_epoch: dec!(20),
+    };
+    sale.as_owner(|builder| {
+        builder
+            .call_method(
+                component,
+                "schedule_listing",
+                manifest_args!(
+                    TestSale::NO_BADGE,
+                    NonFungibleLocalId::integer(1),
+                    Some(schedule)
+                ),
+            )
+            .call_method(component, "start_sale", manifest_args!(TestSale::NO_BADGE))
+    })
+    .expect_commit_success();
+    sale
+}
+
+fn buy_specific(sale: &mut TestSale, payment: Decimal) -> TransactionReceipt {
+    let (account, component, payment_token) = (sale.account, sale.component, sale.payment_token);
+    sale.as_public(|builder| {
+        builder
+            .withdraw_from_account(account, payment_token, payment)
+            .take_all_from_worktop(payment_token, "payment")
+            .with_bucket("payment", |builder, bucket| {
+                builder.call_method(
+                    component,
+                    "buy_specific",
+                    manifest_args!(bucket, NonFungibleLocalId::integer(1)),
+                )
+            })
+    })
+}
+
+#[test]
+fn scheduled_listings_sell_at_the_declining_price() {
+    let mut sale = sale_with_schedule();
+    let (account, nft_resource, payment_token) =
+        (sale.account, sale.nft_resource, sale.payment_token);
+
+    // The scheduled NFT is not part of the flat-price inventory
+    sale.buy(dec!(30), 3).expect_commit_failure();
+
+    let now = sale.ledger.get_current_epoch();
+    sale.ledger.set_current_epoch(now.after(2).unwrap());
+    buy_specific(&mut sale, dec!(59)).expect_commit_failure();
+
+    let before = sale.balance(account, payment_token);
+    buy_specific(&mut sale, dec!(100)).expect_commit_success();
+    assert_eq!(sale.balance(account, payment_token), before - dec!(60));
+    assert_eq!(sale.balance(account, nft_resource), dec!(1));
+}
+
+#[test]
+fn scheduled_prices_stop_at_the_floor() {
+    let mut sale = sale_with_schedule();
+    let component = sale.component;
+    let now = sale.ledger.get_current_epoch();
+    sale.ledger.set_current_epoch(now.after(100).unwrap());
+
+    let price: Option<Decimal> = sale
+        .as_public(|builder| {
+            builder.call_method(
+                component,
+                "listing_price",
+                manifest_args!(NonFungibleLocalId::integer(1)),
+            )
+        })
+        .expect_commit_success()
+        .output(1);
+    assert_eq!(price, Some(dec!(30)));
+}
+
+#[test]
+fn future_prices_match_what_buy_specific_charges() {
+    let mut sale = sale_with_schedule();
+    let (account, component, payment_token) = (sale.account, sale.component, sale.payment_token);
+    let target = sale.ledger.get_current_epoch().after(3).unwrap();
+
+    let quote: Option<Decimal> = sale
+        .as_public(|builder| {
+            builder.call_method(
+                component,
+                "listing_price_at",
+                manifest_args!(NonFungibleLocalId::integer(1), target),
+            )
+        })
+        .expect_commit_success()
+        .output(1);
+    assert_eq!(quote, Some(dec!(40)));
+
+    sale.ledger.set_current_epoch(target);
+    let before = sale.balance(account, payment_token);
+    buy_specific(&mut sale, quote.unwrap()).expect_commit_success();
+    assert_eq!(sale.balance(account, payment_token), before - dec!(40));
+}
+
+#[test]
+fn scheduled_listings_pass_the_same_gates_as_purchases() {
+    let mut sale = sale_with_schedule();
+    let component = sale.component;
+    sale.as_owner(|builder| {
+        builder.call_method(
+            component,
+            "set_allowlist_root",
+            manifest_args!(Some(Hash([1; 32]))),
+        )
+    })
+    .expect_commit_success();
+
+    buy_specific(&mut sale, dec!(100)).expect_commit_failure();
+}
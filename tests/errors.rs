@@ -0,0 +1,28 @@
+mod common;
+
+use common::TestSale;
+use nft_sale_interface::SaleError;
+use scrypto_test::prelude::*;
+
+fn expect_error(receipt: TransactionReceipt, expected: SaleError) {
+    receipt.expect_specific_failure(|error| {
+        SaleError::from_message(&format!("{:?}", error)) == Some(expected)
+    });
+}
+
+#[test]
+fn failed_purchases_report_error_codes() {
+    let mut sale = TestSale::new(20, 20, dec!(10));
+    expect_error(sale.buy(dec!(10), 1), SaleError::SaleNotOpen);
+
+    sale.start_sale().expect_commit_success();
+    expect_error(sale.buy(dec!(5), 1), SaleError::InsufficientPayment);
+    expect_error(sale.buy(dec!(110), 11), SaleError::TransactionLimitExceeded);
+}
+
+#[test]
+fn invalid_prices_report_error_codes() {
+    let mut sale = TestSale::new(1, 1, dec!(10));
+    expect_error(sale.change_price(dec!(-1)), SaleError::InvalidPrice);
+    sale.change_price(dec!(0)).expect_commit_success();
+}
@@ -0,0 +1,158 @@
+mod common;
+
+use common::TestSale;
+use scrypto_test::prelude::*;
+
+fn escrow(sale: &mut TestSale, payment: Decimal, number_of_nfts: u16) -> TransactionReceipt {
+    let (account, component, payment_token) = (sale.account, sale.component, sale.payment_token);
+    sale.as_public(|builder| {
+        builder
+            .withdraw_from_account(account, payment_token, payment)
+            .take_all_from_worktop(payment_token, "payment")
+            .with_bucket("payment", |builder, bucket| {
+                builder.call_method(component, "escrow", manifest_args!(bucket, number_of_nfts))
+            })
+    })
+}
+
+fn claim_escrow(sale: &mut TestSale, receipts: u64) -> TransactionReceipt {
+    let (account, component, escrow_receipt) = (sale.account, sale.component, sale.escrow_receipt);
+    sale.as_public(|builder| {
+        builder
+            .withdraw_from_account(account, escrow_receipt, receipts)
+            .take_all_from_worktop(escrow_receipt, "receipts")
+            .with_bucket("receipts", |builder, bucket| {
+                builder.call_method(component, "claim_escrow", manifest_args!(bucket))
+            })
+    })
+}
+
+fn open_and_convert(sale: &mut TestSale) {
+    let component = sale.component;
+    sale.as_owner(|builder| {
+        builder
+            .call_method(component, "start_sale", manifest_args!(TestSale::NO_BADGE))
+            .call_method(
+                component,
+                "convert_escrows",
+                manifest_args!(TestSale::NO_BADGE, 10u32),
+            )
+    })
+    .expect_commit_success();
+}
+
+#[test]
+fn escrows_convert_in_deposit_order_at_the_opening_price() {
+    let mut sale = TestSale::new(3, 3, dec!(10));
+    let (account, nft_resource, payment_token) =
+        (sale.account, sale.nft_resource, sale.payment_token);
+    let before = sale.balance(account, payment_token);
+
+    escrow(&mut sale, dec!(25), 2).expect_commit_success();
+    escrow(&mut sale, dec!(20), 2).expect_commit_success();
+    open_and_convert(&mut sale);
+
+    // The first escrow gets both NFTs, the second only the one left over
+    claim_escrow(&mut sale, 2).expect_commit_success();
+    assert_eq!(sale.balance(account, nft_resource), dec!(3));
+    assert_eq!(sale.balance(account, payment_token), before - dec!(30));
+    assert_eq!(sale.balance(account, sale.escrow_receipt), dec!(0));
+}
+
+#[test]
+fn price_changes_after_the_opening_do_not_reach_escrows() {
+    let mut sale = TestSale::new(2, 2, dec!(10));
+    let (account, nft_resource, payment_token) =
+        (sale.account, sale.nft_resource, sale.payment_token);
+    let before = sale.balance(account, payment_token);
+
+    escrow(&mut sale, dec!(20), 2).expect_commit_success();
+    sale.start_sale().expect_commit_success();
+    sale.change_price(dec!(15)).expect_commit_success();
+    let component = sale.component;
+    sale.as_owner(|builder| {
+        builder.call_method(
+            component,
+            "convert_escrows",
+            manifest_args!(TestSale::NO_BADGE, 10u32),
+        )
+    })
+    .expect_commit_success();
+
+    claim_escrow(&mut sale, 1).expect_commit_success();
+    assert_eq!(sale.balance(account, nft_resource), dec!(2));
+    assert_eq!(sale.balance(account, payment_token), before - dec!(20));
+}
+
+#[test]
+fn escrows_are_refundable_before_conversion() {
+    let mut sale = TestSale::new(3, 3, dec!(10));
+    let (account, payment_token) = (sale.account, sale.payment_token);
+    let before = sale.balance(account, payment_token);
+
+    escrow(&mut sale, dec!(20), 2).expect_commit_success();
+    claim_escrow(&mut sale, 1).expect_commit_success();
+
+    assert_eq!(sale.balance(account, payment_token), before);
+    sale.start_sale().expect_commit_success();
+    escrow(&mut sale, dec!(20), 2).expect_commit_failure();
+}
+
+#[test]
+fn only_admins_convert_escrows() {
+    let mut sale = TestSale::new(3, 3, dec!(10));
+    let component = sale.component;
+    sale.start_sale().expect_commit_success();
+
+    sale.as_public(|builder| {
+        builder.call_method(
+            component,
+            "convert_escrows",
+            manifest_args!(TestSale::NO_BADGE, 1u32),
+        )
+    })
+    .expect_auth_failure();
+}
+
+#[test]
+fn escrows_are_capped_per_transaction() {
+    let mut sale = TestSale::new_with(6, 6, |builder| builder.price(dec!(10)).max_per_tx(2));
+    let (account, nft_resource) = (sale.account, sale.nft_resource);
+
+    escrow(&mut sale, dec!(30), 3).expect_commit_failure();
+    escrow(&mut sale, dec!(30), 2).expect_commit_success();
+    open_and_convert(&mut sale);
+
+    claim_escrow(&mut sale, 1).expect_commit_success();
+    assert_eq!(sale.balance(account, nft_resource), dec!(2));
+}
+
+#[test]
+fn escrows_cannot_reopen_after_the_sale_ends() {
+    let mut sale = TestSale::new(3, 3, dec!(10));
+    let component = sale.component;
+    sale.as_owner(|builder| {
+        builder
+            .call_method(component, "start_sale", manifest_args!(TestSale::NO_BADGE))
+            .call_method(component, "end_sale", manifest_args!(TestSale::NO_BADGE))
+    })
+    .expect_commit_success();
+
+    escrow(&mut sale, dec!(10), 1).expect_commit_failure();
+}
+
+#[test]
+fn allowlisted_sales_take_no_anonymous_escrows() {
+    let mut sale = TestSale::new(3, 3, dec!(10));
+    let component = sale.component;
+    sale.as_owner(|builder| {
+        builder.call_method(
+            component,
+            "set_allowlist_root",
+            manifest_args!(Some(Hash([1; 32]))),
+        )
+    })
+    .expect_commit_success();
+
+    escrow(&mut sale, dec!(10), 1).expect_commit_failure();
+}
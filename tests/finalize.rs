@@ -0,0 +1,71 @@
+mod common;
+
+use common::TestSale;
+use scrypto_test::prelude::*;
+
+fn finalize(sale: &mut TestSale) -> TransactionReceipt {
+    let component = sale.component;
+    sale.as_owner(|builder| builder.call_method(component, "finalize", manifest_args!()))
+}
+
+#[test]
+fn settled_sales_are_finalized_for_good() {
+    let mut sale = TestSale::new(1, 1, dec!(10));
+    sale.start_sale().expect_commit_success();
+    sale.buy(dec!(10), 1).expect_commit_success();
+    finalize(&mut sale).expect_commit_failure();
+    sale.end_refund_guarantee();
+    sale.withdraw_profits().expect_commit_success();
+
+    let receipt = finalize(&mut sale);
+    assert!(receipt
+        .expect_commit_success()
+        .application_events
+        .iter()
+        .any(|(event_type, _)| event_type.1 == "SaleFinalizedEvent"));
+    let component = sale.component;
+    assert_eq!(
+        sale.ledger.get_metadata(component.into(), "finalized"),
+        Some(MetadataValue::Bool(true))
+    );
+
+    sale.start_sale().expect_commit_failure();
+    sale.add_nfts(1).expect_commit_failure();
+    finalize(&mut sale).expect_commit_failure();
+}
+
+#[test]
+fn sales_holding_nfts_cannot_be_finalized() {
+    let mut sale = TestSale::new(1, 1, dec!(10));
+    finalize(&mut sale).expect_commit_failure();
+}
+
+#[test]
+fn unspent_allowance_budgets_block_finalization() {
+    let mut sale = TestSale::new(1, 1, dec!(10));
+    let (account, component, payment_token) = (sale.account, sale.component, sale.payment_token);
+    sale.start_sale().expect_commit_success();
+    sale.buy(dec!(10), 1).expect_commit_success();
+    sale.end_refund_guarantee();
+    sale.withdraw_profits().expect_commit_success();
+
+    sale.as_owner(|builder| {
+        builder.call_method(
+            component,
+            "grant_allowance",
+            manifest_args!(account, 1u64, dec!(10)),
+        )
+    })
+    .expect_commit_success();
+    sale.as_public(|builder| {
+        builder
+            .withdraw_from_account(account, payment_token, dec!(10))
+            .take_all_from_worktop(payment_token, "budget")
+            .with_bucket("budget", |builder, bucket| {
+                builder.call_method(component, "deposit_budget", manifest_args!(account, bucket))
+            })
+    })
+    .expect_commit_success();
+
+    finalize(&mut sale).expect_commit_failure();
+}
@@ -0,0 +1,257 @@
+mod common;
+
+use common::TestSale;
+use nft_sale_interface::{GatedPhase, PhaseInfo, TreasuryBreakdown};
+use scrypto_test::prelude::*;
+
+fn buy_gated(sale: &mut TestSale, badge: ResourceAddress) -> TransactionReceipt {
+    let (account, component, payment_token) = (sale.account, sale.component, sale.payment_token);
+    sale.as_public(|builder| {
+        builder
+            .create_proof_from_account_of_amount(account, badge, dec!(1))
+            .pop_from_auth_zone("badge")
+            .withdraw_from_account(account, payment_token, dec!(10))
+            .take_all_from_worktop(payment_token, "payment")
+            .with_name_lookup(|builder, lookup| {
+                let (payment, badge) = (lookup.bucket("payment"), lookup.proof("badge"));
+                builder.call_method(component, "buy_gated", manifest_args!(payment, 1u16, badge))
+            })
+    })
+}
+
+fn gated_phase(sale: &mut TestSale) -> Option<String> {
+    let component = sale.component;
+    let info: PhaseInfo = sale
+        .as_public(|builder| builder.call_method(component, "current_phase_info", manifest_args!()))
+        .expect_commit_success()
+        .output(1);
+    info.gated_phase
+}
+
+#[test]
+fn phases_admit_the_holders_of_their_badges() {
+    let mut sale = TestSale::new(10, 10, dec!(10));
+    let (account, component) = (sale.account, sale.component);
+    let og_pass = sale.ledger.create_fungible_resource(dec!(1), 0, account);
+    let partner_pass = sale.ledger.create_fungible_resource(dec!(1), 0, account);
+    let now = sale.ledger.get_current_epoch();
+    let phases = vec![
+        GatedPhase {
+            name: "OG".to_string(),
+            badge: Some(og_pass),
+            starts_at: now,
+            proceeds_to: None,
+        },
+        GatedPhase {
+            name: "Partners".to_string(),
+            badge: Some(partner_pass),
+            starts_at: now.after(10).unwrap(),
+            proceeds_to: None,
+        },
+        GatedPhase {
+            name: "Public".to_string(),
+            badge: None,
+            starts_at: now.after(20).unwrap(),
+            proceeds_to: None,
+        },
+    ];
+    sale.as_owner(|builder| {
+        builder
+            .call_method(component, "set_gated_phases", manifest_args!(phases))
+            .call_method(component, "start_sale", manifest_args!(TestSale::NO_BADGE))
+    })
+    .expect_commit_success();
+
+    assert_eq!(gated_phase(&mut sale), Some("OG".to_string()));
+    sale.buy(dec!(10), 1).expect_commit_failure();
+    buy_gated(&mut sale, partner_pass).expect_commit_failure();
+    buy_gated(&mut sale, og_pass).expect_commit_success();
+
+    // Earlier badges stay valid in later phases
+    sale.ledger.set_current_epoch(now.after(10).unwrap());
+    buy_gated(&mut sale, partner_pass).expect_commit_success();
+    buy_gated(&mut sale, og_pass).expect_commit_success();
+    sale.buy(dec!(10), 1).expect_commit_failure();
+
+    sale.ledger.set_current_epoch(now.after(20).unwrap());
+    assert_eq!(gated_phase(&mut sale), Some("Public".to_string()));
+    sale.buy(dec!(10), 1).expect_commit_success();
+}
+
+#[test]
+fn badges_are_refused_without_gated_phases() {
+    let mut sale = TestSale::new(10, 10, dec!(10));
+    let account = sale.account;
+    let badge = sale.ledger.create_fungible_resource(dec!(1), 0, account);
+    sale.start_sale().expect_commit_success();
+
+    buy_gated(&mut sale, badge).expect_commit_failure();
+}
+
+#[test]
+fn phases_route_their_proceeds() {
+    let mut sale = TestSale::new(10, 10, dec!(10));
+    let (component, payment_token) = (sale.component, sale.payment_token);
+    let (_, _, development) = sale.ledger.new_allocated_account();
+    let now = sale.ledger.get_current_epoch();
+    let phases = vec![
+        GatedPhase {
+            name: "Presale".to_string(),
+            badge: None,
+            starts_at: now,
+            proceeds_to: Some(development),
+        },
+        GatedPhase {
+            name: "Public".to_string(),
+            badge: None,
+            starts_at: now.after(10).unwrap(),
+            proceeds_to: None,
+        },
+    ];
+    sale.as_owner(|builder| {
+        builder
+            .call_method(component, "set_gated_phases", manifest_args!(phases))
+            .call_method(component, "start_sale", manifest_args!(TestSale::NO_BADGE))
+    })
+    .expect_commit_success();
+
+    let receipt = sale.buy(dec!(10), 1);
+    let commit = receipt.expect_commit_success();
+    assert!(commit
+        .application_events
+        .iter()
+        .any(|(event_type, _)| event_type.1 == "ProceedsRoutedEvent"));
+    assert_eq!(sale.balance(development, payment_token), dec!(10));
+
+    // Later proceeds stay in the treasury
+    sale.ledger.set_current_epoch(now.after(10).unwrap());
+    sale.buy(dec!(10), 1).expect_commit_success();
+    assert_eq!(sale.balance(development, payment_token), dec!(10));
+
+    // Refunds pay the routed price too, but only what reached the treasury can be held back
+    sale.as_owner(|builder| builder.call_method(component, "cancel_sale", manifest_args!()))
+        .expect_commit_success();
+    let breakdown: TreasuryBreakdown = sale
+        .as_public(|builder| builder.call_method(component, "treasury_breakdown", manifest_args!()))
+        .expect_commit_success()
+        .output(1);
+    assert_eq!(breakdown.locked_for_refunds, dec!(10));
+}
+
+#[test]
+fn gated_buyers_get_no_way_around_the_allowlist() {
+    let mut sale = TestSale::new(10, 10, dec!(10));
+    let (account, component) = (sale.account, sale.component);
+    let og_pass = sale.ledger.create_fungible_resource(dec!(1), 0, account);
+    let stranger = sale.ledger.create_fungible_resource(dec!(1), 0, account);
+    let phases = vec![GatedPhase {
+        name: "Public".to_string(),
+        badge: None,
+        starts_at: sale.ledger.get_current_epoch(),
+        proceeds_to: None,
+    }];
+    sale.as_owner(|builder| {
+        builder
+            .call_method(component, "set_gated_phases", manifest_args!(phases))
+            .call_method(component, "start_sale", manifest_args!(TestSale::NO_BADGE))
+    })
+    .expect_commit_success();
+
+    // An open phase does not turn any resource into a badge
+    buy_gated(&mut sale, stranger).expect_commit_failure();
+    sale.buy(dec!(10), 1).expect_commit_success();
+
+    let phases = vec![GatedPhase {
+        name: "OG".to_string(),
+        badge: Some(og_pass),
+        starts_at: sale.ledger.get_current_epoch(),
+        proceeds_to: None,
+    }];
+    let root = Hash([7; 32]);
+    sale.as_owner(|builder| {
+        builder
+            .call_method(component, "set_gated_phases", manifest_args!(phases))
+            .call_method(component, "set_allowlist_root", manifest_args!(Some(root)))
+    })
+    .expect_commit_success();
+    buy_gated(&mut sale, og_pass).expect_commit_failure();
+}
+
+#[test]
+fn gated_buyers_have_to_name_an_account_under_a_cooldown() {
+    let mut sale = TestSale::new(5, 5, dec!(10));
+    let (account, component) = (sale.account, sale.component);
+    let og_pass = sale.ledger.create_fungible_resource(dec!(1), 0, account);
+    let phases = vec![GatedPhase {
+        name: "OG".to_string(),
+        badge: Some(og_pass),
+        starts_at: sale.ledger.get_current_epoch(),
+        proceeds_to: None,
+    }];
+    sale.as_owner(|builder| {
+        builder
+            .call_method(component, "set_gated_phases", manifest_args!(phases))
+            .call_method(
+                component,
+                "set_purchase_cooldown",
+                manifest_args!(Some(10u64)),
+            )
+            .call_method(component, "start_sale", manifest_args!(TestSale::NO_BADGE))
+    })
+    .expect_commit_success();
+
+    buy_gated(&mut sale, og_pass).expect_commit_failure();
+}
+
+#[test]
+fn presales_can_be_configured_at_instantiation() {
+    // Any resource the account holds serves as the presale badge
+    let presale = GatedPhase {
+        name: "Presale".to_string(),
+        badge: Some(XRD),
+        starts_at: Epoch::zero(),
+        proceeds_to: None,
+    };
+    let mut sale = TestSale::new_with(10, 10, |builder| builder.price(10).presale(vec![presale]));
+    sale.start_sale().expect_commit_success();
+
+    assert_eq!(gated_phase(&mut sale), Some("Presale".to_string()));
+    sale.buy(dec!(10), 1).expect_commit_failure();
+    buy_gated(&mut sale, XRD).expect_commit_success();
+}
+
+#[test]
+fn phase_info_counts_down_to_the_next_gated_phase() {
+    let mut sale = TestSale::new(10, 10, dec!(10));
+    let component = sale.component;
+    let now = sale.ledger.get_current_epoch();
+    let phase = |name: &str, starts_at: Epoch| GatedPhase {
+        name: name.to_string(),
+        badge: None,
+        starts_at,
+        proceeds_to: None,
+    };
+    let phases = vec![
+        phase("Early", now.after(5).unwrap()),
+        phase("Public", now.after(15).unwrap()),
+    ];
+    sale.as_owner(|builder| {
+        builder.call_method(component, "set_gated_phases", manifest_args!(phases))
+    })
+    .expect_commit_success();
+    let epochs_until_next_phase = |sale: &mut TestSale| {
+        let info: PhaseInfo = sale
+            .as_public(|builder| {
+                builder.call_method(component, "current_phase_info", manifest_args!())
+            })
+            .expect_commit_success()
+            .output(1);
+        info.epochs_until_next_phase
+    };
+
+    assert_eq!(epochs_until_next_phase(&mut sale), Some(5));
+    sale.ledger.set_current_epoch(now.after(5).unwrap());
+    assert_eq!(epochs_until_next_phase(&mut sale), Some(10));
+    sale.ledger.set_current_epoch(now.after(15).unwrap());
+    assert_eq!(epochs_until_next_phase(&mut sale), None);
+}
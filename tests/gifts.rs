@@ -0,0 +1,53 @@
+mod common;
+
+use common::TestSale;
+use nft_sale_interface::NftSelection;
+use scrypto_test::prelude::*;
+
+fn gift(
+    sale: &mut TestSale,
+    selection: NftSelection,
+    recipient: ComponentAddress,
+) -> TransactionReceipt {
+    let component = sale.component;
+    sale.as_owner(|builder| {
+        builder.call_method(
+            component,
+            "gift_from_vault",
+            manifest_args!(selection, recipient),
+        )
+    })
+}
+
+#[test]
+fn gifts_are_bounded_per_call() {
+    let mut sale = TestSale::new(60, 60, dec!(10));
+    let nft_resource = sale.nft_resource;
+    let (_, _, partner) = sale.ledger.new_allocated_account();
+
+    gift(&mut sale, NftSelection::Count(51), partner).expect_commit_failure();
+    gift(&mut sale, NftSelection::Count(50), partner).expect_commit_success();
+    assert_eq!(sale.balance(partner, nft_resource), dec!(50));
+}
+
+#[test]
+fn gifts_take_nfts_by_id_or_count_until_the_vault_is_empty() {
+    let mut sale = TestSale::new(3, 3, dec!(10));
+    let nft_resource = sale.nft_resource;
+    let (_, _, partner) = sale.ledger.new_allocated_account();
+    let chosen = NonFungibleLocalId::integer(1);
+
+    gift(
+        &mut sale,
+        NftSelection::Ids(indexset!(chosen.clone())),
+        partner,
+    )
+    .expect_commit_success();
+    assert_eq!(sale.balance(partner, nft_resource), dec!(1));
+    gift(&mut sale, NftSelection::Count(2), partner).expect_commit_success();
+    assert_eq!(sale.balance(partner, nft_resource), dec!(3));
+
+    // Nothing is left to gift, neither the NFT already given away nor any other
+    gift(&mut sale, NftSelection::Ids(indexset!(chosen)), partner).expect_commit_failure();
+    gift(&mut sale, NftSelection::Count(1), partner).expect_commit_failure();
+}
@@ -0,0 +1,61 @@
+mod common;
+
+use common::TestSale;
+use scrypto_test::prelude::*;
+
+// Designate a guardian badge held by the test account
+fn designate_guardian(sale: &mut TestSale) -> ResourceAddress {
+    let (account, component) = (sale.account, sale.component);
+    let guardian_badge = sale.ledger.create_fungible_resource(dec!(1), 0, account);
+    sale.as_owner(|builder| {
+        builder.set_main_role(component, "guardian", rule!(require(guardian_badge)))
+    })
+    .expect_commit_success();
+    guardian_badge
+}
+
+#[test]
+fn the_guardian_pauses_and_resumes_buying() {
+    let mut sale = TestSale::new(5, 5, dec!(10));
+    let component = sale.component;
+    sale.start_sale().expect_commit_success();
+    let guardian_badge = designate_guardian(&mut sale);
+
+    sale.with_badge(guardian_badge, |builder| {
+        builder.call_method(component, "pause_buying", manifest_args!())
+    })
+    .expect_commit_success();
+    sale.buy(dec!(10), 1).expect_commit_failure();
+
+    sale.with_badge(guardian_badge, |builder| {
+        builder.call_method(component, "resume_buying", manifest_args!())
+    })
+    .expect_commit_success();
+    sale.buy(dec!(10), 1).expect_commit_success();
+}
+
+#[test]
+fn the_guardian_has_no_other_powers() {
+    let mut sale = TestSale::new(5, 5, dec!(10));
+    let component = sale.component;
+    sale.start_sale().expect_commit_success();
+    sale.buy(dec!(10), 1).expect_commit_success();
+
+    // Nobody can pause before a guardian is designated, the owner included
+    sale.as_owner(|builder| builder.call_method(component, "pause_buying", manifest_args!()))
+        .expect_auth_failure();
+
+    let guardian_badge = designate_guardian(&mut sale);
+    sale.with_badge(guardian_badge, |builder| {
+        builder.call_method(component, "withdraw_profits", manifest_args!())
+    })
+    .expect_auth_failure();
+    sale.with_badge(guardian_badge, |builder| {
+        builder.call_method(
+            component,
+            "change_price",
+            manifest_args!(TestSale::NO_BADGE, dec!(1)),
+        )
+    })
+    .expect_auth_failure();
+}
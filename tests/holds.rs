@@ -0,0 +1,186 @@
+mod common;
+
+use common::TestSale;
+use nft_sale_interface::HoldTerms;
+use scrypto_test::prelude::*;
+
+// A started sale of 3 NFTs at 10, offering 5 epoch holds for a deposit of at least 2
+fn sale_with_holds(expiry_fee: Decimal) -> TestSale {
+    let mut sale = TestSale::new(3, 3, dec!(10));
+    let component = sale.component;
+    let terms = HoldTerms {
+        duration_epochs: 5,
+        minimum_deposit: dec!(2),
+        expiry_fee,
+    };
+    sale.as_owner(|builder| {
+        builder
+            .call_method(component, "set_hold_terms", manifest_args!(Some(terms)))
+            .call_method(component, "start_sale", manifest_args!(TestSale::NO_BADGE))
+    })
+    .expect_commit_success();
+    sale
+}
+
+fn hold(sale: &mut TestSale, id: u64, deposit: Decimal) -> TransactionReceipt {
+    let (account, component, payment_token) = (sale.account, sale.component, sale.payment_token);
+    sale.as_public(|builder| {
+        builder
+            .withdraw_from_account(account, payment_token, deposit)
+            .take_all_from_worktop(payment_token, "deposit")
+            .with_bucket("deposit", |builder, bucket| {
+                builder.call_method(
+                    component,
+                    "hold",
+                    manifest_args!(NonFungibleLocalId::integer(id), bucket),
+                )
+            })
+    })
+}
+
+fn complete_hold(sale: &mut TestSale, payment: Decimal) -> TransactionReceipt {
+    let (account, component, hold_receipt, payment_token) = (
+        sale.account,
+        sale.component,
+        sale.hold_receipt,
+        sale.payment_token,
+    );
+    sale.as_public(|builder| {
+        builder
+            .withdraw_from_account(account, hold_receipt, 1)
+            .take_all_from_worktop(hold_receipt, "receipt")
+            .withdraw_from_account(account, payment_token, payment)
+            .take_all_from_worktop(payment_token, "payment")
+            .with_name_lookup(|builder, lookup| {
+                let (receipt, payment) = (lookup.bucket("receipt"), lookup.bucket("payment"));
+                builder.call_method(component, "complete_hold", manifest_args!(receipt, payment))
+            })
+    })
+}
+
+fn reclaim_hold(sale: &mut TestSale) -> TransactionReceipt {
+    let (account, component, hold_receipt) = (sale.account, sale.component, sale.hold_receipt);
+    sale.as_public(|builder| {
+        builder
+            .withdraw_from_account(account, hold_receipt, 1)
+            .take_all_from_worktop(hold_receipt, "receipt")
+            .with_bucket("receipt", |builder, bucket| {
+                builder.call_method(component, "reclaim_hold", manifest_args!(bucket))
+            })
+    })
+}
+
+#[test]
+fn held_nfts_are_bought_with_the_deposit_credited() {
+    let mut sale = sale_with_holds(dec!(0));
+    let (account, nft_resource, payment_token) =
+        (sale.account, sale.nft_resource, sale.payment_token);
+    let before = sale.balance(account, payment_token);
+
+    hold(&mut sale, 2, dec!(3)).expect_commit_success();
+    // The held NFT is out of reach of other buyers
+    sale.buy(dec!(30), 3).expect_commit_failure();
+    complete_hold(&mut sale, dec!(7)).expect_commit_success();
+
+    assert_eq!(sale.balance(account, nft_resource), dec!(1));
+    assert_eq!(sale.balance(account, payment_token), before - dec!(10));
+    assert_eq!(sale.balance(account, sale.hold_receipt), dec!(0));
+}
+
+#[test]
+fn expired_holds_refund_the_deposit_less_the_fee() {
+    let mut sale = sale_with_holds(dec!("0.5"));
+    let (account, payment_token) = (sale.account, sale.payment_token);
+    let before = sale.balance(account, payment_token);
+
+    hold(&mut sale, 1, dec!(4)).expect_commit_success();
+    let expires_at = sale.ledger.get_current_epoch().after(5).unwrap();
+    reclaim_hold(&mut sale).expect_commit_failure();
+
+    sale.ledger.set_current_epoch(expires_at);
+    complete_hold(&mut sale, dec!(6)).expect_commit_failure();
+    reclaim_hold(&mut sale).expect_commit_success();
+
+    assert_eq!(sale.balance(account, payment_token), before - dec!(2));
+    // The NFT is back on sale
+    sale.buy(dec!(30), 3).expect_commit_success();
+}
+
+#[test]
+fn deposits_outside_the_terms_are_rejected() {
+    let mut sale = sale_with_holds(dec!(0));
+
+    hold(&mut sale, 1, dec!(1)).expect_commit_failure();
+    hold(&mut sale, 1, dec!(11)).expect_commit_failure();
+    hold(&mut sale, 4, dec!(2)).expect_commit_failure();
+}
+
+#[test]
+fn expiry_fees_stay_under_the_maximum_fee_rate() {
+    let mut sale = TestSale::new_with(3, 3, |builder| builder.price(10).max_fee_rate(dec!("0.1")));
+    let component = sale.component;
+    let terms = |expiry_fee: Decimal| HoldTerms {
+        duration_epochs: 5,
+        minimum_deposit: dec!(2),
+        expiry_fee,
+    };
+
+    sale.as_owner(|builder| {
+        builder.call_method(
+            component,
+            "set_hold_terms",
+            manifest_args!(Some(terms(dec!("0.5")))),
+        )
+    })
+    .expect_commit_failure();
+    sale.as_owner(|builder| {
+        builder.call_method(
+            component,
+            "set_hold_terms",
+            manifest_args!(Some(terms(dec!("0.1")))),
+        )
+    })
+    .expect_commit_success();
+}
+
+#[test]
+fn holds_pass_the_same_gates_as_purchases() {
+    let mut sale = sale_with_holds(dec!(0));
+    let component = sale.component;
+    hold(&mut sale, 1, dec!(2)).expect_commit_success();
+
+    // Once the sale is limited to the allowlist, anonymous holds can neither be taken nor
+    // completed
+    sale.as_owner(|builder| {
+        builder.call_method(
+            component,
+            "set_allowlist_root",
+            manifest_args!(Some(Hash([1; 32]))),
+        )
+    })
+    .expect_commit_success();
+    hold(&mut sale, 2, dec!(2)).expect_commit_failure();
+    complete_hold(&mut sale, dec!(8)).expect_commit_failure();
+}
+
+#[test]
+fn completed_holds_are_flagged_as_large_purchases() {
+    let mut sale = sale_with_holds(dec!(0));
+    let component = sale.component;
+    sale.as_owner(|builder| {
+        builder.call_method(
+            component,
+            "set_large_purchase_threshold",
+            manifest_args!(Some(dec!(10))),
+        )
+    })
+    .expect_commit_success();
+
+    hold(&mut sale, 2, dec!(3)).expect_commit_success();
+    let receipt = complete_hold(&mut sale, dec!(7));
+    assert!(receipt
+        .expect_commit_success()
+        .application_events
+        .iter()
+        .any(|(event_type, _)| event_type.1 == "LargePurchaseEvent"));
+}
@@ -0,0 +1,158 @@
+mod common;
+
+use common::TestSale;
+use nft_sale_interface::{InventoryRules, NftSelection};
+use scrypto_test::prelude::*;
+
+const STOCKED: u64 = 3;
+
+// A sale of a collection with the given id type, stocked with freshly minted NFTs
+fn sale_of(id_type: NonFungibleIdType) -> TestSale {
+    let mut ledger = LedgerSimulatorBuilder::new().build();
+    let (public_key, _private_key, account) = ledger.new_allocated_account();
+    let nft_resource = ledger.create_freely_mintable_and_burnable_non_fungible_resource(
+        OwnerRole::None,
+        id_type,
+        None::<Vec<(NonFungibleLocalId, EmptyNonFungibleData)>>,
+        account,
+    );
+    let mut sale = TestSale::deploy(ledger, public_key, account, nft_resource, |builder| {
+        builder.price(10)
+    });
+
+    let ids: Vec<NonFungibleLocalId> = (1..=STOCKED)
+        .map(|i| match id_type {
+            NonFungibleIdType::Integer => NonFungibleLocalId::integer(i),
+            NonFungibleIdType::String => {
+                NonFungibleLocalId::string(format!("piece_{}", i)).unwrap()
+            }
+            NonFungibleIdType::Bytes => NonFungibleLocalId::bytes(vec![i as u8; 4]).unwrap(),
+            NonFungibleIdType::RUID => NonFungibleLocalId::ruid([i as u8; 32]),
+        })
+        .collect();
+    let component = sale.component;
+    sale.as_owner(|builder| {
+        let builder = match id_type {
+            NonFungibleIdType::RUID => builder
+                .mint_ruid_non_fungible(nft_resource, ids.iter().map(|_| EmptyNonFungibleData {})),
+            _ => builder.mint_non_fungible(
+                nft_resource,
+                ids.into_iter().map(|id| (id, EmptyNonFungibleData {})),
+            ),
+        };
+        builder
+            .take_all_from_worktop(nft_resource, "nfts")
+            .with_bucket("nfts", |builder, bucket| {
+                builder.call_method(
+                    component,
+                    "add_nfts_to_vault",
+                    manifest_args!(TestSale::NO_BADGE, bucket),
+                )
+            })
+            .call_method(component, "start_sale", manifest_args!(TestSale::NO_BADGE))
+    })
+    .expect_commit_success();
+    sale
+}
+
+fn held_ids(sale: &mut TestSale, owner: ComponentAddress) -> Vec<NonFungibleLocalId> {
+    let nft_resource = sale.nft_resource;
+    let vaults = sale.ledger.get_component_vaults(owner, nft_resource);
+    vaults
+        .into_iter()
+        .flat_map(|vault| {
+            sale.ledger
+                .inspect_non_fungible_vault(vault)
+                .map(|(_, ids)| ids.collect::<Vec<_>>())
+                .unwrap_or_default()
+        })
+        .collect()
+}
+
+// Gift a specific id, buy another and refund it after cancelling the sale
+fn exercise_id_taking_methods(id_type: NonFungibleIdType) {
+    let mut sale = sale_of(id_type);
+    let (account, component, nft_resource, payment_token) = (
+        sale.account,
+        sale.component,
+        sale.nft_resource,
+        sale.payment_token,
+    );
+    let (_, _, partner) = sale.ledger.new_allocated_account();
+
+    let gifted = held_ids(&mut sale, component)[0].clone();
+    let selection = NftSelection::Ids(indexset!(gifted.clone()));
+    sale.as_owner(|builder| {
+        builder.call_method(
+            component,
+            "gift_from_vault",
+            manifest_args!(selection, partner),
+        )
+    })
+    .expect_commit_success();
+    assert_eq!(held_ids(&mut sale, partner), vec![gifted]);
+
+    sale.buy(dec!(10), 1).expect_commit_success();
+    assert_eq!(sale.balance(account, nft_resource), dec!(1));
+
+    sale.as_owner(|builder| builder.call_method(component, "cancel_sale", manifest_args!()))
+        .expect_commit_success();
+    let before = sale.balance(account, payment_token);
+    sale.as_public(|builder| {
+        builder
+            .withdraw_from_account(account, nft_resource, 1)
+            .take_all_from_worktop(nft_resource, "nfts")
+            .with_bucket("nfts", |builder, bucket| {
+                builder.call_method(component, "claim_refund", manifest_args!(bucket))
+            })
+    })
+    .expect_commit_success();
+    assert_eq!(sale.balance(account, payment_token), before + dec!(10));
+    assert_eq!(
+        sale.balance(component, nft_resource),
+        Decimal::from(STOCKED - 1)
+    );
+}
+
+#[test]
+fn integer_ids() {
+    exercise_id_taking_methods(NonFungibleIdType::Integer);
+}
+
+#[test]
+fn string_ids() {
+    exercise_id_taking_methods(NonFungibleIdType::String);
+}
+
+#[test]
+fn bytes_ids() {
+    exercise_id_taking_methods(NonFungibleIdType::Bytes);
+}
+
+#[test]
+fn ruid_ids() {
+    exercise_id_taking_methods(NonFungibleIdType::RUID);
+}
+
+#[test]
+fn id_ranges_are_refused_for_string_ids() {
+    let mut sale = sale_of(NonFungibleIdType::String);
+    let component = sale.component;
+    let rules = InventoryRules {
+        max_inventory: None,
+        reserved_ranges: vec![(1, 2)],
+    };
+
+    sale.as_owner(|builder| {
+        builder.call_method(
+            component,
+            "issue_listing_manager",
+            manifest_args!("Curator".to_owned(), vec![(1u64, 2u64)]),
+        )
+    })
+    .expect_commit_failure();
+    sale.as_owner(|builder| {
+        builder.call_method(component, "set_inventory_rules", manifest_args!(rules))
+    })
+    .expect_commit_failure();
+}
@@ -0,0 +1,148 @@
+mod common;
+
+use common::TestSale;
+use nft_sale_interface::{
+    ChangePolicy, DeliveryPolicy, HoldTerms, NFTSaleBuilder, SaleSnapshot, SnapshotChunk,
+};
+use scrypto_test::prelude::*;
+
+#[test]
+fn sale_can_be_stocked_at_instantiation() {
+    let mut ledger = LedgerSimulatorBuilder::new().build();
+    let (public_key, _private_key, account) = ledger.new_allocated_account();
+    let nft_resource = ledger.create_non_fungible_resource_advanced(
+        NonFungibleResourceRoles::default(),
+        account,
+        3,
+    );
+    let package_address = ledger.compile_and_publish(this_package!());
+    let config = NFTSaleBuilder::new(nft_resource, XRD)
+        .price(10)
+        .build()
+        .unwrap();
+
+    let manifest = ManifestBuilder::new()
+        .lock_fee_from_faucet()
+        .withdraw_from_account(account, nft_resource, 3)
+        .take_all_from_worktop(nft_resource, "nfts")
+        .with_bucket("nfts", |builder, bucket| {
+            builder.call_function(
+                package_address,
+                "NFTSale",
+                "instantiate_with_nfts",
+                manifest_args!(bucket, config),
+            )
+        })
+        .try_deposit_entire_worktop_or_abort(account, None)
+        .build();
+    let receipt = ledger.execute_manifest(
+        manifest,
+        vec![NonFungibleGlobalId::from_public_key(&public_key)],
+    );
+    let component = receipt.expect_commit_success().new_component_addresses()[0];
+
+    assert_eq!(
+        ledger.get_component_balance(component, nft_resource),
+        dec!(3)
+    );
+    assert_eq!(ledger.get_component_balance(account, nft_resource), dec!(0));
+}
+
+#[test]
+fn sales_can_be_cloned_for_another_collection() {
+    let mut sale = TestSale::new_with(1, 1, |builder| {
+        builder.price(10).change_policy(ChangePolicy::Tip)
+    });
+    let (account, component, package_address) =
+        (sale.account, sale.component, sale.package_address);
+    let now = sale.ledger.get_current_epoch();
+    let hold_terms = Some(HoldTerms {
+        duration_epochs: 5,
+        minimum_deposit: dec!(2),
+        expiry_fee: dec!(0),
+    });
+    sale.as_owner(|builder| {
+        builder
+            .call_method(component, "set_cashback_rate", manifest_args!(dec!("0.1")))
+            .call_method(
+                component,
+                "change_price",
+                manifest_args!(TestSale::NO_BADGE, dec!(12)),
+            )
+            .call_method(component, "set_hold_terms", manifest_args!(hold_terms))
+            .call_method(
+                component,
+                "set_large_purchase_threshold",
+                manifest_args!(Some(dec!(100))),
+            )
+            .call_method(
+                component,
+                "set_allowlist_root",
+                manifest_args!(Some(hash("allowlist"))),
+            )
+            .call_method(
+                component,
+                "set_donation_list",
+                manifest_args!(vec![(account, 1u32)]),
+            )
+            .call_method(
+                component,
+                "set_delivery_policy",
+                manifest_args!(DeliveryPolicy::Restock),
+            )
+            .call_method(component, "set_deferred_delivery", manifest_args!(true))
+            .call_method(
+                component,
+                "set_purchase_cooldown",
+                manifest_args!(Some(5u64)),
+            )
+            .call_method(
+                component,
+                "set_pass_window",
+                manifest_args!(now, now.after(10).unwrap()),
+            )
+    })
+    .expect_commit_success();
+    let next_collection = sale.ledger.create_non_fungible_resource_advanced(
+        NonFungibleResourceRoles::default(),
+        account,
+        1,
+    );
+
+    let receipt = sale.as_public(|builder| {
+        builder.call_function(
+            package_address,
+            "NFTSale",
+            "clone_sale",
+            manifest_args!(component, next_collection),
+        )
+    });
+    let clone = receipt.expect_commit_success().new_component_addresses()[0];
+
+    let mut summary = |component: ComponentAddress| -> SaleSnapshot {
+        let (summary, _): (SnapshotChunk, Option<u64>) = sale
+            .as_public(|builder| {
+                builder.call_method(component, "export_state_snapshot", manifest_args!(0u64))
+            })
+            .expect_commit_success()
+            .output(1);
+        let SnapshotChunk::Summary(summary) = summary else {
+            panic!("the first chunk is the summary");
+        };
+        *summary
+    };
+    let (source, clone) = (summary(component), summary(clone));
+    assert_eq!(clone.nft_resource_address, next_collection);
+    assert_eq!(clone.price, dec!(12));
+    assert_eq!(clone.change_policy, ChangePolicy::Tip);
+    assert_eq!(clone.inventory, dec!(0));
+
+    // Apart from the collection, its stock and its history, the clone is set up like the source
+    let expected = SaleSnapshot {
+        nft_resource_address: next_collection,
+        inventory: dec!(0),
+        audit_log_length: clone.audit_log_length,
+        ..source
+    };
+    assert_eq!(clone, expected);
+}
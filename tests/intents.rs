@@ -0,0 +1,106 @@
+mod common;
+
+use common::TestSale;
+use scrypto_test::prelude::*;
+
+fn buy_as(sale: &mut TestSale, number_of_nfts: u16) -> TransactionReceipt {
+    let (account, component, payment_token) = (sale.account, sale.component, sale.payment_token);
+    sale.as_public(|builder| {
+        builder
+            .withdraw_from_account(account, payment_token, dec!(10) * number_of_nfts)
+            .take_all_from_worktop(payment_token, "payment")
+            .with_bucket("payment", |builder, bucket| {
+                builder.call_method(
+                    component,
+                    "buy_as",
+                    manifest_args!(bucket, number_of_nfts, account),
+                )
+            })
+    })
+}
+
+fn open_deferred(sale: &mut TestSale) {
+    let component = sale.component;
+    sale.as_owner(|builder| {
+        builder
+            .call_method(component, "set_deferred_delivery", manifest_args!(true))
+            .call_method(component, "start_sale", manifest_args!(TestSale::NO_BADGE))
+    })
+    .expect_commit_success();
+}
+
+#[test]
+fn deferred_purchases_are_delivered_in_order_on_fulfilment() {
+    let mut sale = TestSale::new(5, 5, dec!(10));
+    let (account, component, nft_resource) = (sale.account, sale.component, sale.nft_resource);
+    open_deferred(&mut sale);
+
+    buy_as(&mut sale, 1).expect_commit_success();
+    buy_as(&mut sale, 1).expect_commit_success();
+    assert_eq!(sale.balance(account, nft_resource), dec!(0));
+
+    let receipt = sale.as_owner(|builder| {
+        builder.call_method(
+            component,
+            "fulfil",
+            manifest_args!(TestSale::NO_BADGE, 1u32),
+        )
+    });
+    let remaining: u64 = receipt.expect_commit_success().output(1);
+    assert_eq!(remaining, 1);
+    assert_eq!(sale.balance(account, nft_resource), dec!(1));
+
+    let receipt = sale.as_owner(|builder| {
+        builder.call_method(
+            component,
+            "fulfil",
+            manifest_args!(TestSale::NO_BADGE, 10u32),
+        )
+    });
+    let commit = receipt.expect_commit_success();
+    let remaining: u64 = commit.output(1);
+    assert_eq!(remaining, 0);
+    assert_eq!(sale.balance(account, nft_resource), dec!(2));
+    assert!(commit
+        .application_events
+        .iter()
+        .any(|(event_type, _)| event_type.1 == "IntentFulfilledEvent"));
+}
+
+#[test]
+fn anonymous_purchases_are_refused_while_deferred() {
+    let mut sale = TestSale::new(5, 5, dec!(10));
+    let component = sale.component;
+    open_deferred(&mut sale);
+
+    sale.buy(dec!(10), 1).expect_commit_failure();
+    sale.as_public(|builder| {
+        builder.call_method(
+            component,
+            "fulfil",
+            manifest_args!(TestSale::NO_BADGE, 1u32),
+        )
+    })
+    .expect_auth_failure();
+}
+
+#[test]
+fn fulfilment_batches_are_bounded_by_the_nfts_they_move() {
+    let mut sale = TestSale::new_with(60, 60, |builder| builder.price(10).max_per_tx(30));
+    let (account, component, nft_resource) = (sale.account, sale.component, sale.nft_resource);
+    open_deferred(&mut sale);
+    buy_as(&mut sale, 30).expect_commit_success();
+    buy_as(&mut sale, 30).expect_commit_success();
+
+    // Both intents together would move more NFTs than a call may
+    let receipt = sale.as_owner(|builder| {
+        builder.call_method(
+            component,
+            "fulfil",
+            manifest_args!(TestSale::NO_BADGE, u32::MAX),
+        )
+    });
+    let remaining: u64 = receipt.expect_commit_success().output(1);
+    assert_eq!(remaining, 1);
+    assert_eq!(sale.balance(account, nft_resource), dec!(30));
+}
@@ -0,0 +1,298 @@
+mod common;
+
+use common::TestSale;
+use nft_sale_interface::LimitTier;
+use scrypto_test::prelude::*;
+
+const MINTED: usize = 80;
+const STOCKED: u64 = 50;
+const MAX_PER_TX: u16 = 10;
+const OPERATIONS_PER_RUN: usize = 40;
+
+// Small deterministic generator so failing sequences can be replayed from their seed
+struct XorShift(u64);
+
+impl XorShift {
+    fn next(&mut self) -> u64 {
+        self.0 ^= self.0 << 13;
+        self.0 ^= self.0 >> 7;
+        self.0 ^= self.0 << 17;
+        self.0
+    }
+
+    fn below(&mut self, bound: u64) -> u64 {
+        self.next() % bound
+    }
+}
+
+#[derive(Debug)]
+enum Operation {
+    Buy {
+        number_of_nfts: u16,
+        payment: Decimal,
+    },
+    ChangePrice(Decimal),
+    AddNfts(u64),
+    WithdrawProfits,
+}
+
+impl Operation {
+    fn random(rng: &mut XorShift, price: Decimal) -> Self {
+        match rng.below(10) {
+            0..=4 => {
+                // Occasionally go over the per-transaction cap
+                let number_of_nfts = rng.below(MAX_PER_TX as u64 + 3) as u16;
+                // Pay short, exact or with change
+                let offset = Decimal::from(rng.below(5)) - dec!(2);
+                let payment = (price * number_of_nfts + offset).max(Decimal::zero());
+                Operation::Buy {
+                    number_of_nfts,
+                    payment,
+                }
+            }
+            5 | 6 => {
+                // Roughly one in eight price changes is invalid
+                let price = Decimal::from(rng.below(16)) - dec!(2);
+                Operation::ChangePrice(price)
+            }
+            7 | 8 => Operation::AddNfts(rng.below(8) + 1),
+            _ => Operation::WithdrawProfits,
+        }
+    }
+}
+
+// What the ledger should look like if `buy` and friends do exactly what they promise
+struct Model {
+    price: Decimal,
+    inventory: u64,
+    reserve: u64,
+    revenue: Decimal,
+}
+
+impl Model {
+    fn apply(&mut self, operation: &Operation) -> bool {
+        match *operation {
+            Operation::Buy {
+                number_of_nfts,
+                payment,
+            } => {
+                let cost = self.price * number_of_nfts;
+                let allowed = number_of_nfts <= MAX_PER_TX
+                    && u64::from(number_of_nfts) <= self.inventory
+                    && payment >= cost;
+                if allowed {
+                    self.inventory -= u64::from(number_of_nfts);
+                    self.revenue += cost;
+                }
+                allowed
+            }
+            Operation::ChangePrice(price) => {
+                let allowed = price >= Decimal::zero();
+                if allowed {
+                    self.price = price;
+                }
+                allowed
+            }
+            Operation::AddNfts(count) => {
+                let allowed = count <= self.reserve;
+                if allowed {
+                    self.reserve -= count;
+                    self.inventory += count;
+                }
+                allowed
+            }
+            // Revenue from sales is held for refunds as long as the sale runs
+            Operation::WithdrawProfits => false,
+        }
+    }
+}
+
+fn run(seed: u64) {
+    let initial_price = dec!(5);
+    let mut sale = TestSale::new(MINTED, STOCKED, initial_price);
+    sale.start_sale().expect_commit_success();
+
+    let initial_tokens = sale.balance(sale.account, sale.payment_token);
+    let mut rng = XorShift(seed);
+    let mut model = Model {
+        price: initial_price,
+        inventory: STOCKED,
+        reserve: MINTED as u64 - STOCKED,
+        revenue: Decimal::zero(),
+    };
+
+    for step in 0..OPERATIONS_PER_RUN {
+        let operation = Operation::random(&mut rng, model.price);
+        let receipt = match operation {
+            Operation::Buy {
+                number_of_nfts,
+                payment,
+            } => sale.buy(payment, number_of_nfts),
+            Operation::ChangePrice(price) => sale.change_price(price),
+            Operation::AddNfts(count) => sale.add_nfts(count),
+            Operation::WithdrawProfits => sale.withdraw_profits(),
+        };
+        let expected = model.apply(&operation);
+        assert_eq!(
+            receipt.is_commit_success(),
+            expected,
+            "seed {} step {}: {:?} had an unexpected outcome",
+            seed,
+            step,
+            operation
+        );
+
+        let (account, component) = (sale.account, sale.component);
+        let (nft_resource, payment_token) = (sale.nft_resource, sale.payment_token);
+
+        // Vault balances equal the revenue the model recorded
+        assert_eq!(
+            sale.balance(component, payment_token),
+            model.revenue,
+            "seed {} step {}",
+            seed,
+            step
+        );
+        // Inventory matches and no NFT was created or lost along the way
+        assert_eq!(
+            sale.balance(component, nft_resource),
+            Decimal::from(model.inventory),
+            "seed {} step {}",
+            seed,
+            step
+        );
+        assert_eq!(
+            sale.balance(account, nft_resource) + Decimal::from(model.inventory),
+            Decimal::from(MINTED as u64),
+            "seed {} step {}",
+            seed,
+            step
+        );
+        // Buyers are only ever charged what ends up in the vault or was withdrawn back to the owner
+        assert_eq!(
+            sale.balance(account, payment_token) + model.revenue,
+            initial_tokens,
+            "seed {} step {}",
+            seed,
+            step
+        );
+        assert_eq!(sale.price(), model.price, "seed {} step {}", seed, step);
+    }
+}
+
+#[test]
+fn test_random_operation_sequences_keep_invariants() {
+    for seed in [0x5eed_0001u64, 0x5eed_0002, 0x5eed_0003, 0x5eed_0004] {
+        run(seed);
+    }
+}
+
+#[test]
+fn test_buy_never_exceeds_per_transaction_cap() {
+    let mut sale = TestSale::new(40, 30, dec!(1));
+    sale.start_sale().expect_commit_success();
+
+    for number_of_nfts in (MAX_PER_TX + 1)..(MAX_PER_TX + 5) {
+        sale.buy(dec!(100), number_of_nfts).expect_commit_failure();
+    }
+    sale.buy(dec!(100), MAX_PER_TX).expect_commit_success();
+
+    let (component, nft_resource) = (sale.component, sale.nft_resource);
+    assert_eq!(sale.balance(component, nft_resource), dec!(20));
+}
+
+#[test]
+fn test_cap_tightens_as_supply_dwindles() {
+    let mut sale = TestSale::new(30, 30, dec!(1));
+    let component = sale.component;
+    let tiers = vec![
+        LimitTier {
+            up_to_remaining: 20,
+            max_nfts_per_transaction: 5,
+        },
+        LimitTier {
+            up_to_remaining: 10,
+            max_nfts_per_transaction: 2,
+        },
+    ];
+    sale.as_owner(|builder| {
+        builder
+            .call_method(
+                component,
+                "set_limit_tiers",
+                manifest_args!(TestSale::NO_BADGE, tiers),
+            )
+            .call_method(component, "start_sale", manifest_args!(TestSale::NO_BADGE))
+    })
+    .expect_commit_success();
+
+    // 30 left: only the instantiation cap applies
+    sale.buy(dec!(100), MAX_PER_TX).expect_commit_success();
+    // 20 left
+    sale.buy(dec!(100), 6).expect_commit_failure();
+    sale.buy(dec!(100), 5).expect_commit_success();
+    sale.buy(dec!(100), 5).expect_commit_success();
+    // 10 left
+    sale.buy(dec!(100), 3).expect_commit_failure();
+    sale.buy(dec!(100), 2).expect_commit_success();
+}
+
+fn verify_invariants(sale: &mut TestSale) -> bool {
+    let component = sale.component;
+    let receipt = sale
+        .as_public(|builder| builder.call_method(component, "verify_invariants", manifest_args!()));
+    let commit = receipt.expect_commit_success();
+    let violations = commit
+        .application_events
+        .iter()
+        .filter(|(event_type, _)| event_type.1 == "InvariantViolatedEvent")
+        .count();
+    let holds: bool = commit.output(1);
+    assert_eq!(holds, violations == 0);
+    holds
+}
+
+#[test]
+fn invariants_hold_through_escrows_and_deferred_purchases() {
+    let mut sale = TestSale::new(5, 5, dec!(10));
+    let (account, component, payment_token) = (sale.account, sale.component, sale.payment_token);
+    assert!(verify_invariants(&mut sale));
+
+    sale.as_public(|builder| {
+        builder
+            .withdraw_from_account(account, payment_token, dec!(20))
+            .take_all_from_worktop(payment_token, "payment")
+            .with_bucket("payment", |builder, bucket| {
+                builder.call_method(component, "escrow", manifest_args!(bucket, 2u16))
+            })
+    })
+    .expect_commit_success();
+    assert!(verify_invariants(&mut sale));
+
+    sale.as_owner(|builder| {
+        builder
+            .call_method(component, "set_deferred_delivery", manifest_args!(true))
+            .call_method(component, "start_sale", manifest_args!(TestSale::NO_BADGE))
+            .call_method(
+                component,
+                "convert_escrows",
+                manifest_args!(TestSale::NO_BADGE, 10u32),
+            )
+    })
+    .expect_commit_success();
+    sale.as_public(|builder| {
+        builder
+            .withdraw_from_account(account, payment_token, dec!(10))
+            .take_all_from_worktop(payment_token, "payment")
+            .with_bucket("payment", |builder, bucket| {
+                builder.call_method(component, "buy_as", manifest_args!(bucket, 1u16, account))
+            })
+    })
+    .expect_commit_success();
+    assert!(verify_invariants(&mut sale));
+
+    // The treasury total follows withdrawals
+    sale.end_refund_guarantee();
+    sale.withdraw_profits().expect_commit_success();
+    assert!(verify_invariants(&mut sale));
+}
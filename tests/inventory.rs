@@ -0,0 +1,121 @@
+mod common;
+
+use common::TestSale;
+use nft_sale_interface::InventoryRules;
+use scrypto_test::prelude::*;
+
+fn set_inventory_rules(sale: &mut TestSale, rules: InventoryRules) {
+    let component = sale.component;
+    sale.as_owner(|builder| {
+        builder.call_method(component, "set_inventory_rules", manifest_args!(rules))
+    })
+    .expect_commit_success();
+}
+
+fn add_ids(sale: &mut TestSale, ids: &[u64]) -> TransactionReceipt {
+    let (account, component, nft_resource) = (sale.account, sale.component, sale.nft_resource);
+    let ids: Vec<NonFungibleLocalId> = ids
+        .iter()
+        .map(|id| NonFungibleLocalId::integer(*id))
+        .collect();
+    sale.as_owner(|builder| {
+        builder
+            .withdraw_non_fungibles_from_account(account, nft_resource, ids)
+            .take_all_from_worktop(nft_resource, "nfts")
+            .with_bucket("nfts", |builder, bucket| {
+                builder.call_method(
+                    component,
+                    "add_nfts_to_vault",
+                    manifest_args!(TestSale::NO_BADGE, bucket),
+                )
+            })
+    })
+}
+
+#[test]
+fn deposits_respect_the_inventory_cap() {
+    let mut sale = TestSale::new(5, 0, dec!(10));
+    set_inventory_rules(
+        &mut sale,
+        InventoryRules {
+            max_inventory: Some(3),
+            reserved_ranges: vec![],
+        },
+    );
+
+    add_ids(&mut sale, &[1, 2]).expect_commit_success();
+    add_ids(&mut sale, &[3, 4]).expect_commit_failure();
+    add_ids(&mut sale, &[3]).expect_commit_success();
+
+    // Delisting an NFT does not make room for another
+    let component = sale.component;
+    sale.as_owner(|builder| {
+        builder.call_method(
+            component,
+            "delist",
+            manifest_args!(
+                TestSale::NO_BADGE,
+                indexset!(NonFungibleLocalId::integer(1))
+            ),
+        )
+    })
+    .expect_commit_success();
+    add_ids(&mut sale, &[4]).expect_commit_failure();
+}
+
+#[test]
+fn reserved_ids_cannot_be_deposited() {
+    let mut sale = TestSale::new(5, 0, dec!(10));
+    set_inventory_rules(
+        &mut sale,
+        InventoryRules {
+            max_inventory: None,
+            reserved_ranges: vec![(4, 5)],
+        },
+    );
+
+    add_ids(&mut sale, &[3, 4]).expect_commit_failure();
+    let receipt = add_ids(&mut sale, &[1, 2, 3]);
+    let commit = receipt.expect_commit_success();
+    assert!(commit
+        .application_events
+        .iter()
+        .any(|(event_type, _)| event_type.1 == "InventoryChangedEvent"));
+}
+
+#[test]
+fn delisted_nfts_stay_in_the_component_but_are_not_sold() {
+    let mut sale = TestSale::new(3, 0, dec!(10));
+    add_ids(&mut sale, &[1, 2, 3]).expect_commit_success();
+    let (account, component, nft_resource) = (sale.account, sale.component, sale.nft_resource);
+    let delisted = indexset!(
+        NonFungibleLocalId::integer(1),
+        NonFungibleLocalId::integer(2)
+    );
+    sale.as_owner(|builder| {
+        builder
+            .call_method(
+                component,
+                "delist",
+                manifest_args!(TestSale::NO_BADGE, delisted),
+            )
+            .call_method(component, "start_sale", manifest_args!(TestSale::NO_BADGE))
+    })
+    .expect_commit_success();
+
+    sale.buy(dec!(20), 2).expect_commit_failure();
+    sale.buy(dec!(10), 1).expect_commit_success();
+    assert_eq!(sale.balance(component, nft_resource), dec!(2));
+
+    let relisted = indexset!(NonFungibleLocalId::integer(1));
+    sale.as_owner(|builder| {
+        builder.call_method(
+            component,
+            "relist",
+            manifest_args!(TestSale::NO_BADGE, relisted),
+        )
+    })
+    .expect_commit_success();
+    sale.buy(dec!(10), 1).expect_commit_success();
+    assert_eq!(sale.balance(account, nft_resource), dec!(2));
+}
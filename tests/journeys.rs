@@ -0,0 +1,128 @@
+// Whole launches from deployment to payout, scripted the way a launchpad would drive them. Each
+// step checks what the integrator would show their users at that point
+mod common;
+
+use common::TestSale;
+use nft_sale_interface::{allowlist_proof, allowlist_root};
+use scrypto_test::prelude::*;
+
+fn presale_buy(
+    sale: &mut TestSale,
+    members: &[ComponentAddress],
+    count: u16,
+) -> TransactionReceipt {
+    let (account, component, payment_token) = (sale.account, sale.component, sale.payment_token);
+    let proof = allowlist_proof(members, account).unwrap();
+    let payment = sale.price() * count;
+    sale.as_public(|builder| {
+        builder
+            .withdraw_from_account(account, payment_token, payment)
+            .take_all_from_worktop(payment_token, "payment")
+            .with_bucket("payment", |builder, bucket| {
+                builder.call_method(
+                    component,
+                    "buy_allowlisted",
+                    manifest_args!(bucket, count, account, proof),
+                )
+            })
+    })
+}
+
+fn open_presale(sale: &mut TestSale, members: &[ComponentAddress]) {
+    let component = sale.component;
+    let root = allowlist_root(members);
+    sale.as_owner(|builder| {
+        builder
+            .call_method(component, "set_allowlist_root", manifest_args!(Some(root)))
+            .call_method(component, "start_sale", manifest_args!(TestSale::NO_BADGE))
+    })
+    .expect_commit_success();
+}
+
+fn open_public_sale(sale: &mut TestSale) {
+    let component = sale.component;
+    sale.as_owner(|builder| {
+        builder.call_method(
+            component,
+            "set_allowlist_root",
+            manifest_args!(None::<Hash>),
+        )
+    })
+    .expect_commit_success();
+}
+
+fn is_sold(sale: &mut TestSale) -> bool {
+    let component = sale.component;
+    sale.as_public(|builder| builder.call_method(component, "is_sold", manifest_args!()))
+        .expect_commit_success()
+        .output(1)
+}
+
+#[test]
+fn launch_sells_out_and_pays_the_creator() {
+    // Deploy with the whole collection stocked
+    let mut sale = TestSale::new(6, 6, dec!(10));
+    let (account, component, nft_resource, payment_token) = (
+        sale.account,
+        sale.component,
+        sale.nft_resource,
+        sale.payment_token,
+    );
+    let before = sale.balance(account, payment_token);
+
+    // Allowlisted presale: the public cannot buy yet
+    let members = vec![FAUCET, account];
+    open_presale(&mut sale, &members);
+    sale.buy(dec!(10), 1).expect_commit_failure();
+    presale_buy(&mut sale, &members, 2).expect_commit_success();
+
+    // Public sale at a new price until the vault is empty
+    open_public_sale(&mut sale);
+    sale.change_price(dec!(12)).expect_commit_success();
+    sale.buy(dec!(48), 4).expect_commit_success();
+    assert!(is_sold(&mut sale));
+    sale.buy(dec!(12), 1).expect_commit_failure();
+    assert_eq!(sale.balance(account, nft_resource), dec!(6));
+
+    // The creator takes the revenue once the refund guarantee is over and closes the books for
+    // good
+    sale.end_refund_guarantee();
+    sale.withdraw_profits().expect_commit_success();
+    assert_eq!(sale.balance(account, payment_token), before);
+    assert_eq!(sale.balance(component, payment_token), dec!(0));
+    sale.as_owner(|builder| builder.call_method(component, "finalize", manifest_args!()))
+        .expect_commit_success();
+}
+
+#[test]
+fn cancelled_launch_refunds_its_buyers() {
+    let mut sale = TestSale::new(6, 6, dec!(10));
+    let (account, component, nft_resource, payment_token) = (
+        sale.account,
+        sale.component,
+        sale.nft_resource,
+        sale.payment_token,
+    );
+
+    let members = vec![account];
+    open_presale(&mut sale, &members);
+    presale_buy(&mut sale, &members, 1).expect_commit_success();
+    open_public_sale(&mut sale);
+    sale.buy(dec!(20), 2).expect_commit_success();
+
+    // The creator calls the launch off and buyers return their NFTs for what they paid
+    sale.as_owner(|builder| builder.call_method(component, "cancel_sale", manifest_args!()))
+        .expect_commit_success();
+    let before = sale.balance(account, payment_token);
+    sale.as_public(|builder| {
+        builder
+            .withdraw_from_account(account, nft_resource, 3)
+            .take_all_from_worktop(nft_resource, "nfts")
+            .with_bucket("nfts", |builder, bucket| {
+                builder.call_method(component, "claim_refund", manifest_args!(bucket))
+            })
+    })
+    .expect_commit_success();
+    assert_eq!(sale.balance(account, payment_token), before + dec!(30));
+    assert_eq!(sale.balance(component, nft_resource), dec!(6));
+}
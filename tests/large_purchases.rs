@@ -0,0 +1,31 @@
+mod common;
+
+use common::TestSale;
+use scrypto_test::prelude::*;
+
+fn is_flagged(receipt: &TransactionReceipt) -> bool {
+    receipt
+        .expect_commit_success()
+        .application_events
+        .iter()
+        .any(|(event_type, _)| event_type.1 == "LargePurchaseEvent")
+}
+
+#[test]
+fn purchases_above_the_threshold_are_flagged() {
+    let mut sale = TestSale::new(10, 10, dec!(10));
+    let component = sale.component;
+    sale.as_owner(|builder| {
+        builder
+            .call_method(
+                component,
+                "set_large_purchase_threshold",
+                manifest_args!(Some(dec!(50))),
+            )
+            .call_method(component, "start_sale", manifest_args!(TestSale::NO_BADGE))
+    })
+    .expect_commit_success();
+
+    assert!(!is_flagged(&sale.buy(dec!(40), 4)));
+    assert!(is_flagged(&sale.buy(dec!(50), 5)));
+}
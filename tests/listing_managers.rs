@@ -0,0 +1,81 @@
+mod common;
+
+use common::TestSale;
+use nft_sale_interface::AuditEntry;
+use scrypto_test::prelude::*;
+
+fn manage(sale: &mut TestSale, method: &str, ids: &[u64]) -> TransactionReceipt {
+    let (account, component, listing_manager) =
+        (sale.account, sale.component, sale.listing_manager);
+    let nft_ids: IndexSet<NonFungibleLocalId> = ids
+        .iter()
+        .copied()
+        .map(NonFungibleLocalId::integer)
+        .collect();
+    sale.as_public(|builder| {
+        builder
+            .create_proof_from_account_of_amount(account, listing_manager, dec!(1))
+            .pop_from_auth_zone("badge")
+            .with_name_lookup(|builder, lookup| {
+                let badge = lookup.proof("badge");
+                builder.call_method(component, method, manifest_args!(badge, nft_ids))
+            })
+    })
+}
+
+#[test]
+fn listing_managers_only_manage_their_ranges() {
+    let mut sale = TestSale::new(5, 5, dec!(10));
+    let component = sale.component;
+    sale.as_owner(|builder| {
+        builder.call_method(
+            component,
+            "issue_listing_manager",
+            manifest_args!("Curator", vec![(1u64, 3u64)]),
+        )
+    })
+    .expect_commit_success();
+
+    manage(&mut sale, "manage_delist", &[1, 2]).expect_commit_success();
+    manage(&mut sale, "manage_delist", &[3, 4]).expect_commit_failure();
+    manage(&mut sale, "manage_relist", &[2]).expect_commit_success();
+
+    // Delisting is still reserved to admins for everyone else
+    sale.as_public(|builder| {
+        builder.call_method(
+            component,
+            "delist",
+            manifest_args!(
+                TestSale::NO_BADGE,
+                indexset!(NonFungibleLocalId::integer(3))
+            ),
+        )
+    })
+    .expect_auth_failure();
+}
+
+#[test]
+fn managed_changes_are_logged_under_the_manager_badge() {
+    let mut sale = TestSale::new(5, 5, dec!(10));
+    let (component, listing_manager) = (sale.component, sale.listing_manager);
+    sale.as_owner(|builder| {
+        builder.call_method(
+            component,
+            "issue_listing_manager",
+            manifest_args!("Curator", vec![(1u64, 3u64)]),
+        )
+    })
+    .expect_commit_success();
+    manage(&mut sale, "manage_delist", &[1]).expect_commit_success();
+
+    let receipt = sale.as_public(|builder| {
+        builder.call_method(component, "audit_log", manifest_args!(2u64, 1u32))
+    });
+    let (_, entries): (u64, Vec<AuditEntry>) = receipt.expect_commit_success().output(1);
+    assert_eq!(entries[0].action, "delist");
+    assert_eq!(entries[0].authority.role, "listing manager");
+    let Some(ResourceOrNonFungible::NonFungible(badge)) = &entries[0].authority.badge else {
+        panic!("The entry names no manager badge");
+    };
+    assert_eq!(badge.resource_address(), listing_manager);
+}
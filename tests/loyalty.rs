@@ -0,0 +1,54 @@
+mod common;
+
+use common::TestSale;
+use nft_sale_interface::LoyaltyDiscount;
+use scrypto_test::prelude::*;
+
+fn buy_returning(
+    sale: &mut TestSale,
+    receipt: ResourceAddress,
+    payment: Decimal,
+) -> TransactionReceipt {
+    let (account, component, payment_token) = (sale.account, sale.component, sale.payment_token);
+    sale.as_public(|builder| {
+        builder
+            .create_proof_from_account_of_amount(account, receipt, dec!(1))
+            .pop_from_auth_zone("receipt")
+            .withdraw_from_account(account, payment_token, payment)
+            .take_all_from_worktop(payment_token, "payment")
+            .with_name_lookup(|builder, lookup| {
+                let (payment, receipt) = (lookup.bucket("payment"), lookup.proof("receipt"));
+                builder.call_method(
+                    component,
+                    "buy_returning",
+                    manifest_args!(payment, 1u16, receipt),
+                )
+            })
+    })
+}
+
+#[test]
+fn returning_buyers_get_the_loyalty_discount() {
+    let mut sale = TestSale::new(2, 2, dec!(10));
+    let (account, component, payment_token) = (sale.account, sale.component, sale.payment_token);
+    let earlier_drop = sale.ledger.create_fungible_resource(dec!(1), 0, account);
+    sale.start_sale().expect_commit_success();
+    buy_returning(&mut sale, earlier_drop, dec!(8)).expect_commit_failure();
+
+    sale.as_owner(|builder| {
+        builder.call_method(
+            component,
+            "set_loyalty_discount",
+            manifest_args!(Some(LoyaltyDiscount {
+                honored: vec![earlier_drop],
+                discount: dec!("0.2"),
+            })),
+        )
+    })
+    .expect_commit_success();
+
+    buy_returning(&mut sale, payment_token, dec!(8)).expect_commit_failure();
+    let before = sale.balance(account, payment_token);
+    buy_returning(&mut sale, earlier_drop, dec!(8)).expect_commit_success();
+    assert_eq!(sale.balance(account, payment_token), before - dec!(8));
+}
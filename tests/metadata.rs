@@ -0,0 +1,86 @@
+mod common;
+
+use common::TestSale;
+use scrypto_test::prelude::*;
+
+#[test]
+fn owner_brands_the_sale_after_instantiation() {
+    let mut sale = TestSale::new(1, 1, dec!(10));
+    let (account, component) = (sale.account, sale.component);
+    sale.as_owner(|builder| {
+        builder
+            .call_method(component, "set_dapp_definition", manifest_args!(account))
+            .call_method(
+                component,
+                "set_info_url",
+                manifest_args!("https://example.com/collection".to_owned()),
+            )
+            .call_method(
+                component,
+                "set_social_urls",
+                manifest_args!(vec!["https://x.com/example".to_owned()]),
+            )
+    })
+    .expect_commit_success();
+
+    assert_eq!(
+        sale.ledger
+            .get_metadata(component.into(), "dapp_definition"),
+        Some(MetadataValue::GlobalAddress(account.into()))
+    );
+    assert_eq!(
+        sale.ledger.get_metadata(component.into(), "info_url"),
+        Some(MetadataValue::Url(UncheckedUrl::of(
+            "https://example.com/collection"
+        )))
+    );
+    assert_eq!(
+        sale.ledger.get_metadata(component.into(), "social_urls"),
+        Some(MetadataValue::UrlArray(vec![UncheckedUrl::of(
+            "https://x.com/example"
+        )]))
+    );
+}
+
+#[test]
+fn branding_is_owner_only() {
+    let mut sale = TestSale::new(1, 1, dec!(10));
+    let component = sale.component;
+    sale.as_public(|builder| {
+        builder.call_method(
+            component,
+            "set_info_url",
+            manifest_args!("https://example.com/scam".to_owned()),
+        )
+    })
+    .expect_auth_failure();
+}
+
+#[test]
+fn price_changes_are_published_in_metadata() {
+    let mut sale = TestSale::new(1, 1, dec!(10));
+    let (component, payment_token) = (sale.component, sale.payment_token);
+    assert_eq!(
+        sale.ledger.get_metadata(component.into(), "price"),
+        Some(MetadataValue::Decimal(dec!(10)))
+    );
+    assert_eq!(
+        sale.ledger.get_metadata(component.into(), "payment_token"),
+        Some(MetadataValue::GlobalAddress(payment_token.into()))
+    );
+
+    sale.change_price(dec!(12)).expect_commit_success();
+    assert_eq!(
+        sale.ledger.get_metadata(component.into(), "price"),
+        Some(MetadataValue::Decimal(dec!(12)))
+    );
+}
+
+#[test]
+fn published_metadata_is_only_written_by_the_sale_itself() {
+    // Not even the owner can publish a price the sale does not charge
+    let mut sale = TestSale::new(1, 1, dec!(10));
+    let component = sale.component;
+    sale.as_owner(|builder| builder.set_metadata(component, "price", dec!(1)))
+        .expect_auth_failure();
+}
@@ -0,0 +1,43 @@
+mod common;
+
+use common::TestSale;
+use scrypto_test::prelude::*;
+
+fn buy_vouchers(sale: &mut TestSale, amount: Decimal) -> TransactionReceipt {
+    let (account, component, payment_token) = (sale.account, sale.component, sale.payment_token);
+    sale.as_public(|builder| {
+        builder
+            .withdraw_from_account(account, payment_token, amount)
+            .take_all_from_worktop(payment_token, "payment")
+            .with_bucket("payment", |builder, bucket| {
+                builder.call_method(component, "buy_vouchers", manifest_args!(bucket))
+            })
+    })
+}
+
+#[test]
+fn dust_payments_are_turned_away() {
+    let mut sale = TestSale::new(5, 5, dec!(10));
+    let component = sale.component;
+    sale.as_owner(|builder| {
+        builder
+            .call_method(component, "set_minimum_payment", manifest_args!(dec!(1)))
+            .call_method(component, "start_sale", manifest_args!(TestSale::NO_BADGE))
+    })
+    .expect_commit_success();
+
+    sale.buy(dec!("0.001"), 0).expect_commit_failure();
+    buy_vouchers(&mut sale, dec!("0.001")).expect_commit_failure();
+    sale.buy(dec!(10), 1).expect_commit_success();
+    buy_vouchers(&mut sale, dec!(1)).expect_commit_success();
+}
+
+#[test]
+fn minimum_payments_cannot_be_negative() {
+    let mut sale = TestSale::new(5, 5, dec!(10));
+    let component = sale.component;
+    sale.as_owner(|builder| {
+        builder.call_method(component, "set_minimum_payment", manifest_args!(dec!(-1)))
+    })
+    .expect_commit_failure();
+}
@@ -0,0 +1,150 @@
+mod common;
+
+use common::TestSale;
+use nft_sale_interface::{NftSelection, PhaseInfo, SaleError, SalePhase};
+use scrypto_test::prelude::*;
+
+// A started sale of 5 NFTs at 10 with 2 passes for 2 NFTs each at 6, redeemable for 10 epochs
+fn sale_with_passes() -> (TestSale, Epoch) {
+    let mut sale = TestSale::new(5, 5, dec!(10));
+    let component = sale.component;
+    let now = sale.ledger.get_current_epoch();
+    let closes_at = now.after(10).unwrap();
+    sale.as_owner(|builder| {
+        builder
+            .call_method(
+                component,
+                "mint_passes",
+                manifest_args!(2u32, 2u16, dec!(6)),
+            )
+            .call_method(component, "set_pass_window", manifest_args!(now, closes_at))
+            .call_method(component, "start_sale", manifest_args!(TestSale::NO_BADGE))
+    })
+    .expect_commit_success();
+    (sale, closes_at)
+}
+
+fn redeem_passes(sale: &mut TestSale, passes: u64, payment: Decimal) -> TransactionReceipt {
+    let (account, component, mint_pass, payment_token) = (
+        sale.account,
+        sale.component,
+        sale.mint_pass,
+        sale.payment_token,
+    );
+    sale.as_public(|builder| {
+        builder
+            .withdraw_from_account(account, mint_pass, passes)
+            .take_all_from_worktop(mint_pass, "passes")
+            .withdraw_from_account(account, payment_token, payment)
+            .take_all_from_worktop(payment_token, "payment")
+            .with_name_lookup(|builder, lookup| {
+                let (passes, payment) = (lookup.bucket("passes"), lookup.bucket("payment"));
+                builder.call_method(component, "redeem_passes", manifest_args!(passes, payment))
+            })
+    })
+}
+
+#[test]
+fn passes_buy_their_allocation_at_the_locked_price() {
+    let (mut sale, _) = sale_with_passes();
+    let (account, payment_token, nft_resource, mint_pass) = (
+        sale.account,
+        sale.payment_token,
+        sale.nft_resource,
+        sale.mint_pass,
+    );
+    let before = sale.balance(account, payment_token);
+
+    redeem_passes(&mut sale, 1, dec!(12)).expect_commit_success();
+
+    assert_eq!(sale.balance(account, nft_resource), dec!(2));
+    assert_eq!(sale.balance(account, mint_pass), dec!(1));
+    assert_eq!(sale.balance(account, payment_token), before - dec!(12));
+}
+
+#[test]
+fn public_buyers_cannot_take_reserved_nfts_during_the_window() {
+    let (mut sale, closes_at) = sale_with_passes();
+
+    // 4 of the 5 NFTs are reserved for the two passes
+    sale.buy(dec!(20), 2).expect_commit_failure();
+    sale.buy(dec!(10), 1).expect_commit_success();
+
+    sale.ledger.set_current_epoch(closes_at);
+    redeem_passes(&mut sale, 1, dec!(12)).expect_commit_failure();
+    sale.buy(dec!(40), 4).expect_commit_success();
+}
+
+#[test]
+fn phase_info_counts_down_the_priority_window() {
+    let (mut sale, closes_at) = sale_with_passes();
+    let component = sale.component;
+    let phase_info = |sale: &mut TestSale| -> PhaseInfo {
+        sale.as_public(|builder| {
+            builder.call_method(component, "current_phase_info", manifest_args!())
+        })
+        .expect_commit_success()
+        .output(1)
+    };
+
+    let info = phase_info(&mut sale);
+    assert_eq!(info.phase, SalePhase::Priority);
+    assert_eq!(info.remaining, dec!(4));
+    assert_eq!(info.epochs_until_next_phase, Some(10));
+
+    sale.ledger.set_current_epoch(closes_at);
+    let info = phase_info(&mut sale);
+    assert_eq!(info.phase, SalePhase::Public);
+    assert_eq!(info.price, dec!(10));
+    assert_eq!(info.remaining, dec!(5));
+    assert_eq!(info.epochs_until_next_phase, None);
+}
+
+#[test]
+fn owner_takes_from_the_vault_respect_the_reservation() {
+    let (mut sale, closes_at) = sale_with_passes();
+    let component = sale.component;
+    let (_, _, partner) = sale.ledger.new_allocated_account();
+    let gift = |sale: &mut TestSale, count: u32| {
+        sale.as_owner(|builder| {
+            builder.call_method(
+                component,
+                "gift_from_vault",
+                manifest_args!(NftSelection::Count(count), partner),
+            )
+        })
+    };
+
+    gift(&mut sale, 2).expect_commit_failure();
+    gift(&mut sale, 1).expect_commit_success();
+
+    // Once the window closes the stale reservation no longer counts towards new passes
+    sale.ledger.set_current_epoch(closes_at);
+    let reopens_at = closes_at.after(10).unwrap();
+    sale.as_owner(|builder| {
+        builder
+            .call_method(
+                component,
+                "set_pass_window",
+                manifest_args!(closes_at, reopens_at),
+            )
+            .call_method(
+                component,
+                "mint_passes",
+                manifest_args!(2u32, 2u16, dec!(6)),
+            )
+    })
+    .expect_commit_success();
+}
+
+#[test]
+fn passes_cannot_be_redeemed_after_a_cancellation() {
+    let (mut sale, _) = sale_with_passes();
+    let component = sale.component;
+    sale.as_owner(|builder| builder.call_method(component, "cancel_sale", manifest_args!()))
+        .expect_commit_success();
+
+    redeem_passes(&mut sale, 1, dec!(12)).expect_specific_failure(|error| {
+        SaleError::from_message(&format!("{:?}", error)) == Some(SaleError::SaleCancelled)
+    });
+}
@@ -0,0 +1,91 @@
+mod common;
+
+use common::TestSale;
+use scrypto_test::prelude::*;
+
+#[derive(ScryptoSbor, ManifestSbor)]
+struct PhygitalItem {
+    redeemed: bool,
+}
+
+impl NonFungibleData for PhygitalItem {
+    const MUTABLE_FIELDS: &'static [&'static str] = &["redeemed"];
+}
+
+// A sale of one phygital NFT whose data anyone may update, bought by the test account
+fn sold_phygital_nft() -> TestSale {
+    let mut ledger = LedgerSimulatorBuilder::new().build();
+    let (public_key, _private_key, account) = ledger.new_allocated_account();
+    let manifest = ManifestBuilder::new()
+        .lock_fee_from_faucet()
+        .create_non_fungible_resource(
+            OwnerRole::None,
+            NonFungibleIdType::Integer,
+            false,
+            NonFungibleResourceRoles {
+                non_fungible_data_update_roles: non_fungible_data_update_roles! {
+                    non_fungible_data_updater => rule!(allow_all);
+                    non_fungible_data_updater_updater => rule!(deny_all);
+                },
+                ..Default::default()
+            },
+            metadata!(),
+            Some(indexmap!(NonFungibleLocalId::integer(1) => PhygitalItem { redeemed: false })),
+        )
+        .try_deposit_entire_worktop_or_abort(account, None)
+        .build();
+    let receipt = ledger.execute_manifest(
+        manifest,
+        vec![NonFungibleGlobalId::from_public_key(&public_key)],
+    );
+    let nft_resource = receipt.expect_commit_success().new_resource_addresses()[0];
+
+    let mut sale = TestSale::deploy(ledger, public_key, account, nft_resource, |builder| {
+        builder.price(10)
+    });
+    sale.add_nfts(1).expect_commit_success();
+    sale.start_sale().expect_commit_success();
+    sale.buy(dec!(10), 1).expect_commit_success();
+    sale
+}
+
+fn redeem_physical(sale: &mut TestSale, shipping_hash: Hash) -> TransactionReceipt {
+    let (account, component, nft_resource) = (sale.account, sale.component, sale.nft_resource);
+    sale.as_public(|builder| {
+        builder
+            .create_proof_from_account_of_non_fungibles(
+                account,
+                nft_resource,
+                [NonFungibleLocalId::integer(1)],
+            )
+            .pop_from_auth_zone("nft")
+            .with_name_lookup(|builder, lookup| {
+                let nft = lookup.proof("nft");
+                builder.call_method(
+                    component,
+                    "redeem_physical",
+                    manifest_args!(nft, shipping_hash),
+                )
+            })
+    })
+}
+
+#[test]
+fn physical_items_are_redeemed_once() {
+    let mut sale = sold_phygital_nft();
+    let nft_resource = sale.nft_resource;
+
+    let commit = redeem_physical(&mut sale, hash("1 Ledger Lane"));
+    assert!(commit
+        .expect_commit_success()
+        .application_events
+        .iter()
+        .any(|(event_type, _)| event_type.1 == "PhysicalRedeemedEvent"));
+    let item: PhygitalItem = sale
+        .ledger
+        .get_non_fungible_data(nft_resource, NonFungibleLocalId::integer(1));
+    assert!(item.redeemed);
+
+    // The item behind an NFT ships once, whatever address is given the second time
+    redeem_physical(&mut sale, hash("2 Ledger Lane")).expect_commit_failure();
+}
@@ -0,0 +1,100 @@
+mod common;
+
+use common::TestSale;
+use nft_sale_interface::PriceSchedule;
+use scrypto_test::prelude::*;
+
+#[test]
+fn platform_receives_its_cut_of_every_sale() {
+    let mut ledger = LedgerSimulatorBuilder::new().build();
+    let (public_key, _private_key, account) = ledger.new_allocated_account();
+    let (_, _, platform) = ledger.new_allocated_account();
+    let nft_resource = ledger.create_non_fungible_resource_advanced(
+        NonFungibleResourceRoles::default(),
+        account,
+        3,
+    );
+    let mut sale = TestSale::deploy(ledger, public_key, account, nft_resource, |builder| {
+        builder.price(10).platform_fee(dec!("0.05"), platform)
+    });
+    sale.add_nfts(3).expect_commit_success();
+    sale.start_sale().expect_commit_success();
+
+    sale.buy(dec!(20), 2).expect_commit_success();
+
+    let (component, payment_token) = (sale.component, sale.payment_token);
+    assert_eq!(sale.balance(platform, payment_token), dec!(1));
+    assert_eq!(sale.balance(component, payment_token), dec!(19));
+}
+
+#[test]
+fn refused_fees_name_the_method_that_paid_them() {
+    let mut ledger = LedgerSimulatorBuilder::new().build();
+    let (public_key, _private_key, account) = ledger.new_allocated_account();
+    let (platform_key, _, platform) = ledger.new_allocated_account();
+    let manifest = ManifestBuilder::new()
+        .lock_fee_from_faucet()
+        .call_method(
+            platform,
+            "set_default_deposit_rule",
+            manifest_args!(DefaultDepositRule::Reject),
+        )
+        .build();
+    ledger
+        .execute_manifest(
+            manifest,
+            vec![NonFungibleGlobalId::from_public_key(&platform_key)],
+        )
+        .expect_commit_success();
+    let nft_resource = ledger.create_non_fungible_resource_advanced(
+        NonFungibleResourceRoles::default(),
+        account,
+        3,
+    );
+    let mut sale = TestSale::deploy(ledger, public_key, account, nft_resource, |builder| {
+        builder.price(10).platform_fee(dec!("0.05"), platform)
+    });
+    sale.add_nfts(3).expect_commit_success();
+    let (component, payment_token) = (sale.component, sale.payment_token);
+    let schedule = PriceSchedule {
+        start_price: dec!(10),
+        floor_price: dec!(10),
+        starts_at: sale.ledger.get_current_epoch(),
+        decline_per_epoch: dec!(0),
+    };
+    sale.as_owner(|builder| {
+        builder
+            .call_method(
+                component,
+                "schedule_listing",
+                manifest_args!(
+                    TestSale::NO_BADGE,
+                    NonFungibleLocalId::integer(1),
+                    Some(schedule)
+                ),
+            )
+            .call_method(component, "start_sale", manifest_args!(TestSale::NO_BADGE))
+    })
+    .expect_commit_success();
+    let refused_in = |method: &'static str| {
+        move |error: &RuntimeError| {
+            format!("{:?}", error).contains(&format!("[{}]: Account", method))
+        }
+    };
+
+    sale.buy(dec!(10), 1)
+        .expect_specific_failure(refused_in("Buy"));
+    sale.as_public(|builder| {
+        builder
+            .withdraw_from_account(account, payment_token, dec!(10))
+            .take_all_from_worktop(payment_token, "payment")
+            .with_bucket("payment", |builder, bucket| {
+                builder.call_method(
+                    component,
+                    "buy_specific",
+                    manifest_args!(bucket, NonFungibleLocalId::integer(1)),
+                )
+            })
+    })
+    .expect_specific_failure(refused_in("Buy Specific"));
+}
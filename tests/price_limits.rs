@@ -0,0 +1,44 @@
+mod common;
+
+use common::TestSale;
+use nft_sale_interface::PriceChangeLimit;
+use scrypto_test::prelude::*;
+
+#[test]
+fn price_changes_are_rate_limited_unless_overridden() {
+    let mut sale = TestSale::new(1, 1, dec!(10));
+    let component = sale.component;
+    let limit = Some(PriceChangeLimit {
+        min_interval_epochs: 10,
+        max_change: dec!("0.5"),
+    });
+    sale.as_owner(|builder| {
+        builder.call_method(component, "set_price_change_limit", manifest_args!(limit))
+    })
+    .expect_commit_success();
+
+    sale.change_price(dec!(12)).expect_commit_success();
+    sale.change_price(dec!(13)).expect_commit_failure();
+
+    let now = sale.ledger.get_current_epoch();
+    sale.ledger.set_current_epoch(now.after(10).unwrap());
+    sale.change_price(dec!(30)).expect_commit_failure();
+    sale.change_price(dec!(18)).expect_commit_success();
+    assert_eq!(sale.price(), dec!(18));
+
+    sale.as_owner(|builder| {
+        builder.call_method(component, "override_price", manifest_args!(dec!(100)))
+    })
+    .expect_commit_success();
+    assert_eq!(sale.price(), dec!(100));
+}
+
+#[test]
+fn only_the_owner_can_override_the_price() {
+    let mut sale = TestSale::new(1, 1, dec!(10));
+    let (component, admin_badge) = (sale.component, sale.admin_badge);
+    sale.with_badge(admin_badge, |builder| {
+        builder.call_method(component, "override_price", manifest_args!(dec!(1)))
+    })
+    .expect_auth_failure();
+}
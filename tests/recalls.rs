@@ -0,0 +1,109 @@
+mod common;
+
+use common::TestSale;
+use nft_sale_interface::NftSelection;
+use scrypto_test::prelude::*;
+
+// Epochs a scheduled recall waits before it can be executed
+const RECALL_TIMELOCK_EPOCHS: u64 = 288;
+
+// A sale over a collection anyone may recall from, with 2 NFTs gifted to a partner account.
+// Returns the partner and the vault holding their NFTs
+fn sale_with_gifted_nfts() -> (TestSale, ComponentAddress, InternalAddress) {
+    let mut ledger = LedgerSimulatorBuilder::new().build();
+    let (public_key, _private_key, account) = ledger.new_allocated_account();
+    let nft_resource = ledger.create_non_fungible_resource_advanced(
+        NonFungibleResourceRoles {
+            recall_roles: recall_roles! {
+                recaller => rule!(allow_all);
+                recaller_updater => rule!(deny_all);
+            },
+            ..Default::default()
+        },
+        account,
+        2,
+    );
+    let mut sale = TestSale::deploy(ledger, public_key, account, nft_resource, |builder| {
+        builder.price(10)
+    });
+    sale.add_nfts(2).expect_commit_success();
+
+    let component = sale.component;
+    let (_, _, partner) = sale.ledger.new_allocated_account();
+    sale.as_owner(|builder| {
+        builder.call_method(
+            component,
+            "gift_from_vault",
+            manifest_args!(NftSelection::Count(2), partner),
+        )
+    })
+    .expect_commit_success();
+    let vault = sale.ledger.get_component_vaults(partner, nft_resource)[0];
+    (sale, partner, InternalAddress::new_or_panic(vault.0))
+}
+
+fn schedule_recall(sale: &mut TestSale, vault: InternalAddress) -> u64 {
+    let nft_id = sale
+        .ledger
+        .inspect_non_fungible_vault(vault.into_node_id())
+        .unwrap()
+        .1
+        .next()
+        .unwrap();
+    let component = sale.component;
+    let receipt = sale.as_owner(|builder| {
+        builder.call_method(
+            component,
+            "schedule_recall",
+            manifest_args!(vault, indexset!(nft_id)),
+        )
+    });
+    receipt.expect_commit_success().output(1)
+}
+
+fn recall_item(sale: &mut TestSale, recall_id: u64) -> TransactionReceipt {
+    let component = sale.component;
+    sale.as_owner(|builder| {
+        builder.call_method(component, "recall_item", manifest_args!(recall_id))
+    })
+}
+
+#[test]
+fn recalls_wait_for_the_timelock() {
+    let (mut sale, partner, vault) = sale_with_gifted_nfts();
+    let (account, nft_resource) = (sale.account, sale.nft_resource);
+    let scheduled_at = sale.ledger.get_current_epoch();
+    let recall_id = schedule_recall(&mut sale, vault);
+
+    recall_item(&mut sale, recall_id).expect_commit_failure();
+    sale.ledger
+        .set_current_epoch(scheduled_at.after(RECALL_TIMELOCK_EPOCHS - 1).unwrap());
+    recall_item(&mut sale, recall_id).expect_commit_failure();
+    assert_eq!(sale.balance(partner, nft_resource), dec!(2));
+
+    sale.ledger
+        .set_current_epoch(scheduled_at.after(RECALL_TIMELOCK_EPOCHS).unwrap());
+    recall_item(&mut sale, recall_id).expect_commit_success();
+    assert_eq!(sale.balance(partner, nft_resource), dec!(1));
+    assert_eq!(sale.balance(account, nft_resource), dec!(1));
+
+    // A recall is executed once
+    recall_item(&mut sale, recall_id).expect_commit_failure();
+}
+
+#[test]
+fn cancelled_recalls_cannot_be_executed() {
+    let (mut sale, partner, vault) = sale_with_gifted_nfts();
+    let (component, nft_resource) = (sale.component, sale.nft_resource);
+    let scheduled_at = sale.ledger.get_current_epoch();
+    let recall_id = schedule_recall(&mut sale, vault);
+
+    sale.as_owner(|builder| {
+        builder.call_method(component, "cancel_recall", manifest_args!(recall_id))
+    })
+    .expect_commit_success();
+    sale.ledger
+        .set_current_epoch(scheduled_at.after(RECALL_TIMELOCK_EPOCHS).unwrap());
+    recall_item(&mut sale, recall_id).expect_commit_failure();
+    assert_eq!(sale.balance(partner, nft_resource), dec!(2));
+}
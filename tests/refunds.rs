@@ -0,0 +1,302 @@
+mod common;
+
+use common::TestSale;
+use nft_sale_interface::{ItemSale, SaleError, TreasuryBreakdown};
+use scrypto_test::prelude::*;
+
+fn cancel_sale(sale: &mut TestSale) -> TransactionReceipt {
+    let component = sale.component;
+    sale.as_owner(|builder| builder.call_method(component, "cancel_sale", manifest_args!()))
+}
+
+fn claim_refund(sale: &mut TestSale, count: u64) -> TransactionReceipt {
+    let (account, component, nft_resource) = (sale.account, sale.component, sale.nft_resource);
+    sale.as_public(|builder| {
+        builder
+            .withdraw_from_account(account, nft_resource, count)
+            .take_all_from_worktop(nft_resource, "nfts")
+            .with_bucket("nfts", |builder, bucket| {
+                builder.call_method(component, "claim_refund", manifest_args!(bucket))
+            })
+    })
+}
+
+fn lock_insurance(
+    sale: &mut TestSale,
+    deposit: Decimal,
+    locked_until: Epoch,
+    reveal_by: Option<Epoch>,
+) -> TransactionReceipt {
+    let (account, component, payment_token) = (sale.account, sale.component, sale.payment_token);
+    sale.as_owner(|builder| {
+        builder
+            .withdraw_from_account(account, payment_token, deposit)
+            .take_all_from_worktop(payment_token, "deposit")
+            .with_bucket("deposit", |builder, bucket| {
+                builder.call_method(
+                    component,
+                    "lock_insurance",
+                    manifest_args!(bucket, locked_until, reveal_by),
+                )
+            })
+    })
+}
+
+#[test]
+fn cancelled_sale_refunds_the_price_paid() {
+    let mut sale = TestSale::new(2, 2, dec!(10));
+    sale.start_sale().expect_commit_success();
+    sale.buy(dec!(10), 1).expect_commit_success();
+    sale.change_price(dec!(20)).expect_commit_success();
+    sale.buy(dec!(20), 1).expect_commit_success();
+
+    cancel_sale(&mut sale).expect_commit_success();
+    sale.start_sale().expect_commit_failure();
+    sale.buy(dec!(20), 1).expect_commit_failure();
+    sale.withdraw_profits().expect_commit_failure();
+
+    let (account, component, payment_token) = (sale.account, sale.component, sale.payment_token);
+    let before = sale.balance(account, payment_token);
+    claim_refund(&mut sale, 2).expect_commit_success();
+    assert_eq!(sale.balance(account, payment_token), before + dec!(30));
+    assert_eq!(sale.balance(component, payment_token), dec!(0));
+}
+
+#[test]
+fn refunds_require_a_cancelled_sale_and_an_open_window() {
+    let mut sale = TestSale::new(2, 2, dec!(10));
+    sale.start_sale().expect_commit_success();
+    sale.buy(dec!(20), 2).expect_commit_success();
+    claim_refund(&mut sale, 1).expect_commit_failure();
+
+    cancel_sale(&mut sale).expect_commit_success();
+    let now = sale.ledger.get_current_epoch();
+    sale.ledger.set_current_epoch(now.after(2016).unwrap());
+    claim_refund(&mut sale, 1).expect_commit_failure();
+    sale.withdraw_profits().expect_commit_success();
+}
+
+#[test]
+fn item_history_keeps_refunded_sales() {
+    let mut sale = TestSale::new(1, 1, dec!(10));
+    let component = sale.component;
+    let item_history = |sale: &mut TestSale| -> Vec<ItemSale> {
+        sale.as_public(|builder| {
+            builder.call_method(
+                component,
+                "item_history",
+                manifest_args!(NonFungibleLocalId::integer(1)),
+            )
+        })
+        .expect_commit_success()
+        .output(1)
+    };
+    assert!(item_history(&mut sale).is_empty());
+
+    sale.start_sale().expect_commit_success();
+    sale.buy(dec!(10), 1).expect_commit_success();
+    let history = item_history(&mut sale);
+    assert_eq!(history.len(), 1);
+    assert_eq!(history[0].price, dec!(10));
+    assert!(!history[0].refunded);
+
+    cancel_sale(&mut sale).expect_commit_success();
+    claim_refund(&mut sale, 1).expect_commit_success();
+    assert!(item_history(&mut sale)[0].refunded);
+}
+
+#[test]
+fn revenue_beyond_refunds_stays_withdrawable() {
+    let mut sale = TestSale::new(2, 2, dec!(10));
+    let (account, component, payment_token) = (sale.account, sale.component, sale.payment_token);
+    let treasury_breakdown = |sale: &mut TestSale| -> TreasuryBreakdown {
+        sale.as_public(|builder| {
+            builder.call_method(component, "treasury_breakdown", manifest_args!())
+        })
+        .expect_commit_success()
+        .output(1)
+    };
+    sale.start_sale().expect_commit_success();
+    sale.buy(dec!(20), 2).expect_commit_success();
+    // Vouchers are not refundable, so their revenue is not held back
+    sale.as_public(|builder| {
+        builder
+            .withdraw_from_account(account, payment_token, dec!(5))
+            .take_all_from_worktop(payment_token, "payment")
+            .with_bucket("payment", |builder, bucket| {
+                builder.call_method(component, "buy_vouchers", manifest_args!(bucket))
+            })
+    })
+    .expect_commit_success();
+    cancel_sale(&mut sale).expect_commit_success();
+
+    assert_eq!(
+        treasury_breakdown(&mut sale),
+        TreasuryBreakdown {
+            free: dec!(5),
+            locked_for_refunds: dec!(20),
+            escrowed: dec!(0),
+            held_deposits: dec!(0),
+        }
+    );
+    let before = sale.balance(account, payment_token);
+    sale.withdraw_profits().expect_commit_success();
+    assert_eq!(sale.balance(account, payment_token), before + dec!(5));
+    sale.withdraw_profits().expect_commit_failure();
+
+    claim_refund(&mut sale, 2).expect_commit_success();
+    assert_eq!(treasury_breakdown(&mut sale).locked_for_refunds, dec!(0));
+}
+
+#[test]
+fn revenue_is_held_for_refunds_from_the_first_sale() {
+    let mut sale = TestSale::new(2, 2, dec!(10));
+    let (account, component, payment_token) = (sale.account, sale.component, sale.payment_token);
+    sale.start_sale().expect_commit_success();
+    sale.buy(dec!(20), 2).expect_commit_success();
+    sale.withdraw_profits().expect_commit_failure();
+
+    cancel_sale(&mut sale).expect_commit_success();
+    let before = sale.balance(account, payment_token);
+    claim_refund(&mut sale, 2).expect_commit_success();
+    assert_eq!(sale.balance(account, payment_token), before + dec!(20));
+    assert_eq!(sale.balance(component, payment_token), dec!(0));
+}
+
+#[test]
+fn ended_sales_release_their_revenue_once_the_guarantee_runs_out() {
+    let mut sale = TestSale::new(2, 2, dec!(10));
+    let component = sale.component;
+    let end_sale = |sale: &mut TestSale| {
+        sale.as_owner(|builder| {
+            builder.call_method(component, "end_sale", manifest_args!(TestSale::NO_BADGE))
+        })
+    };
+    sale.start_sale().expect_commit_success();
+    sale.buy(dec!(10), 1).expect_commit_success();
+    end_sale(&mut sale).expect_commit_success();
+    sale.withdraw_profits().expect_commit_failure();
+
+    // Restarting the sale resets the guarantee
+    let now = sale.ledger.get_current_epoch();
+    sale.ledger.set_current_epoch(now.after(2000).unwrap());
+    sale.start_sale().expect_commit_success();
+    sale.buy(dec!(10), 1).expect_commit_success();
+    end_sale(&mut sale).expect_commit_success();
+    sale.ledger.set_current_epoch(now.after(2016).unwrap());
+    sale.withdraw_profits().expect_commit_failure();
+
+    sale.ledger.set_current_epoch(now.after(4016).unwrap());
+    sale.withdraw_profits().expect_commit_success();
+    cancel_sale(&mut sale).expect_commit_failure();
+    sale.start_sale().expect_commit_failure();
+}
+
+#[test]
+fn insurance_pays_the_refunds_the_treasury_cannot() {
+    let mut ledger = LedgerSimulatorBuilder::new().build();
+    let (public_key, _private_key, account) = ledger.new_allocated_account();
+    let (_, _, platform) = ledger.new_allocated_account();
+    let nft_resource = ledger.create_non_fungible_resource_advanced(
+        NonFungibleResourceRoles::default(),
+        account,
+        2,
+    );
+    let mut sale = TestSale::deploy(ledger, public_key, account, nft_resource, |builder| {
+        builder.price(10).platform_fee(dec!("0.05"), platform)
+    });
+    let (component, payment_token) = (sale.component, sale.payment_token);
+    sale.add_nfts(2).expect_commit_success();
+    sale.start_sale().expect_commit_success();
+    // The platform's cut leaves the treasury as the NFTs are sold
+    sale.buy(dec!(20), 2).expect_commit_success();
+
+    let now = sale.ledger.get_current_epoch();
+    let locked_until = now.after(100).unwrap();
+    // A lock that is already over pledges nothing
+    lock_insurance(&mut sale, dec!(50), now, None).expect_specific_failure(|error| {
+        SaleError::from_message(&format!("{:?}", error)) == Some(SaleError::InvalidConfiguration)
+    });
+    lock_insurance(&mut sale, dec!(50), locked_until, None).expect_commit_success();
+    let withdraw_insurance = |sale: &mut TestSale| {
+        sale.as_owner(|builder| {
+            builder.call_method(component, "withdraw_insurance", manifest_args!())
+        })
+    };
+    withdraw_insurance(&mut sale).expect_commit_failure();
+
+    cancel_sale(&mut sale).expect_commit_success();
+    let before = sale.balance(account, payment_token);
+    let receipt = claim_refund(&mut sale, 2);
+    let commit = receipt.expect_commit_success();
+    assert!(commit
+        .application_events
+        .iter()
+        .any(|(event_type, _)| event_type.1 == "InsuranceSlashedEvent"));
+    assert_eq!(sale.balance(account, payment_token), before + dec!(20));
+
+    // The rest stays locked until the refund window closes, past the original lock
+    sale.ledger.set_current_epoch(locked_until);
+    withdraw_insurance(&mut sale).expect_commit_failure();
+    sale.ledger.set_current_epoch(now.after(2016).unwrap());
+    withdraw_insurance(&mut sale).expect_commit_success();
+    assert_eq!(sale.balance(component, payment_token), dec!(0));
+}
+
+#[test]
+fn missed_reveal_deadlines_slash_the_insurance() {
+    let mut sale = TestSale::new(1, 1, dec!(10));
+    let (account, component, payment_token) = (sale.account, sale.component, sale.payment_token);
+    sale.start_sale().expect_commit_success();
+    sale.buy(dec!(10), 1).expect_commit_success();
+    let now = sale.ledger.get_current_epoch();
+    let reveal_by = now.after(10).unwrap();
+    lock_insurance(
+        &mut sale,
+        dec!(50),
+        now.after(100).unwrap(),
+        Some(reveal_by),
+    )
+    .expect_commit_success();
+
+    let slash_insurance = |sale: &mut TestSale| {
+        sale.as_public(|builder| {
+            builder.call_method(component, "slash_insurance", manifest_args!())
+        })
+    };
+    slash_insurance(&mut sale).expect_specific_failure(|error| {
+        SaleError::from_message(&format!("{:?}", error)) == Some(SaleError::OutsideWindow)
+    });
+
+    // Past the deadline the owner can no longer reveal, and anyone can cancel the sale
+    sale.ledger.set_current_epoch(reveal_by);
+    sale.as_owner(|builder| builder.call_method(component, "reveal", manifest_args!()))
+        .expect_commit_failure();
+    slash_insurance(&mut sale).expect_commit_success();
+    sale.buy(dec!(10), 1).expect_commit_failure();
+
+    let before = sale.balance(account, payment_token);
+    claim_refund(&mut sale, 1).expect_commit_success();
+    assert_eq!(sale.balance(account, payment_token), before + dec!(10));
+}
+
+#[test]
+fn revealing_in_time_keeps_the_insurance() {
+    let mut sale = TestSale::new(1, 1, dec!(10));
+    let component = sale.component;
+    let now = sale.ledger.get_current_epoch();
+    let reveal_by = now.after(10).unwrap();
+    lock_insurance(
+        &mut sale,
+        dec!(50),
+        now.after(100).unwrap(),
+        Some(reveal_by),
+    )
+    .expect_commit_success();
+    sale.as_owner(|builder| builder.call_method(component, "reveal", manifest_args!()))
+        .expect_commit_success();
+
+    sale.ledger.set_current_epoch(reveal_by);
+    sale.as_public(|builder| builder.call_method(component, "slash_insurance", manifest_args!()))
+        .expect_commit_failure();
+}
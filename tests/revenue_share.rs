@@ -0,0 +1,72 @@
+mod common;
+
+use common::TestSale;
+use scrypto_test::prelude::*;
+
+fn claim(sale: &mut TestSale, share: u64) -> TransactionReceipt {
+    let (account, component, backer_share) = (sale.account, sale.component, sale.backer_share);
+    sale.as_public(|builder| {
+        builder
+            .create_proof_from_account_of_non_fungibles(
+                account,
+                backer_share,
+                [NonFungibleLocalId::integer(share)],
+            )
+            .pop_from_auth_zone("shares")
+            .with_name_lookup(|builder, lookup| {
+                let shares = lookup.proof("shares");
+                builder.call_method(component, "claim_revenue_share", manifest_args!(shares))
+            })
+    })
+}
+
+#[test]
+fn backers_claim_their_share_of_revenue_once() {
+    let mut sale = TestSale::new(4, 4, dec!(10));
+    let (account, component, payment_token) = (sale.account, sale.component, sale.payment_token);
+    sale.as_owner(|builder| {
+        builder
+            .call_method(
+                component,
+                "issue_backer_shares",
+                manifest_args!(2u32, dec!("0.2")),
+            )
+            .call_method(component, "start_sale", manifest_args!(TestSale::NO_BADGE))
+    })
+    .expect_commit_success();
+    sale.buy(dec!(20), 2).expect_commit_success();
+
+    // 20% of 20 is split between 2 shares
+    let before = sale.balance(account, payment_token);
+    claim(&mut sale, 1).expect_commit_success();
+    assert_eq!(sale.balance(account, payment_token), before + dec!(2));
+    claim(&mut sale, 1).expect_commit_success();
+    assert_eq!(sale.balance(account, payment_token), before + dec!(2));
+
+    sale.buy(dec!(10), 1).expect_commit_success();
+    let before = sale.balance(account, payment_token);
+    claim(&mut sale, 1).expect_commit_success();
+    claim(&mut sale, 2).expect_commit_success();
+    assert_eq!(sale.balance(account, payment_token), before + dec!(4));
+
+    sale.end_refund_guarantee();
+    sale.withdraw_profits().expect_commit_success();
+    assert_eq!(sale.balance(account, payment_token), before + dec!(28));
+}
+
+#[test]
+fn backer_shares_are_issued_once() {
+    let mut sale = TestSale::new(1, 1, dec!(10));
+    let component = sale.component;
+    let issue = |builder: ManifestBuilder| {
+        builder.call_method(
+            component,
+            "issue_backer_shares",
+            manifest_args!(1u32, dec!("0.1")),
+        )
+    };
+
+    sale.as_public(issue).expect_auth_failure();
+    sale.as_owner(issue).expect_commit_success();
+    sale.as_owner(issue).expect_commit_failure();
+}
@@ -0,0 +1,204 @@
+mod common;
+
+use common::TestSale;
+use nft_sale_interface::SaleOwner;
+use scrypto_test::prelude::*;
+
+#[test]
+fn test_pricer_badge_can_only_change_price() {
+    let mut sale = TestSale::new(10, 5, dec!(5));
+    let (component, pricer_badge) = (sale.component, sale.pricer_badge);
+
+    sale.with_badge(pricer_badge, |builder| {
+        TestSale::presenting(builder, pricer_badge, |builder, badge| {
+            builder.call_method(component, "change_price", manifest_args!(badge, dec!(7)))
+        })
+    })
+    .expect_commit_success();
+    assert_eq!(sale.price(), dec!(7));
+
+    for method in ["start_sale", "end_sale", "withdraw_profits"] {
+        sale.with_badge(pricer_badge, |builder| {
+            builder.call_method(component, method, manifest_args!())
+        })
+        .expect_auth_failure();
+    }
+}
+
+#[test]
+fn test_owner_can_recall_the_pricer_badge() {
+    let mut sale = TestSale::new(10, 5, dec!(5));
+    let (account, pricer_badge) = (sale.account, sale.pricer_badge);
+    let (_, _, pricing_bot) = sale.ledger.new_allocated_account();
+    sale.as_owner(|builder| {
+        builder
+            .withdraw_from_account(account, pricer_badge, dec!(1))
+            .try_deposit_entire_worktop_or_abort(pricing_bot, None)
+    })
+    .expect_commit_success();
+    let vault = sale.ledger.get_component_vaults(pricing_bot, pricer_badge)[0];
+    let vault = InternalAddress::new_or_panic(vault.0);
+
+    sale.as_public(|builder| builder.recall(vault, dec!(1)))
+        .expect_auth_failure();
+    sale.as_owner(|builder| builder.recall(vault, dec!(1)))
+        .expect_commit_success();
+    assert_eq!(sale.balance(pricing_bot, pricer_badge), dec!(0));
+    assert_eq!(sale.balance(account, pricer_badge), dec!(1));
+}
+
+#[derive(ScryptoSbor)]
+struct AdminBadge {
+    name: String,
+}
+
+impl NonFungibleData for AdminBadge {
+    const MUTABLE_FIELDS: &'static [&'static str] = &["name"];
+}
+
+#[test]
+fn test_owner_can_rename_admin_badges() {
+    let mut sale = TestSale::new(10, 5, dec!(5));
+    let (component, admin_badge) = (sale.component, sale.admin_badge);
+    let badge_id = NonFungibleLocalId::integer(0);
+
+    sale.as_owner(|builder| {
+        builder.call_method(
+            component,
+            "rename_admin_badge",
+            manifest_args!(badge_id.clone(), "alice.xrd"),
+        )
+    })
+    .expect_commit_success();
+    let badge: AdminBadge = sale
+        .ledger
+        .get_non_fungible_data(admin_badge, badge_id.clone());
+    assert_eq!(badge.name, "alice.xrd");
+
+    sale.with_badge(admin_badge, |builder| {
+        builder.call_method(
+            component,
+            "rename_admin_badge",
+            manifest_args!(badge_id, "mallory.xrd"),
+        )
+    })
+    .expect_auth_failure();
+}
+
+#[test]
+fn test_price_changes_require_a_badge() {
+    let mut sale = TestSale::new(10, 5, dec!(5));
+    let component = sale.component;
+
+    sale.as_public(|builder| {
+        builder.call_method(
+            component,
+            "change_price",
+            manifest_args!(TestSale::NO_BADGE, dec!(1)),
+        )
+    })
+    .expect_auth_failure();
+    assert_eq!(sale.price(), dec!(5));
+}
+
+#[test]
+fn test_vip_badge_lifts_the_per_transaction_cap() {
+    let mut sale = TestSale::new(15, 15, dec!(1));
+    let (account, component, vip_badge, payment_token) = (
+        sale.account,
+        sale.component,
+        sale.vip_badge,
+        sale.payment_token,
+    );
+    sale.start_sale().expect_commit_success();
+    sale.as_owner(|builder| {
+        builder.call_method(
+            component,
+            "grant_vip",
+            manifest_args!("Market maker".to_owned()),
+        )
+    })
+    .expect_commit_success();
+
+    let buy_vip = |builder: ManifestBuilder| {
+        builder
+            .withdraw_from_account(account, payment_token, dec!(12))
+            .take_all_from_worktop(payment_token, "payment")
+            .with_bucket("payment", |builder, bucket| {
+                builder.call_method(component, "buy_vip", manifest_args!(bucket, 12u16))
+            })
+    };
+    sale.as_public(buy_vip).expect_auth_failure();
+    sale.with_badge(vip_badge, buy_vip).expect_commit_success();
+
+    let nft_resource = sale.nft_resource;
+    assert_eq!(sale.balance(account, nft_resource), dec!(12));
+}
+
+#[test]
+fn test_free_sales_are_open_to_vip_badge_holders_only() {
+    let mut sale = TestSale::new_with(5, 5, |builder| builder.price(dec!(0)).max_per_tx(2));
+    let (account, component, vip_badge, payment_token) = (
+        sale.account,
+        sale.component,
+        sale.vip_badge,
+        sale.payment_token,
+    );
+    sale.start_sale().expect_commit_success();
+    sale.buy(dec!(0), 1).expect_commit_failure();
+
+    sale.as_owner(|builder| {
+        builder.call_method(component, "grant_vip", manifest_args!("Holder".to_owned()))
+    })
+    .expect_commit_success();
+    sale.with_badge(vip_badge, |builder| {
+        builder
+            .withdraw_from_account(account, payment_token, dec!(0))
+            .take_all_from_worktop(payment_token, "payment")
+            .with_bucket("payment", |builder, bucket| {
+                builder.call_method(component, "buy_vip", manifest_args!(bucket, 1u16))
+            })
+    })
+    .expect_commit_success();
+
+    let nft_resource = sale.nft_resource;
+    assert_eq!(sale.balance(account, nft_resource), dec!(1));
+
+    // A free claim is capped like any purchase, VIP or not
+    sale.with_badge(vip_badge, |builder| {
+        builder
+            .withdraw_from_account(account, payment_token, dec!(0))
+            .take_all_from_worktop(payment_token, "payment")
+            .with_bucket("payment", |builder, bucket| {
+                builder.call_method(component, "buy_vip", manifest_args!(bucket, 3u16))
+            })
+    })
+    .expect_commit_failure();
+}
+
+#[test]
+fn test_custom_owner_rule_replaces_the_owner_badge() {
+    let mut ledger = LedgerSimulatorBuilder::new().build();
+    let (public_key, _private_key, account) = ledger.new_allocated_account();
+    let nft_resource = ledger.create_non_fungible_resource_advanced(
+        NonFungibleResourceRoles::default(),
+        account,
+        5,
+    );
+    let dao_badge = ledger.create_fungible_resource(dec!(1), 0, account);
+    let mut sale = TestSale::deploy(ledger, public_key, account, nft_resource, |builder| {
+        builder
+            .price(1)
+            .owner(SaleOwner::Fixed(rule!(require(dao_badge))))
+    });
+    let component = sale.component;
+
+    sale.as_owner(|builder| {
+        builder.call_method(component, "start_sale", manifest_args!(TestSale::NO_BADGE))
+    })
+    .expect_auth_failure();
+    sale.with_badge(dao_badge, |builder| {
+        builder.call_method(component, "start_sale", manifest_args!(TestSale::NO_BADGE))
+    })
+    .expect_commit_success();
+}
@@ -0,0 +1,124 @@
+mod common;
+
+use common::TestSale;
+use nft_sale_interface::{NFTSaleBuilder, PurchaseLeg};
+use scrypto_test::prelude::*;
+
+// Instantiate another started sale in the same package and payment token, stocked with all of
+// a fresh collection of `stocked` NFTs. Returns the sale component and its collection
+fn second_sale(
+    sale: &mut TestSale,
+    stocked: usize,
+    price: Decimal,
+) -> (ComponentAddress, ResourceAddress) {
+    let (account, package_address, payment_token) =
+        (sale.account, sale.package_address, sale.payment_token);
+    let nft_resource = sale.ledger.create_non_fungible_resource_advanced(
+        NonFungibleResourceRoles::default(),
+        account,
+        stocked,
+    );
+    let config = NFTSaleBuilder::new(nft_resource, payment_token)
+        .price(price)
+        .build()
+        .unwrap();
+    let receipt = sale.as_public(|builder| {
+        builder.call_function(
+            package_address,
+            "NFTSale",
+            "instantiate_nft_sale",
+            manifest_args!(config),
+        )
+    });
+    let commit = receipt.expect_commit_success();
+    let component = commit.new_component_addresses()[0];
+    let owner_badge = commit.new_resource_addresses()[0];
+
+    sale.with_badge(owner_badge, |builder| {
+        builder
+            .withdraw_from_account(account, nft_resource, stocked)
+            .take_all_from_worktop(nft_resource, "nfts")
+            .with_bucket("nfts", |builder, bucket| {
+                builder.call_method(
+                    component,
+                    "add_nfts_to_vault",
+                    manifest_args!(TestSale::NO_BADGE, bucket),
+                )
+            })
+            .call_method(component, "start_sale", manifest_args!(TestSale::NO_BADGE))
+    })
+    .expect_commit_success();
+    (component, nft_resource)
+}
+
+fn sweep(sale: &mut TestSale, payment: Decimal, legs: Vec<PurchaseLeg>) -> TransactionReceipt {
+    let (account, package_address, payment_token) =
+        (sale.account, sale.package_address, sale.payment_token);
+    sale.as_public(|builder| {
+        builder
+            .withdraw_from_account(account, payment_token, payment)
+            .take_all_from_worktop(payment_token, "payment")
+            .with_bucket("payment", |builder, bucket| {
+                builder.call_function(
+                    package_address,
+                    "PurchaseRouter",
+                    "sweep",
+                    manifest_args!(bucket, legs),
+                )
+            })
+    })
+}
+
+#[test]
+fn sweep_buys_from_every_sale_with_one_payment() {
+    let mut sale = TestSale::new(3, 3, dec!(10));
+    sale.start_sale().expect_commit_success();
+    let (other, other_nfts) = second_sale(&mut sale, 2, dec!(5));
+    let (account, component, nft_resource, payment_token) = (
+        sale.account,
+        sale.component,
+        sale.nft_resource,
+        sale.payment_token,
+    );
+    let before = sale.balance(account, payment_token);
+
+    let legs = vec![
+        PurchaseLeg {
+            sale: component,
+            number_of_nfts: 2,
+            max_price: dec!(10),
+        },
+        PurchaseLeg {
+            sale: other,
+            number_of_nfts: 2,
+            max_price: dec!(5),
+        },
+    ];
+    sweep(&mut sale, dec!(50), legs).expect_commit_success();
+
+    assert_eq!(sale.balance(account, nft_resource), dec!(2));
+    assert_eq!(sale.balance(account, other_nfts), dec!(2));
+    assert_eq!(sale.balance(account, payment_token), before - dec!(30));
+}
+
+#[test]
+fn sweep_aborts_when_a_price_is_above_its_limit() {
+    let mut sale = TestSale::new(3, 3, dec!(10));
+    sale.start_sale().expect_commit_success();
+    let (other, _) = second_sale(&mut sale, 2, dec!(5));
+    let component = sale.component;
+
+    let legs = vec![
+        PurchaseLeg {
+            sale: component,
+            number_of_nfts: 1,
+            max_price: dec!(10),
+        },
+        PurchaseLeg {
+            sale: other,
+            number_of_nfts: 1,
+            max_price: dec!(4),
+        },
+    ];
+    sweep(&mut sale, dec!(50), legs).expect_commit_failure();
+}
@@ -0,0 +1,73 @@
+mod common;
+
+use common::TestSale;
+use scrypto_test::prelude::*;
+
+fn royalty_paid(receipt: &TransactionReceipt) -> Decimal {
+    receipt.expect_commit_success();
+    receipt.fee_summary.total_royalty_cost_in_xrd
+}
+
+#[test]
+fn test_owner_operations_never_pay_royalties() {
+    let mut sale = TestSale::new_with(20, 10, |builder| {
+        builder.price(5).buy_royalty(RoyaltyAmount::Xrd(dec!(2)))
+    });
+    let component = sale.component;
+
+    assert_eq!(royalty_paid(&sale.start_sale()), dec!(0));
+    assert_eq!(royalty_paid(&sale.buy(dec!(10), 2)), dec!(2));
+    assert_eq!(royalty_paid(&sale.add_nfts(5)), dec!(0));
+    assert_eq!(royalty_paid(&sale.change_price(dec!(6))), dec!(0));
+    assert_eq!(
+        royalty_paid(&sale.as_owner(|builder| {
+            builder.call_method(component, "end_sale", manifest_args!(TestSale::NO_BADGE))
+        })),
+        dec!(0)
+    );
+    let now = sale.ledger.get_current_epoch();
+    sale.ledger.set_current_epoch(now.after(2016).unwrap());
+    assert_eq!(royalty_paid(&sale.withdraw_profits()), dec!(0));
+}
+
+#[test]
+fn test_owner_method_royalties_are_locked() {
+    let mut sale = TestSale::new(10, 5, dec!(5));
+    let component = sale.component;
+
+    for method in [
+        "withdraw_profits",
+        "change_price",
+        "add_nfts_to_vault",
+        "start_sale",
+    ] {
+        sale.as_owner(|builder| {
+            builder.set_component_royalty(component, method, RoyaltyAmount::Xrd(dec!(1)))
+        })
+        .expect_commit_failure();
+    }
+
+    // Nor can users be charged for getting back what is theirs
+    for method in [
+        "claim_refund",
+        "claim_escrow",
+        "unstake",
+        "reclaim_hold",
+        "release_hold",
+        "claim_revenue_share",
+        "claim_commission",
+        "withdraw_budget",
+        "redeem_physical",
+    ] {
+        sale.as_owner(|builder| {
+            builder.set_component_royalty(component, method, RoyaltyAmount::Xrd(dec!(1)))
+        })
+        .expect_commit_failure();
+    }
+
+    // The royalty on public methods stays adjustable
+    sale.as_owner(|builder| {
+        builder.set_component_royalty(component, "buy", RoyaltyAmount::Xrd(dec!(1)))
+    })
+    .expect_commit_success();
+}
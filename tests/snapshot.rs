@@ -0,0 +1,71 @@
+mod common;
+
+use common::TestSale;
+use nft_sale_interface::SnapshotChunk;
+use scrypto_test::prelude::*;
+
+fn export(sale: &mut TestSale, cursor: u64) -> (SnapshotChunk, Option<u64>) {
+    let component = sale.component;
+    let receipt = sale.as_public(|builder| {
+        builder.call_method(component, "export_state_snapshot", manifest_args!(cursor))
+    });
+    receipt.expect_commit_success().output(1)
+}
+
+#[test]
+fn snapshot_pages_through_summary_and_inventory() {
+    let mut sale = TestSale::new(150, 150, dec!(10));
+    sale.start_sale().expect_commit_success();
+    sale.buy(dec!(10), 1).expect_commit_success();
+
+    let (summary, mut cursor) = export(&mut sale, 0);
+    let SnapshotChunk::Summary(summary) = summary else {
+        panic!("the first chunk is the summary");
+    };
+    assert_eq!(summary.price, dec!(10));
+    assert_eq!(summary.inventory, dec!(149));
+    assert_eq!(summary.treasury, dec!(10));
+    assert!(summary.sale_allowed);
+
+    let mut ids = IndexSet::new();
+    while let Some(next) = cursor {
+        let (chunk, after) = export(&mut sale, next);
+        let SnapshotChunk::Inventory(page) = chunk else {
+            panic!("chunks after the summary list inventory ids");
+        };
+        ids.extend(page);
+        cursor = after;
+    }
+    assert_eq!(ids.len(), 149);
+}
+
+fn inventory_page(sale: &mut TestSale, cursor: u64) -> (Vec<NonFungibleLocalId>, Option<u64>) {
+    let (chunk, next) = export(sale, cursor);
+    let SnapshotChunk::Inventory(page) = chunk else {
+        panic!("chunks after the summary list inventory ids");
+    };
+    (page, next)
+}
+
+#[test]
+fn sales_between_pages_neither_repeat_nor_skip_ids() {
+    let mut sale = TestSale::new(150, 150, dec!(10));
+    sale.start_sale().expect_commit_success();
+
+    let (first, next) = inventory_page(&mut sale, 1);
+    assert_eq!(first.len(), 100);
+    sale.buy(dec!(100), 10).expect_commit_success();
+    let (second, next) = inventory_page(&mut sale, next.unwrap());
+    assert_eq!(next, None);
+    let count = first.len() + second.len();
+    let paged: IndexSet<NonFungibleLocalId> = first.into_iter().chain(second).collect();
+    assert_eq!(paged.len(), count);
+
+    // Every NFT still on sale was on one of the pages
+    let mut cursor = Some(1);
+    while let Some(next) = cursor {
+        let (page, after) = inventory_page(&mut sale, next);
+        assert!(page.iter().all(|nft_id| paged.contains(nft_id)));
+        cursor = after;
+    }
+}
@@ -0,0 +1,84 @@
+mod common;
+
+use common::TestSale;
+use scrypto_test::prelude::*;
+
+fn buy_for(
+    sale: &mut TestSale,
+    payment: Decimal,
+    number_of_nfts: u16,
+    sponsor: ComponentAddress,
+    recipient: ComponentAddress,
+) -> TransactionReceipt {
+    let (account, component, payment_token) = (sale.account, sale.component, sale.payment_token);
+    sale.as_public(|builder| {
+        builder
+            .withdraw_from_account(account, payment_token, payment)
+            .take_all_from_worktop(payment_token, "payment")
+            .with_bucket("payment", |builder, bucket| {
+                builder.call_method(
+                    component,
+                    "buy_for",
+                    manifest_args!(bucket, number_of_nfts, sponsor, recipient),
+                )
+            })
+    })
+}
+
+#[test]
+fn sponsor_pays_and_recipient_receives_the_nfts() {
+    let mut sale = TestSale::new(3, 3, dec!(10));
+    sale.start_sale().expect_commit_success();
+    let (_, _, member) = sale.ledger.new_allocated_account();
+    let (account, payment_token, nft_resource) =
+        (sale.account, sale.payment_token, sale.nft_resource);
+    let before = sale.balance(account, payment_token);
+
+    buy_for(&mut sale, dec!(25), 2, account, member).expect_commit_success();
+
+    assert_eq!(sale.balance(member, nft_resource), dec!(2));
+    assert_eq!(sale.balance(account, nft_resource), dec!(0));
+    assert_eq!(sale.balance(account, payment_token), before - dec!(20));
+}
+
+#[test]
+fn sponsor_must_authorize_the_purchase() {
+    let mut sale = TestSale::new(1, 1, dec!(10));
+    sale.start_sale().expect_commit_success();
+    let (_, _, other) = sale.ledger.new_allocated_account();
+    let (_, _, member) = sale.ledger.new_allocated_account();
+
+    buy_for(&mut sale, dec!(10), 1, other, member).expect_auth_assertion_failure();
+}
+
+#[test]
+fn nfts_a_recipient_refuses_wait_in_the_locker() {
+    let mut sale = TestSale::new(3, 3, dec!(10));
+    sale.start_sale().expect_commit_success();
+    let (member_key, _, member) = sale.ledger.new_allocated_account();
+    let manifest = ManifestBuilder::new()
+        .lock_fee_from_faucet()
+        .call_method(
+            member,
+            "set_default_deposit_rule",
+            manifest_args!(DefaultDepositRule::Reject),
+        )
+        .build();
+    sale.ledger
+        .execute_manifest(
+            manifest,
+            vec![NonFungibleGlobalId::from_public_key(&member_key)],
+        )
+        .expect_commit_success();
+    let (account, nft_resource) = (sale.account, sale.nft_resource);
+
+    buy_for(&mut sale, dec!(10), 1, account, member).expect_commit_success();
+
+    assert_eq!(sale.balance(member, nft_resource), dec!(0));
+    assert_eq!(sale.balance(account, nft_resource), dec!(0));
+    let component = sale.component;
+    let receipt = sale
+        .as_public(|builder| builder.call_method(component, "delivery_locker", manifest_args!()));
+    let locker: Option<ComponentAddress> = receipt.expect_commit_success().output(1);
+    assert_eq!(sale.balance(locker.unwrap(), nft_resource), dec!(1));
+}
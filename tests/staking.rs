@@ -0,0 +1,177 @@
+mod common;
+
+use common::TestSale;
+use nft_sale_interface::{SaleError, StakeDiscount};
+use scrypto_test::prelude::*;
+
+const LOCK_EPOCHS: u64 = 100;
+
+// A started sale at a price of 10 offering 20% off for stakes of at least 50
+fn sale_with_discount() -> TestSale {
+    let mut sale = TestSale::new(5, 5, dec!(10));
+    let component = sale.component;
+    let terms = Some(StakeDiscount {
+        minimum_stake: dec!(50),
+        lock_epochs: LOCK_EPOCHS,
+        discount: dec!("0.2"),
+    });
+    sale.as_owner(|builder| {
+        builder.call_method(component, "set_stake_discount", manifest_args!(terms))
+    })
+    .expect_commit_success();
+    sale.start_sale().expect_commit_success();
+    sale
+}
+
+fn stake(sale: &mut TestSale, amount: Decimal) -> TransactionReceipt {
+    let (account, component, payment_token) = (sale.account, sale.component, sale.payment_token);
+    sale.as_public(|builder| {
+        builder
+            .withdraw_from_account(account, payment_token, amount)
+            .take_all_from_worktop(payment_token, "stake")
+            .with_bucket("stake", |builder, bucket| {
+                builder.call_method(component, "stake", manifest_args!(bucket))
+            })
+    })
+}
+
+fn buy_staked(sale: &mut TestSale, payment: Decimal, number_of_nfts: u16) -> TransactionReceipt {
+    let (account, component, payment_token, stake_receipt) = (
+        sale.account,
+        sale.component,
+        sale.payment_token,
+        sale.stake_receipt,
+    );
+    sale.as_public(|builder| {
+        builder
+            .create_proof_from_account_of_amount(account, stake_receipt, dec!(1))
+            .pop_from_auth_zone("stake")
+            .withdraw_from_account(account, payment_token, payment)
+            .take_all_from_worktop(payment_token, "payment")
+            .with_name_lookup(|builder, lookup| {
+                let (payment, stake) = (lookup.bucket("payment"), lookup.proof("stake"));
+                builder.call_method(
+                    component,
+                    "buy_staked",
+                    manifest_args!(payment, number_of_nfts, stake),
+                )
+            })
+    })
+}
+
+fn unstake(sale: &mut TestSale) -> TransactionReceipt {
+    let (account, component, stake_receipt) = (sale.account, sale.component, sale.stake_receipt);
+    sale.as_public(|builder| {
+        builder
+            .withdraw_from_account(account, stake_receipt, dec!(1))
+            .take_all_from_worktop(stake_receipt, "receipt")
+            .with_bucket("receipt", |builder, bucket| {
+                builder.call_method(component, "unstake", manifest_args!(bucket))
+            })
+    })
+}
+
+#[test]
+fn stakers_buy_at_the_discounted_price() {
+    let mut sale = sale_with_discount();
+    let (account, payment_token) = (sale.account, sale.payment_token);
+
+    stake(&mut sale, dec!(40)).expect_commit_failure();
+    stake(&mut sale, dec!(50)).expect_commit_success();
+    let before = sale.balance(account, payment_token);
+
+    buy_staked(&mut sale, dec!(20), 2).expect_commit_success();
+    assert_eq!(sale.balance(account, payment_token), before - dec!(16));
+}
+
+#[test]
+fn stake_is_returned_only_after_the_lock() {
+    let mut sale = sale_with_discount();
+    let (account, payment_token) = (sale.account, sale.payment_token);
+    stake(&mut sale, dec!(50)).expect_commit_success();
+    let staked = sale.balance(account, payment_token);
+
+    unstake(&mut sale).expect_commit_failure();
+
+    let now = sale.ledger.get_current_epoch();
+    sale.ledger
+        .set_current_epoch(now.after(LOCK_EPOCHS).unwrap());
+    unstake(&mut sale).expect_commit_success();
+    assert_eq!(sale.balance(account, payment_token), staked + dec!(50));
+
+    // Without a receipt there is no discount
+    buy_staked(&mut sale, dec!(10), 1).expect_commit_failure();
+}
+
+#[test]
+fn discounts_cannot_make_the_sale_free() {
+    let mut sale = sale_with_discount();
+    let component = sale.component;
+    let full_discount = Some(StakeDiscount {
+        minimum_stake: dec!(50),
+        lock_epochs: LOCK_EPOCHS,
+        discount: dec!(1),
+    });
+
+    sale.as_owner(|builder| {
+        builder.call_method(
+            component,
+            "set_stake_discount",
+            manifest_args!(full_discount),
+        )
+    })
+    .expect_commit_failure();
+    // Neither can the price drop to zero while the discount is offered
+    sale.change_price(dec!(0)).expect_commit_failure();
+}
+
+#[test]
+fn unlocked_stakes_no_longer_earn_the_discount() {
+    let mut sale = sale_with_discount();
+    stake(&mut sale, dec!(50)).expect_commit_success();
+
+    let now = sale.ledger.get_current_epoch();
+    sale.ledger
+        .set_current_epoch(now.after(LOCK_EPOCHS).unwrap());
+    buy_staked(&mut sale, dec!(8), 1).expect_specific_failure(|error| {
+        SaleError::from_message(&format!("{:?}", error)) == Some(SaleError::OutsideWindow)
+    });
+}
+
+#[test]
+fn stakes_below_the_minimum_no_longer_earn_the_discount() {
+    let mut sale = sale_with_discount();
+    let component = sale.component;
+    stake(&mut sale, dec!(50)).expect_commit_success();
+
+    // Raising the minimum leaves smaller stakes behind
+    let terms = Some(StakeDiscount {
+        minimum_stake: dec!(60),
+        lock_epochs: LOCK_EPOCHS,
+        discount: dec!("0.2"),
+    });
+    sale.as_owner(|builder| {
+        builder.call_method(component, "set_stake_discount", manifest_args!(terms))
+    })
+    .expect_commit_success();
+    buy_staked(&mut sale, dec!(8), 1).expect_specific_failure(|error| {
+        SaleError::from_message(&format!("{:?}", error)) == Some(SaleError::InsufficientPayment)
+    });
+}
+
+#[test]
+fn stakes_stay_claimable_after_finalize() {
+    let mut sale = sale_with_discount();
+    let (account, component, payment_token) = (sale.account, sale.component, sale.payment_token);
+    stake(&mut sale, dec!(50)).expect_commit_success();
+    sale.buy(dec!(50), 5).expect_commit_success();
+    sale.end_refund_guarantee();
+    sale.withdraw_profits().expect_commit_success();
+    sale.as_owner(|builder| builder.call_method(component, "finalize", manifest_args!()))
+        .expect_commit_success();
+
+    // The refund guarantee outlasts the lock, so the stake is free to go
+    let before = sale.balance(account, payment_token);
+    unstake(&mut sale).expect_commit_success();
+    assert_eq!(sale.balance(account, payment_token), before + dec!(50));
+}
@@ -0,0 +1,53 @@
+mod common;
+
+use common::TestSale;
+use scrypto_test::prelude::*;
+
+// Mirror of the blueprint's event, decoded from the receipt
+#[derive(ScryptoSbor)]
+struct NftsSoldEvent {
+    nft_ids: IndexSet<NonFungibleLocalId>,
+    revenue: Decimal,
+    recipient: Option<ComponentAddress>,
+    sponsor: Option<ComponentAddress>,
+    tag: Option<String>,
+    cohort: Option<u32>,
+}
+
+fn buy_tagged(sale: &mut TestSale, tag: &str) -> TransactionReceipt {
+    let (account, component, payment_token) = (sale.account, sale.component, sale.payment_token);
+    sale.as_public(|builder| {
+        builder
+            .withdraw_from_account(account, payment_token, dec!(10))
+            .take_all_from_worktop(payment_token, "payment")
+            .with_bucket("payment", |builder, bucket| {
+                builder.call_method(component, "buy_tagged", manifest_args!(bucket, 1u16, tag))
+            })
+    })
+}
+
+#[test]
+fn tags_are_recorded_in_the_sale_event() {
+    let mut sale = TestSale::new(5, 5, dec!(10));
+    sale.start_sale().expect_commit_success();
+
+    let receipt = buy_tagged(&mut sale, "spring-campaign");
+    let (_, data) = receipt
+        .expect_commit_success()
+        .application_events
+        .iter()
+        .find(|(event_type, _)| event_type.1 == "NftsSoldEvent")
+        .unwrap();
+    let event: NftsSoldEvent = scrypto_decode(data).unwrap();
+    assert_eq!(event.tag.as_deref(), Some("spring-campaign"));
+    assert_eq!(event.revenue, dec!(10));
+    assert_eq!(event.cohort, None);
+}
+
+#[test]
+fn overlong_tags_are_refused() {
+    let mut sale = TestSale::new(5, 5, dec!(10));
+    sale.start_sale().expect_commit_success();
+
+    buy_tagged(&mut sale, &"x".repeat(65)).expect_commit_failure();
+}
@@ -0,0 +1,94 @@
+mod common;
+
+use common::TestSale;
+use nft_sale_interface::UpgradePath;
+use scrypto_test::prelude::*;
+
+// A stocked sale plus a burnable "generation 1" collection of `old_minted` NFTs in the account
+fn sale_with_old_collection(old_minted: u64) -> (TestSale, ResourceAddress) {
+    let mut sale = TestSale::new(5, 5, dec!(10));
+    let account = sale.account;
+    let old_collection = sale
+        .ledger
+        .create_freely_mintable_and_burnable_non_fungible_resource(
+            OwnerRole::None,
+            NonFungibleIdType::Integer,
+            Some(
+                (1..=old_minted)
+                    .map(|id| (NonFungibleLocalId::integer(id), EmptyNonFungibleData {})),
+            ),
+            account,
+        );
+    (sale, old_collection)
+}
+
+fn set_upgrade_path(sale: &mut TestSale, upgrade_path: Option<UpgradePath>) -> TransactionReceipt {
+    let component = sale.component;
+    sale.as_owner(|builder| {
+        builder.call_method(component, "set_upgrade_path", manifest_args!(upgrade_path))
+    })
+}
+
+fn burn_to_redeem(
+    sale: &mut TestSale,
+    old_collection: ResourceAddress,
+    count: u64,
+) -> TransactionReceipt {
+    let (account, component) = (sale.account, sale.component);
+    sale.as_public(|builder| {
+        builder
+            .withdraw_from_account(account, old_collection, count)
+            .take_all_from_worktop(old_collection, "old")
+            .with_bucket("old", |builder, bucket| {
+                builder.call_method(component, "burn_to_redeem", manifest_args!(bucket))
+            })
+    })
+}
+
+#[test]
+fn old_nfts_are_burned_at_the_configured_rate() {
+    let (mut sale, old_collection) = sale_with_old_collection(4);
+    let now = sale.ledger.get_current_epoch();
+    set_upgrade_path(
+        &mut sale,
+        Some(UpgradePath {
+            old_collection,
+            burned_per_nft: 2,
+            opens_at: now,
+            closes_at: now.after(10).unwrap(),
+        }),
+    )
+    .expect_commit_success();
+
+    burn_to_redeem(&mut sale, old_collection, 3).expect_commit_failure();
+    burn_to_redeem(&mut sale, old_collection, 4).expect_commit_success();
+
+    let (account, nft_resource) = (sale.account, sale.nft_resource);
+    assert_eq!(sale.balance(account, old_collection), dec!(0));
+    assert_eq!(sale.balance(account, nft_resource), dec!(2));
+}
+
+#[test]
+fn upgrades_are_only_accepted_inside_the_window() {
+    let (mut sale, old_collection) = sale_with_old_collection(2);
+    burn_to_redeem(&mut sale, old_collection, 1).expect_commit_failure();
+
+    let now = sale.ledger.get_current_epoch();
+    set_upgrade_path(
+        &mut sale,
+        Some(UpgradePath {
+            old_collection,
+            burned_per_nft: 1,
+            opens_at: now.after(5).unwrap(),
+            closes_at: now.after(10).unwrap(),
+        }),
+    )
+    .expect_commit_success();
+    burn_to_redeem(&mut sale, old_collection, 1).expect_commit_failure();
+
+    sale.ledger.set_current_epoch(now.after(5).unwrap());
+    burn_to_redeem(&mut sale, old_collection, 1).expect_commit_success();
+
+    sale.ledger.set_current_epoch(now.after(10).unwrap());
+    burn_to_redeem(&mut sale, old_collection, 1).expect_commit_failure();
+}
@@ -0,0 +1,55 @@
+mod common;
+
+use common::TestSale;
+use scrypto_test::prelude::*;
+
+fn buy_vouchers(sale: &mut TestSale, amount: Decimal) -> TransactionReceipt {
+    let (account, component, payment_token) = (sale.account, sale.component, sale.payment_token);
+    sale.as_public(|builder| {
+        builder
+            .withdraw_from_account(account, payment_token, amount)
+            .take_all_from_worktop(payment_token, "payment")
+            .with_bucket("payment", |builder, bucket| {
+                builder.call_method(component, "buy_vouchers", manifest_args!(bucket))
+            })
+    })
+}
+
+#[test]
+fn vouchers_are_sold_at_face_value_and_burned_on_redemption() {
+    let mut sale = TestSale::new(5, 5, dec!(10));
+    sale.start_sale().expect_commit_success();
+    let (account, voucher, nft_resource) = (sale.account, sale.voucher, sale.nft_resource);
+
+    buy_vouchers(&mut sale, dec!(25)).expect_commit_success();
+    assert_eq!(sale.balance(account, voucher), dec!(25));
+
+    sale.buy_with(voucher, dec!(25), 2).expect_commit_success();
+    assert_eq!(sale.balance(account, voucher), dec!(5));
+    assert_eq!(sale.balance(account, nft_resource), dec!(2));
+    assert_eq!(
+        sale.ledger.get_fungible_resource_total_supply(voucher),
+        dec!(5),
+        "redeemed vouchers are burned"
+    );
+
+    // Profits were collected when the vouchers were sold, not again when redeemed
+    let payment_token = sale.payment_token;
+    let component = sale.component;
+    assert_eq!(sale.balance(component, payment_token), dec!(25));
+}
+
+#[test]
+fn vouchers_can_only_be_bought_with_the_payment_token() {
+    let mut sale = TestSale::new(1, 1, dec!(10));
+    let (account, component, nft_resource) = (sale.account, sale.component, sale.nft_resource);
+    let receipt = sale.as_public(|builder| {
+        builder
+            .withdraw_from_account(account, nft_resource, 1)
+            .take_all_from_worktop(nft_resource, "payment")
+            .with_bucket("payment", |builder, bucket| {
+                builder.call_method(component, "buy_vouchers", manifest_args!(bucket))
+            })
+    });
+    receipt.expect_commit_failure();
+}